@@ -15,6 +15,7 @@
 //! | Empty directory| `Empty`             | Not in mount table (leftover dir)           |
 //! | Active mount   | `Active`            | In mount table, accessible, has container   |
 //! | Orphaned mount | `Orphaned`          | In mount table, accessible, no container    |
+//! | Stopped        | `Stopped`           | Mounted, accessible, stopped container      |
 //!
 //! The split is necessary because `dcx clean` should skip the unmount step when there is
 //! no mount table entry (`Empty`), whereas it should attempt unmount for `Stale`. Without
@@ -25,8 +26,10 @@
 pub enum MountStatus {
     /// Healthy bindfs mount with a running container.
     Active,
-    /// Healthy bindfs mount but no running container.
+    /// Healthy bindfs mount but no container at all.
     Orphaned,
+    /// Healthy bindfs mount with a stopped container (paused by `dcx stop`).
+    Stopped,
     /// Mount entry exists in mount table but is inaccessible (FUSE process died, etc.).
     Stale,
     /// No bindfs mount found; just a leftover directory.
@@ -38,6 +41,7 @@ pub enum MountStatus {
 /// - `is_fuse_mounted`: the target appears in the mount table as a bindfs entry
 /// - `is_accessible`: stat/ls of the mount point succeeds
 /// - `has_container`: a running container is associated with this mount
+/// - `has_stopped_container`: a stopped container is associated with this mount
 ///
 /// # Design note — Stale vs Empty
 ///
@@ -56,7 +60,12 @@ pub enum MountStatus {
 ///
 /// Note: inputs where `!is_fuse_mounted && has_container` are logically impossible in
 /// practice but are still handled deterministically (→ `Empty`).
-pub fn categorize(is_fuse_mounted: bool, is_accessible: bool, has_container: bool) -> MountStatus {
+pub fn categorize(
+    is_fuse_mounted: bool,
+    is_accessible: bool,
+    has_container: bool,
+    has_stopped_container: bool,
+) -> MountStatus {
     if !is_fuse_mounted {
         return MountStatus::Empty;
     }
@@ -65,6 +74,8 @@ pub fn categorize(is_fuse_mounted: bool, is_accessible: bool, has_container: boo
     }
     if has_container {
         MountStatus::Active
+    } else if has_stopped_container {
+        MountStatus::Stopped
     } else {
         MountStatus::Orphaned
     }
@@ -76,31 +87,38 @@ mod tests {
 
     #[test]
     fn active_when_mounted_accessible_with_container() {
-        assert_eq!(categorize(true, true, true), MountStatus::Active);
+        assert_eq!(categorize(true, true, true, false), MountStatus::Active);
     }
 
     #[test]
     fn orphaned_when_mounted_accessible_no_container() {
-        assert_eq!(categorize(true, true, false), MountStatus::Orphaned);
+        assert_eq!(categorize(true, true, false, false), MountStatus::Orphaned);
+    }
+
+    #[test]
+    fn stopped_when_mounted_accessible_with_stopped_container() {
+        assert_eq!(categorize(true, true, false, true), MountStatus::Stopped);
+        assert_eq!(categorize(true, true, true, true), MountStatus::Active);
+        assert_eq!(categorize(true, false, false, true), MountStatus::Stale);
     }
 
     #[test]
     fn stale_when_mounted_but_inaccessible() {
-        assert_eq!(categorize(true, false, false), MountStatus::Stale);
+        assert_eq!(categorize(true, false, false, false), MountStatus::Stale);
     }
 
     #[test]
     fn stale_when_mounted_inaccessible_even_with_container_flag() {
-        assert_eq!(categorize(true, false, true), MountStatus::Stale);
+        assert_eq!(categorize(true, false, true, false), MountStatus::Stale);
     }
 
     #[test]
     fn empty_when_not_fuse_mounted() {
-        assert_eq!(categorize(false, true, false), MountStatus::Empty);
+        assert_eq!(categorize(false, true, false, false), MountStatus::Empty);
     }
 
     #[test]
     fn empty_when_nothing_present() {
-        assert_eq!(categorize(false, false, false), MountStatus::Empty);
+        assert_eq!(categorize(false, false, false, false), MountStatus::Empty);
     }
 }
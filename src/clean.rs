@@ -5,14 +5,19 @@ use std::path::{Path, PathBuf};
 use std::sync::atomic::Ordering;
 
 use crate::cmd;
+use crate::daemon;
 use crate::docker;
+use crate::duration::parse_duration;
 use crate::exit_codes;
 use crate::format::{self, CleanEntry};
+use crate::jobs;
 use crate::mount_table;
-use crate::naming::{mount_name, relay_dir, scan_relay};
+use crate::naming::{mount_name, relay_dir, scan_orphaned_staging, scan_relay};
+use crate::output;
 use crate::platform;
 use crate::progress;
 use crate::signals;
+use crate::state;
 use crate::up::staging_dir;
 use crate::workspace::resolve_workspace;
 
@@ -26,7 +31,7 @@ struct CleanPlan {
     mount_point: PathBuf,
     /// Mount name (e.g. dcx-myproject-a1b2c3d4)
     mount_name: String,
-    /// State before cleaning: "running", "orphaned", "stale", or "empty dir"
+    /// State before cleaning: "running", "stopped", "orphaned", "stale", or "empty dir"
     state: String,
     /// Container IDs if any exist (populated during scan)
     container_ids: Vec<String>,
@@ -40,8 +45,106 @@ struct CleanPlan {
     is_mounted: bool,
 }
 
+/// `--state` values for `dcx clean`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum StateFilter {
+    /// Mounted, but no container exists
+    Orphaned,
+    /// Mount is dead (in the mount table but inaccessible) or just a leftover directory
+    Stale,
+    /// Containers exist but none is running
+    Exited,
+    /// Mounted with a stopped container (paused by `dcx stop`)
+    Stopped,
+}
+
+/// Raw `--older-than`, `--idle-for` and `--state` values, as given on the command line.
+#[derive(Clone, Debug, Default)]
+pub struct CleanFilterArgs {
+    pub older_than: Option<String>,
+    pub idle_for: Option<String>,
+    pub states: Vec<StateFilter>,
+}
+
+/// Parsed selection criteria. A workspace is selected only if it matches all of them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CleanFilter {
+    /// Minimum container age in seconds.
+    pub older_than: Option<u64>,
+    /// Minimum time since the last `dcx up`/`dcx exec`, in seconds.
+    pub idle_for: Option<u64>,
+    /// Accepted states (any of them); empty accepts every state.
+    pub states: Vec<StateFilter>,
+}
+
+impl CleanFilter {
+    /// Parse the command-line values. Errors name the offending flag.
+    pub fn parse(args: &CleanFilterArgs) -> Result<Self, String> {
+        let duration = |flag: &str, value: &Option<String>| {
+            value
+                .as_deref()
+                .map(|v| parse_duration(v).map_err(|e| format!("--{flag}: {e}")))
+                .transpose()
+        };
+        Ok(Self {
+            older_than: duration("older-than", &args.older_than)?,
+            idle_for: duration("idle-for", &args.idle_for)?,
+            states: args.states.clone(),
+        })
+    }
+
+    /// True when no criterion was given (plain `dcx clean` / `dcx clean --all`).
+    pub fn is_empty(&self) -> bool {
+        self.older_than.is_none() && self.idle_for.is_none() && self.states.is_empty()
+    }
+}
+
+/// What a `CleanFilter` is matched against for one workspace.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorkspaceFacts {
+    /// State from `categorize_mount_state`.
+    pub state: String,
+    /// Whether any container (running or stopped) exists.
+    pub has_container: bool,
+    /// Whether a container is running.
+    pub running: bool,
+    /// When the container was created.
+    pub created: Option<u64>,
+    /// Last `dcx up`/`dcx exec`, or the container's last start if later.
+    pub last_active: Option<u64>,
+    /// A `dcx exec`/`dcx shell` session or detached job is running right now.
+    pub in_use: bool,
+}
+
 // ── Pure functions ─────────────────────────────────────────────────────────────
 
+/// Decide whether a workspace is selected by `filter`.
+///
+/// - `--older-than` needs a container: without one there is no age to compare.
+/// - `--idle-for` uses the last activity, falling back to the creation time; a workspace
+///   with neither (no container and never used) counts as idle. A workspace in use (live
+///   session or running job) is never idle, however long ago that session started.
+pub fn matches_filter(filter: &CleanFilter, facts: &WorkspaceFacts, now: u64) -> bool {
+    let state_ok = filter.states.is_empty()
+        || filter.states.iter().any(|s| match s {
+            StateFilter::Orphaned => facts.state == "orphaned",
+            StateFilter::Stale => facts.state == "stale" || facts.state == "empty dir",
+            StateFilter::Exited => facts.has_container && !facts.running,
+            StateFilter::Stopped => facts.state == "stopped",
+        });
+    let age_ok = filter
+        .older_than
+        .is_none_or(|min| facts.created.is_some_and(|c| now.saturating_sub(c) >= min));
+    let idle_ok = filter.idle_for.is_none_or(|min| {
+        !facts.in_use
+            && facts
+                .last_active
+                .or(facts.created)
+                .is_none_or(|t| now.saturating_sub(t) >= min)
+    });
+    state_ok && age_ok && idle_ok
+}
+
 /// Build the warning text for the confirmation prompt when stopping containers.
 ///
 /// `entries` is a list of `(workspace_display, mount_name, container_id)` tuples.
@@ -160,7 +263,8 @@ fn scan_one(mount_point: &Path, purge: bool) -> CleanPlan {
     let has_container = !container_ids.is_empty();
 
     // Determine state
-    let state = categorize_mount_state(mount_point, has_container);
+    let running = docker::query_container(mount_point).is_some();
+    let state = categorize_mount_state(mount_point, has_container, running);
 
     // Get runtime image ref if container exists (prefer -uid tag over SHA256)
     // Use first container ID if multiple exist (they should all have the same image)
@@ -200,15 +304,19 @@ fn scan_one(mount_point: &Path, purge: bool) -> CleanPlan {
 
 /// Categorize the state of a mount before cleaning.
 ///
-/// Returns a human-readable state string: "running", "orphaned", "stale", or "empty dir"
-fn categorize_mount_state(mount_point: &Path, has_container: bool) -> String {
+/// `has_container` counts stopped containers too; `running` says whether one is running.
+/// Returns a human-readable state string: "running", "stopped", "orphaned", "stale", or
+/// "empty dir"
+fn categorize_mount_state(mount_point: &Path, has_container: bool, running: bool) -> String {
     let table = platform::read_mount_table().unwrap_or_default();
     let is_in_mount_table = mount_table::find_mount_source(&table, mount_point).is_some();
     let is_accessible = mount_point.exists();
 
     if is_in_mount_table && is_accessible {
-        if has_container {
+        if has_container && running {
             "running".to_string()
+        } else if has_container {
+            "stopped".to_string()
         } else {
             "orphaned".to_string()
         }
@@ -228,14 +336,16 @@ fn categorize_mount_state(mount_point: &Path, has_container: bool) -> String {
 /// `container_ids` is a slice of container IDs (may be empty); if empty, container/image removal is skipped.
 /// `purge`: if true, also removes the `dcx-base:<mount_name>` tag and Docker volumes.
 /// Returns a tuple of (state_before_cleaning, action_taken).
-fn clean_one(
+pub fn clean_one(
+    home: &Path,
     mount_point: &Path,
     container_ids: &[String],
     purge: bool,
 ) -> Result<(String, String), String> {
     // Determine state before cleanup
     let has_container = !container_ids.is_empty();
-    let state_before = categorize_mount_state(mount_point, has_container);
+    let running = docker::query_container(mount_point).is_some();
+    let state_before = categorize_mount_state(mount_point, has_container, running);
 
     let mount_name = mount_point
         .file_name()
@@ -276,19 +386,9 @@ fn clean_one(
         eprintln!("Note: Could not remove base image tag: {e}");
     }
 
-    // Kill sync daemon if running (same pattern as dcx down).
+    // Stop syncing (same as dcx down).
+    daemon::stop_sync(home, mount_point);
     let staging = staging_dir(mount_point);
-    let pid_file = staging.join(".sync-daemon.pid");
-    if let Ok(pid_str) = std::fs::read_to_string(&pid_file) {
-        let pid = pid_str.trim();
-        if !pid.is_empty() {
-            let _ = std::process::Command::new("kill")
-                .arg("-TERM")
-                .arg(pid)
-                .status();
-            std::thread::sleep(std::time::Duration::from_millis(200));
-        }
-    }
 
     // Unmount if mounted.
     if is_mounted {
@@ -319,6 +419,214 @@ fn clean_one(
     Ok((state_before, action))
 }
 
+/// Orphaned staging directories under `relay`, with their live sync daemon (if any).
+fn scan_staging(relay: &Path) -> Vec<(PathBuf, format::StagingPlan)> {
+    scan_orphaned_staging(relay)
+        .into_iter()
+        .map(|dir| {
+            let plan = format::StagingPlan {
+                staging_dir: dir
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                daemon_pid: daemon::live_legacy_sync_pid(&dir),
+            };
+            (dir, plan)
+        })
+        .collect()
+}
+
+/// Stop the sync daemon of every orphaned staging directory and remove the directories
+/// (PID files, stale or not, go with them).
+///
+/// Returns the cleaned entries and any failures.
+fn sweep_orphaned_staging(home: &Path, relay: &Path) -> (Vec<CleanEntry>, Vec<String>) {
+    let mut cleaned = Vec::new();
+    let mut errors = Vec::new();
+    for (dir, plan) in scan_staging(relay) {
+        // `.dcx-<name>-files` belongs to mount `dcx-<name>`: stop syncing it in the daemon.
+        if let Some(name) = plan
+            .staging_dir
+            .strip_prefix('.')
+            .and_then(|n| n.strip_suffix("-files"))
+        {
+            daemon::unregister(home, name);
+        }
+        if let Some(pid) = plan.daemon_pid {
+            let _ = std::process::Command::new("kill")
+                .arg("-TERM")
+                .arg(pid.to_string())
+                .status();
+            std::thread::sleep(std::time::Duration::from_millis(200));
+        }
+        match std::fs::remove_dir_all(&dir) {
+            Ok(()) => cleaned.push(CleanEntry {
+                workspace: None,
+                mount: plan.staging_dir,
+                was: "orphaned staging".to_string(),
+                action: if plan.daemon_pid.is_some() {
+                    "daemon stopped, removed".to_string()
+                } else {
+                    "removed".to_string()
+                },
+            }),
+            Err(e) => errors.push(format!("Failed to remove {}: {e}", dir.display())),
+        }
+    }
+    (cleaned, errors)
+}
+
+/// Print the `--dry-run` preview: workspace plans, then orphaned staging directories.
+fn print_dry_run(plans: Vec<format::DryRunPlan>, staging: Vec<format::StagingPlan>, empty: &str) {
+    if output::is_structured() {
+        output::emit(&serde_json::json!({ "plans": plans, "orphaned_staging": staging }));
+        return;
+    }
+    let mut sections = Vec::new();
+    if !plans.is_empty() {
+        sections.push(format::format_dry_run(&plans));
+    }
+    if !staging.is_empty() {
+        sections.push(format::format_staging_dry_run(&staging));
+    }
+    if sections.is_empty() {
+        println!("{empty}");
+    } else {
+        println!("{}", sections.join("\n"));
+    }
+}
+
+/// Last `dcx up`/`dcx exec` in a workspace, or its containers' last start if later.
+pub fn last_activity(home: &Path, mount_name: &str, container_ids: &[String]) -> Option<u64> {
+    let started = container_ids
+        .iter()
+        .filter_map(|id| docker::container_started_at(id))
+        .max();
+    state::last_active(home, mount_name).max(started)
+}
+
+/// Gather what `matches_filter` needs for a scanned workspace.
+fn workspace_facts(home: &Path, plan: &CleanPlan) -> WorkspaceFacts {
+    WorkspaceFacts {
+        state: plan.state.clone(),
+        has_container: !plan.container_ids.is_empty(),
+        running: docker::query_container(&plan.mount_point).is_some(),
+        created: plan
+            .container_ids
+            .first()
+            .and_then(|id| docker::container_created(id)),
+        last_active: last_activity(home, &plan.mount_name, &plan.container_ids),
+        in_use: state::has_live_session(home, &plan.mount_name)
+            || jobs::has_running_job(home, &plan.mount_name),
+    }
+}
+
+/// Convert a scan result into its `--dry-run` form.
+fn to_dry_run_plan(plan: CleanPlan) -> format::DryRunPlan {
+    format::DryRunPlan {
+        mount_name: plan.mount_name,
+        state: plan.state,
+        container_ids: plan.container_ids,
+        runtime_image_id: plan.runtime_image_id,
+        has_base_image_tag: plan.has_base_image_tag,
+        volumes: plan.volumes,
+        is_mounted: plan.is_mounted,
+    }
+}
+
+/// Run `dcx clean --older-than/--idle-for/--state`: clean only the workspaces matching
+/// `filter`, across the whole relay.
+///
+/// Unlike `--all`, the global orphan sweeps are skipped: they are not scoped to a
+/// workspace and could remove resources of workspaces the filter left alone.
+fn run_clean_filtered(
+    home: &Path,
+    filter: &CleanFilter,
+    yes: bool,
+    purge: bool,
+    dry_run: bool,
+    interrupted: &std::sync::atomic::AtomicBool,
+) -> i32 {
+    let now = state::unix_now();
+    let mut selected: Vec<CleanPlan> = Vec::new();
+    let mut active_left = 0;
+    for mount_point in scan_relay(&relay_dir(home)) {
+        let plan = scan_one(&mount_point, purge);
+        let facts = workspace_facts(home, &plan);
+        if matches_filter(filter, &facts, now) {
+            selected.push(plan);
+        } else if facts.running {
+            active_left += 1;
+        }
+    }
+
+    if dry_run {
+        let plans: Vec<format::DryRunPlan> = selected.into_iter().map(to_dry_run_plan).collect();
+        if output::is_structured() {
+            output::emit(&serde_json::json!({ "plans": plans }));
+        } else {
+            println!("{}", format::format_dry_run(&plans));
+        }
+        return exit_codes::SUCCESS;
+    }
+    if selected.is_empty() {
+        println!("Nothing to clean.");
+        return exit_codes::SUCCESS;
+    }
+
+    // Matching workspaces can still be running (e.g. `--older-than` alone): confirm first.
+    let running_containers: Vec<(String, String, String)> = selected
+        .iter()
+        .filter_map(|plan| {
+            docker::query_container(&plan.mount_point)
+                .map(|id| ("(unknown)".to_string(), plan.mount_name.clone(), id))
+        })
+        .collect();
+    if !running_containers.is_empty() && !yes {
+        eprintln!("{}", confirm_prompt(&running_containers));
+        eprint!("\nContinue? [y/N] ");
+        let _ = io::stderr().flush();
+        let mut input = String::new();
+        if io::stdin().lock().read_line(&mut input).is_err() {
+            return exit_codes::RUNTIME_ERROR;
+        }
+        if !matches!(input.trim().to_ascii_lowercase().as_str(), "y" | "yes") {
+            return exit_codes::USER_ABORTED;
+        }
+    }
+
+    let mut cleaned: Vec<CleanEntry> = Vec::new();
+    let mut failures: Vec<String> = Vec::new();
+    for plan in &selected {
+        progress::step(&format!("Cleaning {}...", plan.mount_name));
+        match clean_one(home, &plan.mount_point, &plan.container_ids, purge) {
+            Ok((was, action)) => cleaned.push(CleanEntry {
+                workspace: None,
+                mount: plan.mount_name.clone(),
+                was,
+                action,
+            }),
+            Err(e) => failures.push(format!("{}: {e}", plan.mount_point.display())),
+        }
+        if interrupted.load(Ordering::Relaxed) {
+            eprintln!("Signal received, finishing current unmount...");
+            break;
+        }
+    }
+
+    if !cleaned.is_empty() {
+        println!("{}", format::format_clean_summary(&cleaned, active_left));
+    }
+    for f in &failures {
+        eprintln!("Error: {f}");
+    }
+    if failures.is_empty() {
+        exit_codes::SUCCESS
+    } else {
+        exit_codes::RUNTIME_ERROR
+    }
+}
+
 // ── Entry point ───────────────────────────────────────────────────────────────
 
 /// Run `dcx clean`.
@@ -327,6 +635,7 @@ fn clean_one(
 /// With `--all`: cleans all dcx-managed workspaces.
 /// With `--dry-run`: shows what would be cleaned without executing.
 /// With `--purge`: also removes the build image and Docker volumes.
+/// With `--older-than`/`--idle-for`/`--state`: cleans only the matching workspaces.
 ///
 /// Returns the exit code that `main` should pass to `std::process::exit`.
 pub fn run_clean(
@@ -336,7 +645,16 @@ pub fn run_clean(
     yes: bool,
     purge: bool,
     dry_run: bool,
+    filter_args: &CleanFilterArgs,
 ) -> i32 {
+    let filter = match CleanFilter::parse(filter_args) {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("{e}");
+            return exit_codes::USAGE_ERROR;
+        }
+    };
+
     // Install SIGINT handler. If Ctrl+C arrives while an unmount is in progress,
     // we finish that entry's cleanup then exit (remaining entries are skipped).
     let interrupted = signals::interrupted_flag();
@@ -350,6 +668,10 @@ pub fn run_clean(
     progress::step("Scanning relay directory...");
     let relay = relay_dir(home);
 
+    if !filter.is_empty() {
+        return run_clean_filtered(home, &filter, yes, purge, dry_run, &interrupted);
+    }
+
     // Handle --dry-run for default mode (no `--all`)
     if !all && dry_run {
         // Resolve workspace path
@@ -366,21 +688,12 @@ pub fn run_clean(
         let mount_point = relay.join(&name);
 
         let plan = scan_one(&mount_point, purge);
-        let dry_run_plan = format::DryRunPlan {
-            mount_name: plan.mount_name,
-            state: plan.state,
-            container_ids: plan.container_ids,
-            runtime_image_id: plan.runtime_image_id,
-            has_base_image_tag: plan.has_base_image_tag,
-            volumes: plan.volumes,
-            is_mounted: plan.is_mounted,
-        };
-        let output = format::format_dry_run(&[dry_run_plan]);
-        if output.trim().is_empty() {
-            println!("Nothing to clean for {}.", workspace.display());
-        } else {
-            println!("{output}");
-        }
+        let staging = scan_staging(&relay).into_iter().map(|(_, p)| p).collect();
+        print_dry_run(
+            vec![to_dry_run_plan(plan)],
+            staging,
+            &format!("Nothing to clean for {}.", workspace.display()),
+        );
         return exit_codes::SUCCESS;
     }
 
@@ -438,7 +751,7 @@ pub fn run_clean(
 
         // Clean if there's anything to do: mount exists, or purge wants base image tag
         if mount_point.exists() || purge {
-            match clean_one(&mount_point, &container_ids, purge) {
+            match clean_one(home, &mount_point, &container_ids, purge) {
                 Ok((was_state, action)) => {
                     println!("Cleaned {}:", workspace.display());
                     println!(
@@ -493,7 +806,7 @@ pub fn run_clean(
                 }
 
                 // Mounted but no container for this mount - clean it up (no purge for orphaned)
-                match clean_one(&path, &[], false) {
+                match clean_one(home, &path, &[], false) {
                     Ok((was_state, action)) => {
                         println!("  {}  was: {}  → {}", name, was_state, action);
                         cleaned_count += 1;
@@ -505,6 +818,15 @@ pub fn run_clean(
             }
         }
 
+        // Staging dirs whose mount dir is gone (mount vanished or `dcx down` failed midway).
+        progress::step("Checking for orphaned staging directories...");
+        let (staging_cleaned, staging_errors) = sweep_orphaned_staging(home, &relay);
+        for entry in &staging_cleaned {
+            println!("  {}  was: {}  → {}", entry.mount, entry.was, entry.action);
+        }
+        cleaned_count += staging_cleaned.len();
+        errors.extend(staging_errors);
+
         // Fallback: clean any vsc-dcx-* or dangling images that weren't caught above.
         // Handles the case where the container was already removed externally before dcx clean ran.
         progress::step("Checking for orphaned images...");
@@ -550,23 +872,12 @@ pub fn run_clean(
     } else {
         // Handle --dry-run for --all mode
         if dry_run {
-            let entry_paths = scan_relay(&relay);
-            let plans: Vec<format::DryRunPlan> = entry_paths
+            let plans: Vec<format::DryRunPlan> = scan_relay(&relay)
                 .iter()
-                .map(|mp| {
-                    let plan = scan_one(mp, purge);
-                    format::DryRunPlan {
-                        mount_name: plan.mount_name,
-                        state: plan.state,
-                        container_ids: plan.container_ids,
-                        runtime_image_id: plan.runtime_image_id,
-                        has_base_image_tag: plan.has_base_image_tag,
-                        volumes: plan.volumes,
-                        is_mounted: plan.is_mounted,
-                    }
-                })
+                .map(|mp| to_dry_run_plan(scan_one(mp, purge)))
                 .collect();
-            println!("{}", format::format_dry_run(&plans));
+            let staging = scan_staging(&relay).into_iter().map(|(_, p)| p).collect();
+            print_dry_run(plans, staging, "Nothing to clean.");
             return exit_codes::SUCCESS;
         }
 
@@ -617,7 +928,7 @@ pub fn run_clean(
 
             let container_ids = docker::query_container_any(mount_point);
 
-            match clean_one(mount_point, &container_ids, purge) {
+            match clean_one(home, mount_point, &container_ids, purge) {
                 Ok((was_state, action)) => {
                     cleaned.push(CleanEntry {
                         workspace: None,
@@ -638,6 +949,13 @@ pub fn run_clean(
             }
         }
 
+        // Staging dirs left behind by mounts that are gone (incl. ones removed above
+        // whose staging removal failed).
+        progress::step("Cleaning up orphaned staging directories...");
+        let (staging_cleaned, staging_errors) = sweep_orphaned_staging(home, &relay);
+        cleaned.extend(staging_cleaned);
+        failures.extend(staging_errors);
+
         // Clean up orphaned containers and images (not associated with existing mounts)
         progress::step("Cleaning up orphaned containers...");
         match docker::clean_orphaned_containers() {
@@ -774,6 +1092,210 @@ mod tests {
         assert!(out.contains("abc123"), "got: {out}");
     }
 
+    // --- CleanFilter / matches_filter ---
+
+    const DAY: u64 = 86_400;
+
+    fn facts(
+        state: &str,
+        running: bool,
+        created: Option<u64>,
+        active: Option<u64>,
+    ) -> WorkspaceFacts {
+        WorkspaceFacts {
+            state: state.to_string(),
+            has_container: created.is_some(),
+            running,
+            created,
+            last_active: active,
+            in_use: false,
+        }
+    }
+
+    #[test]
+    fn state_filter_stopped_selects_only_stopped_workspaces() {
+        let filter = CleanFilter {
+            states: vec![StateFilter::Stopped],
+            ..Default::default()
+        };
+        assert!(matches_filter(
+            &filter,
+            &facts("stopped", false, Some(1), None),
+            DAY
+        ));
+        assert!(!matches_filter(
+            &filter,
+            &facts("running", true, Some(1), None),
+            DAY
+        ));
+        assert!(!matches_filter(
+            &filter,
+            &facts("stale", false, Some(1), None),
+            DAY
+        ));
+    }
+
+    #[test]
+    fn clean_filter_parse_durations_and_reports_flag() {
+        let f = CleanFilter::parse(&CleanFilterArgs {
+            older_than: Some("7d".to_string()),
+            idle_for: Some("2d".to_string()),
+            states: vec![StateFilter::Exited],
+        })
+        .unwrap();
+        assert_eq!(f.older_than, Some(7 * DAY));
+        assert_eq!(f.idle_for, Some(2 * DAY));
+        assert!(!f.is_empty());
+        assert!(
+            CleanFilter::parse(&CleanFilterArgs::default())
+                .unwrap()
+                .is_empty()
+        );
+
+        let err = CleanFilter::parse(&CleanFilterArgs {
+            idle_for: Some("soon".to_string()),
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert!(err.starts_with("--idle-for"), "got: {err}");
+    }
+
+    #[test]
+    fn matches_filter_older_than_needs_a_container() {
+        let now = 100 * DAY;
+        let filter = CleanFilter {
+            older_than: Some(7 * DAY),
+            ..Default::default()
+        };
+        assert!(matches_filter(
+            &filter,
+            &facts("running", true, Some(now - 8 * DAY), None),
+            now
+        ));
+        assert!(!matches_filter(
+            &filter,
+            &facts("running", true, Some(now - DAY), None),
+            now
+        ));
+        assert!(!matches_filter(
+            &filter,
+            &facts("orphaned", false, None, None),
+            now
+        ));
+    }
+
+    #[test]
+    fn matches_filter_idle_for_uses_last_activity() {
+        let now = 100 * DAY;
+        let filter = CleanFilter {
+            idle_for: Some(2 * DAY),
+            ..Default::default()
+        };
+        // Old container, but used an hour ago: kept.
+        let recent = facts("running", true, Some(now - 30 * DAY), Some(now - 3600));
+        assert!(!matches_filter(&filter, &recent, now));
+        // No recorded activity: falls back to creation time.
+        assert!(matches_filter(
+            &filter,
+            &facts("running", true, Some(now - 3 * DAY), None),
+            now
+        ));
+        // Nothing known at all counts as idle.
+        assert!(matches_filter(
+            &filter,
+            &facts("stale", false, None, None),
+            now
+        ));
+        // A session opened three days ago and still running: in use, not idle.
+        let open_session = WorkspaceFacts {
+            in_use: true,
+            ..facts("running", true, Some(now - 30 * DAY), Some(now - 3 * DAY))
+        };
+        assert!(!matches_filter(&filter, &open_session, now));
+    }
+
+    #[test]
+    fn matches_filter_states_are_or_and_criteria_are_and() {
+        let now = 100 * DAY;
+        let states = CleanFilter {
+            states: vec![StateFilter::Orphaned, StateFilter::Exited],
+            ..Default::default()
+        };
+        assert!(matches_filter(
+            &states,
+            &facts("orphaned", false, None, None),
+            now
+        ));
+        assert!(matches_filter(
+            &states,
+            &facts("running", false, Some(now), None),
+            now
+        ));
+        assert!(!matches_filter(
+            &states,
+            &facts("running", true, Some(now), None),
+            now
+        ));
+        assert!(!matches_filter(
+            &states,
+            &facts("empty dir", false, None, None),
+            now
+        ));
+
+        let stale = CleanFilter {
+            states: vec![StateFilter::Stale],
+            ..Default::default()
+        };
+        assert!(matches_filter(
+            &stale,
+            &facts("stale", false, None, None),
+            now
+        ));
+        assert!(matches_filter(
+            &stale,
+            &facts("empty dir", false, None, None),
+            now
+        ));
+
+        let exited_and_old = CleanFilter {
+            older_than: Some(7 * DAY),
+            states: vec![StateFilter::Exited],
+            ..Default::default()
+        };
+        assert!(!matches_filter(
+            &exited_and_old,
+            &facts("running", false, Some(now - DAY), None),
+            now
+        ));
+        assert!(matches_filter(
+            &exited_and_old,
+            &facts("running", false, Some(now - 8 * DAY), None),
+            now
+        ));
+    }
+
+    // --- orphaned staging ---
+
+    #[test]
+    fn sweep_orphaned_staging_removes_only_dirs_without_a_mount() {
+        let relay = tempfile::tempdir().unwrap();
+        let live = relay.path().join(".dcx-live-a1b2c3d4-files");
+        let orphan = relay.path().join(".dcx-gone-e5f6g7h8-files");
+        std::fs::create_dir(relay.path().join("dcx-live-a1b2c3d4")).unwrap();
+        std::fs::create_dir(&live).unwrap();
+        std::fs::create_dir(&orphan).unwrap();
+        std::fs::write(orphan.join(".claude.json"), "{}").unwrap();
+        std::fs::write(orphan.join(".sync-daemon.pid"), "999999999").unwrap();
+
+        let (cleaned, errors) = sweep_orphaned_staging(relay.path(), relay.path());
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(cleaned.len(), 1);
+        assert_eq!(cleaned[0].mount, ".dcx-gone-e5f6g7h8-files");
+        assert_eq!(cleaned[0].action, "removed");
+        assert!(!orphan.exists());
+        assert!(live.exists());
+    }
+
     // --- categorize_mount_state ---
 
     #[test]
@@ -782,6 +1304,7 @@ mod tests {
         let state = categorize_mount_state(
             Path::new("/tmp/dcx-test-nonexistent-categorize-99999999"),
             false,
+            false,
         );
         assert_eq!(state, "empty dir");
     }
@@ -790,8 +1313,11 @@ mod tests {
     fn categorize_mount_state_existing_unmounted_dir_is_empty_dir() {
         let dir = tempfile::tempdir().unwrap();
         // Directory exists on filesystem but is not in the mount table.
-        assert_eq!(categorize_mount_state(dir.path(), false), "empty dir");
-        assert_eq!(categorize_mount_state(dir.path(), true), "empty dir");
+        assert_eq!(
+            categorize_mount_state(dir.path(), false, false),
+            "empty dir"
+        );
+        assert_eq!(categorize_mount_state(dir.path(), true, true), "empty dir");
     }
 
     // --- confirm_prompt: zero-entry case ---
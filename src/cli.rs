@@ -1,7 +1,10 @@
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
+use crate::clean::StateFilter;
 use crate::network_mode::NetworkMode;
+use crate::output::OutputFormat;
+use crate::sync::SyncSide;
 
 #[derive(Parser)]
 #[command(
@@ -9,10 +12,15 @@ use crate::network_mode::NetworkMode;
     version,
     about = "Dynamic workspace mounting wrapper for Colima devcontainers",
     long_about = "dcx wraps `devcontainer` to manage bindfs mounts for Colima.\n\n\
-                  Managed subcommands: up, exec, down, logs, clean, status, doctor\n\
+                  Managed subcommands: up, exec, shell, run, jobs, sessions, down, stop, start, sync, daemon, logs, clean, gc, inspect, status, df, doctor\n\
                   All other subcommands are forwarded to `devcontainer` unchanged."
 )]
 pub struct Cli {
+    /// Output format: text, json (one result document) or jsonl (progress events, then the result).
+    /// Supported by status, inspect, df, doctor, clean --dry-run, up --dry-run and down
+    #[arg(long, global = true, value_name = "FORMAT", default_value = "text")]
+    pub output: OutputFormat,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -54,6 +62,10 @@ pub enum Commands {
         /// Build the container image without using Docker cache
         #[arg(long)]
         no_cache: bool,
+
+        /// Bring the workspace down automatically after this long (e.g. 8h)
+        #[arg(long, value_name = "DURATION")]
+        ttl: Option<String>,
     },
 
     /// Run a command inside the devcontainer
@@ -66,6 +78,82 @@ pub enum Commands {
         #[arg(long, value_name = "DIR")]
         config_dir: Option<PathBuf>,
 
+        /// User to run as inside the container (default: remoteUser from devcontainer.json)
+        #[arg(long, short = 'u', value_name = "USER")]
+        user: Option<String>,
+
+        /// Set an environment variable inside the container (may be repeated)
+        #[arg(long = "env", short = 'e', value_name = "KEY=VAL")]
+        env: Vec<String>,
+
+        /// Read environment variables from a file (may be repeated)
+        #[arg(long = "env-file", value_name = "PATH")]
+        env_files: Vec<PathBuf>,
+
+        /// Working directory inside the container; relative paths are resolved against
+        /// the container workspace folder (default: matches the current directory)
+        #[arg(long, short = 'w', value_name = "PATH")]
+        workdir: Option<String>,
+
+        /// Record the interactive session as an asciicast transcript (see `dcx sessions`)
+        #[arg(long, conflicts_with = "all")]
+        record: bool,
+
+        /// Run the command in every active workspace (output prefixed with its name)
+        #[arg(long, conflicts_with_all = ["workspace_folder", "config_dir"])]
+        all: bool,
+
+        /// With --all: number of workspaces to run in at once (default: 1)
+        #[arg(long, value_name = "N", requires = "all", value_parser = clap::value_parser!(u16).range(1..))]
+        parallel: Option<u16>,
+
+        /// Command and arguments to run inside the container
+        #[arg(
+            trailing_var_arg = true,
+            allow_hyphen_values = true,
+            value_name = "CMD"
+        )]
+        command: Vec<String>,
+    },
+
+    /// Open an interactive shell in the devcontainer, bringing it up if needed
+    Shell {
+        /// Workspace folder path (default: current directory)
+        #[arg(long, value_name = "PATH")]
+        workspace_folder: Option<PathBuf>,
+    },
+
+    /// Run a command inside the devcontainer, optionally detached as a background job
+    Run {
+        /// Workspace folder path (default: current directory)
+        #[arg(long, value_name = "PATH")]
+        workspace_folder: Option<PathBuf>,
+
+        /// Directory containing devcontainer.json (default: auto-detected)
+        #[arg(long, value_name = "DIR")]
+        config_dir: Option<PathBuf>,
+
+        /// User to run as inside the container (default: remoteUser from devcontainer.json)
+        #[arg(long, short = 'u', value_name = "USER")]
+        user: Option<String>,
+
+        /// Set an environment variable inside the container (may be repeated)
+        #[arg(long = "env", short = 'e', value_name = "KEY=VAL")]
+        env: Vec<String>,
+
+        /// Read environment variables from a file (may be repeated)
+        #[arg(long = "env-file", value_name = "PATH")]
+        env_files: Vec<PathBuf>,
+
+        /// Working directory inside the container; relative paths are resolved against
+        /// the container workspace folder (default: matches the current directory)
+        #[arg(long, short = 'w', value_name = "PATH")]
+        workdir: Option<String>,
+
+        /// Run in the background and print a job ID (see `dcx jobs`)
+        #[arg(long, short = 'd')]
+        detach: bool,
+
         /// Command and arguments to run inside the container
         #[arg(
             trailing_var_arg = true,
@@ -75,11 +163,65 @@ pub enum Commands {
         command: Vec<String>,
     },
 
+    /// List background jobs started with `dcx run --detach`
+    Jobs {
+        #[command(subcommand)]
+        action: Option<JobsAction>,
+    },
+
+    /// List and replay recorded exec sessions
+    Sessions {
+        #[command(subcommand)]
+        action: SessionsAction,
+    },
+
     /// Stop container and unmount workspace
     Down {
         /// Workspace folder path (default: current directory)
         #[arg(long, value_name = "PATH")]
         workspace_folder: Option<PathBuf>,
+
+        /// Workspaces to take down (instead of --workspace-folder)
+        #[arg(value_name = "WORKSPACE", conflicts_with_all = ["workspace_folder", "all"])]
+        workspaces: Vec<PathBuf>,
+
+        /// Take down every active workspace (images and volumes are kept)
+        #[arg(long, conflicts_with = "workspace_folder")]
+        all: bool,
+
+        /// Skip the confirmation prompt for running containers (--all or several workspaces)
+        #[arg(long)]
+        yes: bool,
+    },
+
+    /// Stop the container but keep it and the mount, for a fast `dcx start`
+    Stop {
+        /// Workspace folder path (default: current directory)
+        #[arg(long, value_name = "PATH")]
+        workspace_folder: Option<PathBuf>,
+
+        /// Also stop file sync for the workspace (resumed by `dcx start`)
+        #[arg(long)]
+        daemon: bool,
+    },
+
+    /// Start a workspace paused by `dcx stop`, without running `devcontainer up`
+    Start {
+        /// Workspace folder path (default: current directory)
+        #[arg(long, value_name = "PATH")]
+        workspace_folder: Option<PathBuf>,
+    },
+
+    /// Manage file sync between host and container
+    Sync {
+        #[command(subcommand)]
+        action: SyncAction,
+    },
+
+    /// Inspect or restart the per-user dcx daemon that syncs files for all workspaces
+    Daemon {
+        #[command(subcommand)]
+        action: DaemonAction,
     },
 
     /// View or stream logs from the container for a workspace
@@ -104,6 +246,26 @@ pub enum Commands {
         /// Number of lines to show from the end of the logs (e.g. 20, all)
         #[arg(long, value_name = "VALUE")]
         tail: Option<String>,
+
+        /// Show the sync daemon log instead of container logs (supports -f and --tail)
+        #[arg(long, conflicts_with_all = ["since", "until"])]
+        daemon: bool,
+
+        /// Merge logs from every dcx container, prefixed with the workspace name
+        #[arg(long, conflicts_with_all = ["workspace_folder", "daemon"])]
+        all: bool,
+
+        /// Also include sync daemon and dcx lifecycle events (up, down, network phases)
+        #[arg(long, conflicts_with = "daemon")]
+        events: bool,
+
+        /// Show the output of the latest `dcx up` run instead of container logs
+        #[arg(long, conflicts_with_all = ["follow", "since", "until", "daemon", "all", "events"])]
+        up: bool,
+
+        /// With --up: show this run instead of the latest (run IDs are listed on error)
+        #[arg(long, value_name = "ID", requires = "up")]
+        run: Option<String>,
     },
 
     /// Clean up dcx-managed mounts
@@ -127,10 +289,66 @@ pub enum Commands {
         /// Show what would be cleaned without doing it
         #[arg(long)]
         dry_run: bool,
+
+        /// Only clean workspaces whose container is older than this (e.g. 7d); implies --all
+        #[arg(long, value_name = "DURATION", conflicts_with = "workspace_folder")]
+        older_than: Option<String>,
+
+        /// Only clean workspaces with no `dcx up`/`dcx exec` for this long (e.g. 2d); implies --all
+        #[arg(long, value_name = "DURATION", conflicts_with = "workspace_folder")]
+        idle_for: Option<String>,
+
+        /// Only clean workspaces in these states (repeatable or comma-separated); implies --all
+        #[arg(
+            long,
+            value_name = "STATE",
+            value_enum,
+            value_delimiter = ',',
+            conflicts_with = "workspace_folder"
+        )]
+        state: Vec<StateFilter>,
+    },
+
+    /// Apply the garbage collection policy (`gc:` in dcx_config.yaml)
+    Gc {
+        /// Workspace folder whose dcx_config.yaml holds the policy (default: current directory)
+        #[arg(long, value_name = "PATH")]
+        workspace_folder: Option<PathBuf>,
+
+        /// Maximum total size of dcx images (e.g. 20GB); overrides gc.max_image_size
+        #[arg(long, value_name = "SIZE")]
+        max_image_size: Option<String>,
+
+        /// Maximum number of workspaces without a running container; overrides gc.max_idle_workspaces
+        #[arg(long, value_name = "N")]
+        max_idle: Option<usize>,
+
+        /// Always keep the N most recently used workspaces; overrides gc.keep
+        #[arg(long, value_name = "N")]
+        keep: Option<usize>,
+
+        /// Show what would be collected without doing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Show everything dcx knows about one workspace
+    Inspect {
+        /// Workspace folder path (default: current directory)
+        #[arg(long, value_name = "PATH")]
+        workspace_folder: Option<PathBuf>,
     },
 
     /// Show status of all dcx-managed workspaces
-    Status,
+    Status {
+        /// Add CPU, memory, PIDs, network I/O and writable-layer size per container,
+        /// plus total disk used by dcx images and volumes
+        #[arg(long)]
+        resources: bool,
+    },
+
+    /// Show disk usage of dcx images, volumes and staging per workspace
+    Df,
 
     /// Validate prerequisites (bindfs, devcontainer, Docker, Colima)
     Doctor,
@@ -164,20 +382,28 @@ pub enum Commands {
         shell: clap_complete::Shell,
     },
 
-    /// Internal sync daemon (not for direct user invocation)
-    #[command(name = "_sync-daemon", hide = true)]
-    SyncDaemon {
-        /// Source (host) file paths (repeatable, paired with --staging)
-        #[arg(long = "source", required = true)]
-        sources: Vec<PathBuf>,
+    /// Internal per-user daemon that syncs files for all workspaces (not for direct user invocation)
+    #[command(name = "_daemon", hide = true)]
+    DaemonRun,
+
+    /// Internal workspace supervisor for TTL and idle timeout (not for direct user invocation)
+    #[command(name = "_supervisor", hide = true)]
+    Supervisor {
+        /// Relay mount point of the workspace
+        #[arg(long = "mount-point", required = true)]
+        mount_point: PathBuf,
 
-        /// Staging file paths (repeatable, paired with --source)
-        #[arg(long = "staging", required = true)]
-        stagings: Vec<PathBuf>,
+        /// Workspace folder, passed to `dcx down` on expiry
+        #[arg(long, required = true)]
+        workspace: PathBuf,
+    },
 
-        /// PID file path
-        #[arg(long = "pid-file", required = true)]
-        pid_file: PathBuf,
+    /// Internal job runner (not for direct user invocation)
+    #[command(name = "_job-runner", hide = true)]
+    JobRunner {
+        /// Job directory holding meta.json
+        #[arg(long = "job-dir", required = true)]
+        job_dir: PathBuf,
     },
 
     /// Forward to devcontainer CLI (any unrecognised subcommand)
@@ -185,6 +411,51 @@ pub enum Commands {
     External(Vec<String>),
 }
 
+impl Commands {
+    /// Subcommand name as typed on the command line (used in `--output` documents).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Commands::Up { .. } => "up",
+            Commands::Exec { .. } => "exec",
+            Commands::Shell { .. } => "shell",
+            Commands::Run { .. } => "run",
+            Commands::Jobs { .. } => "jobs",
+            Commands::Sessions { .. } => "sessions",
+            Commands::Down { .. } => "down",
+            Commands::Stop { .. } => "stop",
+            Commands::Start { .. } => "start",
+            Commands::Sync { .. } => "sync",
+            Commands::Daemon { .. } => "daemon",
+            Commands::Logs { .. } => "logs",
+            Commands::Clean { .. } => "clean",
+            Commands::Gc { .. } => "gc",
+            Commands::Inspect { .. } => "inspect",
+            Commands::Status { .. } => "status",
+            Commands::Df => "df",
+            Commands::Doctor => "doctor",
+            Commands::Autostart { .. } => "autostart",
+            Commands::Completions { .. } => "completions",
+            Commands::DaemonRun => "_daemon",
+            Commands::JobRunner { .. } => "_job-runner",
+            Commands::Supervisor { .. } => "_supervisor",
+            Commands::External(_) => "devcontainer",
+        }
+    }
+
+    /// Whether this invocation supports `--output json|jsonl`.
+    pub fn supports_structured_output(&self) -> bool {
+        match self {
+            Commands::Up { dry_run, .. } | Commands::Clean { dry_run, .. } => *dry_run,
+            Commands::Status { .. }
+            | Commands::Inspect { .. }
+            | Commands::Df
+            | Commands::Doctor
+            | Commands::Down { .. } => true,
+            _ => false,
+        }
+    }
+}
+
 #[derive(Subcommand)]
 pub enum AutostartAction {
     /// Configure Colima to start on boot and start it now if not running
@@ -194,3 +465,62 @@ pub enum AutostartAction {
     /// Show current autostart status
     Status,
 }
+
+#[derive(Subcommand)]
+pub enum JobsAction {
+    /// Print a job's output
+    Logs {
+        /// Job ID
+        id: String,
+
+        /// Keep streaming output until the job finishes
+        #[arg(long, short = 'f')]
+        follow: bool,
+    },
+    /// Stop a running job (sends SIGTERM inside the container)
+    Kill {
+        /// Job ID
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SessionsAction {
+    /// List recorded sessions across all workspaces
+    List,
+    /// Play a recorded session back in the terminal
+    Replay {
+        /// Session ID (from `dcx sessions list`)
+        id: String,
+
+        /// Playback speed multiplier (e.g. 2 for double speed)
+        #[arg(long, value_name = "N", default_value_t = 1.0)]
+        speed: f64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SyncAction {
+    /// Resolve sync conflicts by keeping the host or the container version
+    Resolve {
+        /// Workspace folder path (default: current directory)
+        #[arg(long, value_name = "PATH")]
+        workspace_folder: Option<PathBuf>,
+
+        /// Version to keep
+        #[arg(long, value_name = "SIDE")]
+        take: SyncSide,
+
+        /// Only resolve the conflict for this host file (default: all conflicts)
+        #[arg(value_name = "FILE")]
+        file: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DaemonAction {
+    /// Show whether the daemon is running and which workspaces it syncs
+    Status,
+    /// Restart the daemon; registered workspaces are resumed
+    Restart,
+}
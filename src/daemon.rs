@@ -0,0 +1,779 @@
+#![allow(dead_code)]
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::duration::format_duration;
+use crate::exit_codes;
+use crate::format::{DaemonRow, format_daemon_table};
+use crate::state;
+use crate::sync::{self, DaemonLog};
+
+/// How long a client waits for the daemon to answer, or to come up after being spawned.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The daemon exits after this long with no registered workspace and no requests.
+const IDLE_EXIT: Duration = Duration::from_secs(60);
+
+// ── Paths ─────────────────────────────────────────────────────────────────────
+
+/// Control socket: `~/.local/state/dcx/daemon.sock`.
+pub fn socket_path(home: &Path) -> PathBuf {
+    state::state_root(home).join("daemon.sock")
+}
+
+/// Daemon-level log (start, stop, registrations). Per-workspace sync activity goes to
+/// each workspace's `sync-daemon.log`.
+pub fn log_file(home: &Path) -> PathBuf {
+    state::state_root(home).join("daemon.log")
+}
+
+/// Held (`flock`) by the running daemon for its whole life: only the holder may create or
+/// remove the control socket.
+pub fn lock_file(home: &Path) -> PathBuf {
+    state::state_root(home).join("daemon.lock")
+}
+
+/// Registered workspaces, one mount name per line. Survives daemon restarts.
+pub fn registry_file(home: &Path) -> PathBuf {
+    state::state_root(home).join("daemon-workspaces")
+}
+
+// ── Protocol ──────────────────────────────────────────────────────────────────
+
+/// A control request: one JSON object per line on the socket.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "request", rename_all = "snake_case")]
+pub enum Request {
+    /// Start (or restart) syncing the pairs recorded for a workspace by `dcx up`.
+    Register {
+        workspace: String,
+    },
+    /// Stop syncing a workspace.
+    Unregister {
+        workspace: String,
+    },
+    Health,
+    Shutdown,
+}
+
+/// The answer to a `Request`: one JSON object per line.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Response {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<Health>,
+}
+
+impl Response {
+    fn ok() -> Self {
+        Self {
+            ok: true,
+            ..Self::default()
+        }
+    }
+
+    fn error(message: String) -> Self {
+        Self {
+            ok: false,
+            error: Some(message),
+            health: None,
+        }
+    }
+}
+
+/// What the daemon is doing, for `dcx status`, `dcx inspect` and `dcx daemon status`.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Health {
+    pub pid: u32,
+    /// Unix timestamp when the daemon started.
+    pub started: u64,
+    pub workspaces: Vec<WorkspaceHealth>,
+}
+
+/// One registered workspace.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceHealth {
+    /// Mount name.
+    pub workspace: String,
+    /// Number of synced files.
+    pub files: usize,
+    /// Whether the workspace's sync thread is alive.
+    pub running: bool,
+}
+
+impl Health {
+    /// Whether `mount_name` is registered and being synced.
+    pub fn is_syncing(&self, mount_name: &str) -> bool {
+        self.workspaces
+            .iter()
+            .any(|w| w.workspace == mount_name && w.running)
+    }
+}
+
+/// Whether `name` can be a registered workspace (a mount name, never a path).
+pub fn valid_workspace_name(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('.') && !name.contains('/')
+}
+
+// ── Registry ──────────────────────────────────────────────────────────────────
+
+/// Parse the registry file: one mount name per line, blanks and duplicates ignored.
+pub fn parse_registry(content: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for line in content.lines().map(str::trim) {
+        if valid_workspace_name(line) && !names.iter().any(|n| n == line) {
+            names.push(line.to_string());
+        }
+    }
+    names
+}
+
+/// Serialize the registry file.
+pub fn format_registry(names: &[String]) -> String {
+    names.iter().map(|n| format!("{n}\n")).collect()
+}
+
+pub fn load_registry(home: &Path) -> Vec<String> {
+    parse_registry(&std::fs::read_to_string(registry_file(home)).unwrap_or_default())
+}
+
+/// Write the registry. Best effort, like `state::record_event`.
+pub fn save_registry(home: &Path, names: &[String]) {
+    let _ = std::fs::create_dir_all(state::state_root(home));
+    let _ = std::fs::write(registry_file(home), format_registry(names));
+}
+
+// ── Client ────────────────────────────────────────────────────────────────────
+
+/// Send one request to the running daemon.
+pub fn request(home: &Path, req: &Request) -> Result<Response, String> {
+    let mut stream = UnixStream::connect(socket_path(home))
+        .map_err(|e| format!("dcx daemon is not running: {e}"))?;
+    let _ = stream.set_read_timeout(Some(CLIENT_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(CLIENT_TIMEOUT));
+    let mut line = serde_json::to_string(req).map_err(|e| e.to_string())?;
+    line.push('\n');
+    stream
+        .write_all(line.as_bytes())
+        .map_err(|e| format!("dcx daemon: {e}"))?;
+    let mut reply = String::new();
+    BufReader::new(stream)
+        .read_line(&mut reply)
+        .map_err(|e| format!("dcx daemon: {e}"))?;
+    serde_json::from_str(&reply).map_err(|e| format!("dcx daemon: invalid response: {e}"))
+}
+
+/// Health of the running daemon, or None if it is not running.
+pub fn health(home: &Path) -> Option<Health> {
+    request(home, &Request::Health).ok()?.health
+}
+
+/// Start the daemon unless it is running, and wait until it answers.
+pub fn ensure_running(home: &Path) -> Result<(), String> {
+    use std::os::unix::process::CommandExt;
+
+    if health(home).is_some() {
+        return Ok(());
+    }
+    let exe = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("dcx"));
+    std::process::Command::new(&exe)
+        .arg("_daemon")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .stdin(std::process::Stdio::null())
+        .process_group(0) // Survive the exit of the dcx command that started it
+        .spawn()
+        .map_err(|e| format!("Could not start dcx daemon: {e}"))?;
+    let deadline = Instant::now() + CLIENT_TIMEOUT;
+    while Instant::now() < deadline {
+        if health(home).is_some() {
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    Err(format!(
+        "dcx daemon did not start (see {})",
+        log_file(home).display()
+    ))
+}
+
+/// Have the daemon sync the pairs recorded for `mount_name` by `state::save_sync_pairs`,
+/// starting the daemon if needed.
+pub fn register(home: &Path, mount_name: &str) -> Result<(), String> {
+    ensure_running(home)?;
+    let resp = request(
+        home,
+        &Request::Register {
+            workspace: mount_name.to_string(),
+        },
+    )?;
+    if resp.ok {
+        Ok(())
+    } else {
+        Err(resp.error.unwrap_or_default())
+    }
+}
+
+/// Stop syncing `mount_name`. Without a running daemon the registry is updated directly,
+/// so the workspace is not resumed when the daemon starts again.
+pub fn unregister(home: &Path, mount_name: &str) {
+    let req = Request::Unregister {
+        workspace: mount_name.to_string(),
+    };
+    if request(home, &req).is_err() {
+        let names: Vec<String> = load_registry(home)
+            .into_iter()
+            .filter(|n| n != mount_name)
+            .collect();
+        save_registry(home, &names);
+    }
+}
+
+/// Stop syncing the workspace mounted at `mount_point`: unregister it from the daemon and
+/// stop the per-workspace sync daemon an older dcx may have left.
+pub fn stop_sync(home: &Path, mount_point: &Path) {
+    if let Some(name) = mount_point.file_name() {
+        unregister(home, &name.to_string_lossy());
+    }
+    stop_legacy_sync(mount_point);
+}
+
+/// Stop the per-workspace sync daemon an older dcx may have left for `mount_point` (PID in
+/// the staging dir), so it does not sync the same pairs as the dcx daemon.
+pub fn stop_legacy_sync(mount_point: &Path) {
+    if let Some(pid) = live_legacy_sync_pid(&crate::up::staging_dir(mount_point)) {
+        let _ = std::process::Command::new("kill")
+            .arg("-TERM")
+            .arg(pid.to_string())
+            .status();
+        // Brief wait for daemon to remove its own PID file
+        std::thread::sleep(Duration::from_millis(200));
+    }
+}
+
+/// PID of a live per-workspace sync daemon (started by an older dcx) recorded in `staging`'s
+/// PID file.
+///
+/// The process must be a `dcx _sync-daemon`, so a stale PID file whose PID has been
+/// reused by an unrelated process is never acted on.
+pub fn live_legacy_sync_pid(staging: &Path) -> Option<u32> {
+    let pid: u32 = std::fs::read_to_string(staging.join(".sync-daemon.pid"))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    let out = crate::cmd::run_capture("ps", &["-p", &pid.to_string(), "-o", "args="]).ok()?;
+    (out.status == 0 && out.stdout.contains("_sync-daemon")).then_some(pid)
+}
+
+// ── Daemon ────────────────────────────────────────────────────────────────────
+
+/// Take the daemon lock without blocking. None if another daemon holds it (or the file
+/// cannot be opened). The lock is released when the returned file is closed.
+fn try_lock(home: &Path) -> Option<std::fs::File> {
+    use std::os::unix::io::AsRawFd;
+
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_file(home))
+        .ok()?;
+    // SAFETY: flock on a file descriptor owned by `file`.
+    let rc = unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) };
+    (rc == 0).then_some(file)
+}
+
+/// A workspace's sync thread.
+struct Worker {
+    stop: Arc<AtomicBool>,
+    handle: std::thread::JoinHandle<()>,
+    files: usize,
+}
+
+struct Daemon {
+    home: PathBuf,
+    started: u64,
+    workers: BTreeMap<String, Worker>,
+    log: DaemonLog,
+}
+
+impl Daemon {
+    /// (Re)start syncing a workspace with its recorded pairs. Returns the number of files.
+    fn start_worker(&mut self, name: &str) -> Result<usize, String> {
+        self.stop_worker(name);
+        let pairs = state::load_sync_pairs(&self.home, name);
+        if pairs.is_empty() {
+            return Err(format!("no synced files recorded for {name}"));
+        }
+        let files = pairs.len();
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&stop);
+        let log = DaemonLog::new(Some(state::daemon_log_file(&self.home, name)));
+        let base_dir = state::sync_base_dir(&self.home, name);
+        let handle = std::thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || sync::run_sync_loop(&pairs, Some(&base_dir), &log, &flag))
+            .map_err(|e| format!("could not start sync thread for {name}: {e}"))?;
+        self.workers.insert(
+            name.to_string(),
+            Worker {
+                stop,
+                handle,
+                files,
+            },
+        );
+        Ok(files)
+    }
+
+    /// Stop a workspace's sync thread and wait for it. Returns whether it was registered.
+    fn stop_worker(&mut self, name: &str) -> bool {
+        let Some(worker) = self.workers.remove(name) else {
+            return false;
+        };
+        worker.stop.store(true, Ordering::Relaxed);
+        let _ = worker.handle.join();
+        true
+    }
+
+    fn save_registry(&self) {
+        let names: Vec<String> = self.workers.keys().cloned().collect();
+        save_registry(&self.home, &names);
+    }
+
+    fn health(&self) -> Health {
+        Health {
+            pid: std::process::id(),
+            started: self.started,
+            workspaces: self
+                .workers
+                .iter()
+                .map(|(name, w)| WorkspaceHealth {
+                    workspace: name.clone(),
+                    files: w.files,
+                    running: !w.handle.is_finished(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Handle one request. Returns the response and whether to shut down.
+    fn handle(&mut self, req: Request) -> (Response, bool) {
+        match req {
+            Request::Register { workspace } if valid_workspace_name(&workspace) => {
+                let resp = match self.start_worker(&workspace) {
+                    Ok(files) => {
+                        self.log.info(&format!(
+                            "registered {workspace} ({files} file{})",
+                            if files == 1 { "" } else { "s" }
+                        ));
+                        Response::ok()
+                    }
+                    Err(e) => {
+                        self.log.error(&e);
+                        Response::error(e)
+                    }
+                };
+                self.save_registry();
+                (resp, false)
+            }
+            Request::Unregister { workspace } if valid_workspace_name(&workspace) => {
+                if self.stop_worker(&workspace) {
+                    self.log.info(&format!("unregistered {workspace}"));
+                }
+                self.save_registry();
+                (Response::ok(), false)
+            }
+            Request::Register { workspace } | Request::Unregister { workspace } => (
+                Response::error(format!("invalid workspace name: {workspace}")),
+                false,
+            ),
+            Request::Health => (
+                Response {
+                    health: Some(self.health()),
+                    ..Response::ok()
+                },
+                false,
+            ),
+            Request::Shutdown => (Response::ok(), true),
+        }
+    }
+
+    /// Serve one connection. Returns whether to shut down.
+    fn serve(&mut self, stream: UnixStream) -> bool {
+        // Accepted sockets inherit non-blocking mode from the listener on some platforms.
+        let _ = stream.set_nonblocking(false);
+        let _ = stream.set_read_timeout(Some(CLIENT_TIMEOUT));
+        let mut line = String::new();
+        let mut reader = BufReader::new(&stream);
+        if reader.read_line(&mut line).is_err() {
+            return false;
+        }
+        let (resp, shutdown) = match serde_json::from_str::<Request>(&line) {
+            Ok(req) => self.handle(req),
+            Err(e) => (Response::error(format!("invalid request: {e}")), false),
+        };
+        if let Ok(mut out) = serde_json::to_string(&resp) {
+            out.push('\n');
+            let _ = (&stream).write_all(out.as_bytes());
+        }
+        shutdown
+    }
+
+    /// Stop every sync thread. The registry is kept so a restart resumes them.
+    fn stop_all(&mut self) {
+        let names: Vec<String> = self.workers.keys().cloned().collect();
+        for name in names {
+            self.stop_worker(&name);
+        }
+    }
+}
+
+/// Internal: run the per-user dcx daemon (`dcx _daemon`). Never returns.
+///
+/// Serves the control socket, runs one sync thread per registered workspace and resumes
+/// the registered workspaces on start. Exits on SIGTERM, a `shutdown` request, or after
+/// `IDLE_EXIT` without workspaces.
+pub fn run_daemon(home: &Path) -> ! {
+    let _ = std::fs::create_dir_all(state::state_root(home));
+    let log = DaemonLog::new(Some(log_file(home)));
+    let sock = socket_path(home);
+
+    // Another daemon is running or starting: nothing to do. The lock serializes the
+    // connect/unlink/bind below, so a live daemon's socket is never removed.
+    let Some(_lock) = try_lock(home) else {
+        std::process::exit(exit_codes::SUCCESS);
+    };
+    if UnixStream::connect(&sock).is_ok() {
+        std::process::exit(exit_codes::SUCCESS);
+    }
+    // The lock is ours, so any socket file is stale.
+    let _ = std::fs::remove_file(&sock);
+    let listener = match UnixListener::bind(&sock) {
+        Ok(l) => l,
+        Err(e) => {
+            log.error(&format!("bind {}: {e}", sock.display()));
+            std::process::exit(exit_codes::RUNTIME_ERROR);
+        }
+    };
+    let _ = listener.set_nonblocking(true);
+
+    let term_flag = Arc::new(AtomicBool::new(false));
+    let _ = signal_hook::flag::register(signal_hook::consts::SIGTERM, Arc::clone(&term_flag));
+    // Ignore SIGHUP so the daemon survives when the terminal that started it closes.
+    let _ = signal_hook::flag::register(
+        signal_hook::consts::SIGHUP,
+        Arc::new(AtomicBool::new(false)),
+    );
+
+    let mut daemon = Daemon {
+        home: home.to_path_buf(),
+        started: state::unix_now(),
+        workers: BTreeMap::new(),
+        log,
+    };
+    daemon
+        .log
+        .info(&format!("started (pid {})", std::process::id()));
+    for name in load_registry(home) {
+        match daemon.start_worker(&name) {
+            Ok(_) => daemon.log.info(&format!("resumed {name}")),
+            Err(e) => daemon.log.warn(&format!("{e}; unregistered")),
+        }
+    }
+    daemon.save_registry();
+
+    let mut last_request = Instant::now();
+    loop {
+        if term_flag.load(Ordering::Relaxed) {
+            daemon.log.info("stopping (SIGTERM)");
+            break;
+        }
+        match listener.accept() {
+            Ok((stream, _)) => {
+                last_request = Instant::now();
+                if daemon.serve(stream) {
+                    daemon.log.info("stopping (shutdown request)");
+                    break;
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            Err(e) => {
+                daemon.log.error(&format!("accept: {e}"));
+                std::thread::sleep(Duration::from_millis(100));
+            }
+        }
+        if daemon.workers.is_empty() && last_request.elapsed() >= IDLE_EXIT {
+            daemon.log.info("stopping (no registered workspaces)");
+            break;
+        }
+    }
+
+    daemon.stop_all();
+    let _ = std::fs::remove_file(&sock);
+    daemon.log.info("stopped");
+    std::process::exit(exit_codes::SUCCESS);
+}
+
+// ── dcx daemon ────────────────────────────────────────────────────────────────
+
+/// Rows of the `dcx daemon status` table.
+pub fn daemon_rows(health: &Health) -> Vec<DaemonRow> {
+    health
+        .workspaces
+        .iter()
+        .map(|w| DaemonRow {
+            workspace: w.workspace.clone(),
+            files: w.files,
+            sync: if w.running { "running" } else { "failed" }.to_string(),
+        })
+        .collect()
+}
+
+/// One-line daemon summary below the `dcx status` table. None when the daemon is not
+/// running and no workspace is registered (no synced files anywhere).
+pub fn health_footer(health: Option<&Health>, registered: usize) -> Option<String> {
+    let plural = |n: usize| if n == 1 { "" } else { "s" };
+    match health {
+        Some(h) => Some(format!(
+            "dcx daemon: running (PID {}, syncing {} workspace{})",
+            h.pid,
+            h.workspaces.len(),
+            plural(h.workspaces.len())
+        )),
+        None if registered > 0 => Some(format!(
+            "dcx daemon: not running ({registered} registered workspace{} not syncing; \
+             run `dcx daemon restart`)",
+            plural(registered)
+        )),
+        None => None,
+    }
+}
+
+/// Run `dcx daemon status`.
+///
+/// Returns the exit code that `main` should pass to `std::process::exit`.
+pub fn run_daemon_status(home: &Path) -> i32 {
+    let Some(health) = health(home) else {
+        println!("dcx daemon: not running");
+        let registered = load_registry(home).len();
+        if registered > 0 {
+            println!(
+                "{registered} registered workspace{} will resume when it starts \
+                 (`dcx daemon restart`, `dcx up` or `dcx start`).",
+                if registered == 1 { "" } else { "s" }
+            );
+        }
+        return exit_codes::SUCCESS;
+    };
+    println!(
+        "dcx daemon: running (PID {}, up {})",
+        health.pid,
+        format_duration(state::unix_now().saturating_sub(health.started))
+    );
+    if health.workspaces.is_empty() {
+        println!("No registered workspaces.");
+    } else {
+        println!("{}", format_daemon_table(&daemon_rows(&health)));
+    }
+    exit_codes::SUCCESS
+}
+
+/// Run `dcx daemon restart`: stop the daemon (if running) and start it again. Registered
+/// workspaces are resumed from the registry.
+///
+/// Returns the exit code that `main` should pass to `std::process::exit`.
+pub fn run_daemon_restart(home: &Path) -> i32 {
+    if request(home, &Request::Shutdown).is_ok() {
+        // Wait for the old daemon to exit and release its lock.
+        let deadline = Instant::now() + CLIENT_TIMEOUT;
+        while try_lock(home).is_none() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+    if let Err(e) = ensure_running(home) {
+        eprintln!("{e}");
+        return exit_codes::RUNTIME_ERROR;
+    }
+    let resumed = health(home).map(|h| h.workspaces.len()).unwrap_or(0);
+    println!(
+        "dcx daemon restarted ({resumed} workspace{} resumed).",
+        if resumed == 1 { "" } else { "s" }
+    );
+    exit_codes::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_wire_format() {
+        let req = Request::Register {
+            workspace: "dcx-proj-abcd1234".to_string(),
+        };
+        let line = serde_json::to_string(&req).unwrap();
+        assert_eq!(
+            line,
+            r#"{"request":"register","workspace":"dcx-proj-abcd1234"}"#
+        );
+        assert_eq!(serde_json::from_str::<Request>(&line).unwrap(), req);
+        assert_eq!(
+            serde_json::from_str::<Request>(r#"{"request":"health"}"#).unwrap(),
+            Request::Health
+        );
+        assert!(serde_json::from_str::<Request>(r#"{"request":"reboot"}"#).is_err());
+    }
+
+    #[test]
+    fn response_omits_empty_fields() {
+        assert_eq!(
+            serde_json::to_string(&Response::ok()).unwrap(),
+            r#"{"ok":true}"#
+        );
+        let resp: Response = serde_json::from_str(r#"{"ok":false,"error":"x"}"#).unwrap();
+        assert_eq!(resp, Response::error("x".to_string()));
+    }
+
+    #[test]
+    fn registry_round_trip_skips_blanks_duplicates_and_paths() {
+        let names = parse_registry("dcx-a-1\n\ndcx-b-2\ndcx-a-1\n../etc\n.hidden\n");
+        assert_eq!(names, vec!["dcx-a-1".to_string(), "dcx-b-2".to_string()]);
+        assert_eq!(parse_registry(&format_registry(&names)), names);
+    }
+
+    #[test]
+    fn unregister_without_daemon_updates_registry() {
+        let home = tempfile::tempdir().unwrap();
+        save_registry(home.path(), &["dcx-a-1".to_string(), "dcx-b-2".to_string()]);
+        unregister(home.path(), "dcx-a-1");
+        assert_eq!(load_registry(home.path()), vec!["dcx-b-2".to_string()]);
+    }
+
+    #[test]
+    fn live_legacy_sync_pid_ignores_pids_of_other_processes() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(live_legacy_sync_pid(dir.path()), None);
+        // Our own PID is alive but is not a sync daemon (e.g. a reused PID).
+        std::fs::write(
+            dir.path().join(".sync-daemon.pid"),
+            std::process::id().to_string(),
+        )
+        .unwrap();
+        assert_eq!(live_legacy_sync_pid(dir.path()), None);
+    }
+
+    #[test]
+    fn daemon_lock_is_exclusive_until_released() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(state::state_root(home.path())).unwrap();
+        let held = try_lock(home.path()).expect("first lock");
+        assert!(try_lock(home.path()).is_none());
+        drop(held);
+        assert!(try_lock(home.path()).is_some());
+    }
+
+    #[test]
+    fn health_is_syncing_requires_running_thread() {
+        let health = Health {
+            pid: 1,
+            started: 0,
+            workspaces: vec![
+                WorkspaceHealth {
+                    workspace: "dcx-a-1".to_string(),
+                    files: 2,
+                    running: true,
+                },
+                WorkspaceHealth {
+                    workspace: "dcx-b-2".to_string(),
+                    files: 1,
+                    running: false,
+                },
+            ],
+        };
+        assert!(health.is_syncing("dcx-a-1"));
+        assert!(!health.is_syncing("dcx-b-2"));
+        assert!(!health.is_syncing("dcx-c-3"));
+        assert_eq!(daemon_rows(&health)[1].sync, "failed");
+    }
+
+    #[test]
+    fn health_footer_reports_daemon_state() {
+        let health = Health {
+            pid: 42,
+            started: 0,
+            workspaces: vec![],
+        };
+        assert_eq!(
+            health_footer(Some(&health), 0).unwrap(),
+            "dcx daemon: running (PID 42, syncing 0 workspaces)"
+        );
+        assert!(
+            health_footer(None, 2)
+                .unwrap()
+                .contains("2 registered workspaces not syncing")
+        );
+        assert_eq!(health_footer(None, 0), None);
+    }
+
+    #[test]
+    fn daemon_registers_and_unregisters_workspaces() {
+        let home = tempfile::tempdir().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("a.json");
+        std::fs::write(&source, "{}").unwrap();
+        state::save_sync_pairs(
+            home.path(),
+            "dcx-a-1",
+            &[sync::SyncPair {
+                source: source.clone(),
+                staging: dir.path().join("staged.json"),
+                merge: None,
+            }],
+        );
+        let mut daemon = Daemon {
+            home: home.path().to_path_buf(),
+            started: 0,
+            workers: BTreeMap::new(),
+            log: DaemonLog::new(None),
+        };
+
+        let (resp, _) = daemon.handle(Request::Register {
+            workspace: "dcx-a-1".to_string(),
+        });
+        assert!(resp.ok, "{resp:?}");
+        assert_eq!(load_registry(home.path()), vec!["dcx-a-1".to_string()]);
+        let (resp, _) = daemon.handle(Request::Health);
+        let health = resp.health.unwrap();
+        assert_eq!(health.workspaces[0].files, 1);
+
+        // No recorded pairs: rejected and not registered.
+        let (resp, _) = daemon.handle(Request::Register {
+            workspace: "dcx-b-2".to_string(),
+        });
+        assert!(!resp.ok);
+        let (resp, _) = daemon.handle(Request::Register {
+            workspace: "../x".to_string(),
+        });
+        assert!(!resp.ok);
+
+        let (resp, shutdown) = daemon.handle(Request::Unregister {
+            workspace: "dcx-a-1".to_string(),
+        });
+        assert!(resp.ok && !shutdown);
+        assert!(load_registry(home.path()).is_empty());
+        assert!(daemon.handle(Request::Shutdown).1);
+    }
+}
@@ -9,24 +9,76 @@ struct DcxFileRaw {
     path: String,
     #[serde(default)]
     sync: bool,
+    #[serde(default)]
+    merge: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct NetworkPhasesRaw {
+    #[serde(default)]
+    build: Option<String>,
+    #[serde(default)]
+    create: Option<String>,
+    #[serde(default)]
+    run: Option<String>,
+}
+
+/// `up.network` accepts either a single mode or a per-phase map.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NetworkRaw {
+    Mode(String),
+    Phases(NetworkPhasesRaw),
 }
 
 #[derive(Deserialize, Default)]
 struct DcxUpConfigRaw {
     #[serde(default)]
-    network: Option<String>,
+    network: Option<NetworkRaw>,
 
     #[serde(default)]
     yes: Option<bool>,
 
     #[serde(default)]
     files: Vec<DcxFileRaw>,
+
+    #[serde(default)]
+    shell: Option<String>,
+
+    #[serde(default)]
+    record_sessions: bool,
+
+    #[serde(default)]
+    idle_timeout: Option<String>,
+}
+
+/// `gc.max_image_size` accepts a size string (`20GB`) or a plain byte count.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SizeRaw {
+    Bytes(u64),
+    Text(String),
+}
+
+#[derive(Deserialize, Default)]
+struct DcxGcConfigRaw {
+    #[serde(default)]
+    max_image_size: Option<SizeRaw>,
+
+    #[serde(default)]
+    max_idle_workspaces: Option<usize>,
+
+    #[serde(default)]
+    keep: Option<usize>,
 }
 
 #[derive(Deserialize, Default)]
 struct DcxConfigRaw {
     #[serde(default)]
     up: DcxUpConfigRaw,
+
+    #[serde(default)]
+    gc: DcxGcConfigRaw,
 }
 
 // ── Public types ──────────────────────────────────────────────────────────────
@@ -37,6 +89,19 @@ pub struct FileMount {
     pub path: String,
     /// Whether to sync this file bidirectionally between host and container.
     pub sync: bool,
+    /// Document format (`json`, `yaml`, `toml`) for key-level merging of concurrent edits.
+    /// Only used with `sync`. Validated at call site.
+    pub merge: Option<String>,
+}
+
+/// Per-phase network modes from `up.network: {build, create, run}`.
+/// Values are validated at call site; an omitted build phase is `open`, other omitted
+/// phases fall back to the default mode.
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct NetworkPhases {
+    pub build: Option<String>,
+    pub create: Option<String>,
+    pub run: Option<String>,
 }
 
 #[derive(Debug, PartialEq, Default)]
@@ -44,16 +109,43 @@ pub struct DcxUpConfig {
     /// Network isolation level (validated at call site). None means not set.
     pub network: Option<String>,
 
+    /// Per-phase network policy. Set instead of `network` when `up.network` is a map.
+    pub network_phases: Option<NetworkPhases>,
+
     /// Skip confirmation prompts. None means not set.
     pub yes: Option<bool>,
 
     /// File mounts (paths may contain `~`). Expansion happens at call site.
     pub files: Vec<FileMount>,
+
+    /// Shell for `dcx shell`. None means detect from the container's /etc/passwd.
+    pub shell: Option<String>,
+
+    /// Record every interactive `dcx exec` / `dcx shell` session (like `--record`).
+    pub record_sessions: bool,
+
+    /// Bring the workspace down after this long without activity (e.g. `2h`).
+    /// Validated at call site.
+    pub idle_timeout: Option<String>,
+}
+
+/// Garbage collection policy applied by `dcx gc` and, opportunistically, `dcx up`.
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct DcxGcConfig {
+    /// Maximum total size of dcx images (e.g. `20GB`). Validated at call site.
+    pub max_image_size: Option<String>,
+
+    /// Maximum number of workspaces without a running container.
+    pub max_idle_workspaces: Option<usize>,
+
+    /// Number of most recently used workspaces that are never collected.
+    pub keep: Option<usize>,
 }
 
 #[derive(Debug, PartialEq, Default)]
 pub struct DcxConfig {
     pub up: DcxUpConfig,
+    pub gc: DcxGcConfig,
 }
 
 // ── Public functions ──────────────────────────────────────────────────────────
@@ -62,21 +154,48 @@ pub struct DcxConfig {
 /// Returns an empty DcxConfig on any parse error.
 pub fn parse_dcx_config(yaml: &str) -> DcxConfig {
     match serde_yaml::from_str::<DcxConfigRaw>(yaml) {
-        Ok(raw) => DcxConfig {
-            up: DcxUpConfig {
-                network: raw.up.network,
-                yes: raw.up.yes,
-                files: raw
-                    .up
-                    .files
-                    .into_iter()
-                    .map(|f| FileMount {
-                        path: f.path,
-                        sync: f.sync,
-                    })
-                    .collect(),
-            },
-        },
+        Ok(raw) => {
+            let (network, network_phases) = match raw.up.network {
+                Some(NetworkRaw::Mode(mode)) => (Some(mode), None),
+                Some(NetworkRaw::Phases(p)) => (
+                    None,
+                    Some(NetworkPhases {
+                        build: p.build,
+                        create: p.create,
+                        run: p.run,
+                    }),
+                ),
+                None => (None, None),
+            };
+            DcxConfig {
+                up: DcxUpConfig {
+                    network,
+                    network_phases,
+                    yes: raw.up.yes,
+                    files: raw
+                        .up
+                        .files
+                        .into_iter()
+                        .map(|f| FileMount {
+                            path: f.path,
+                            sync: f.sync,
+                            merge: f.merge,
+                        })
+                        .collect(),
+                    shell: raw.up.shell,
+                    record_sessions: raw.up.record_sessions,
+                    idle_timeout: raw.up.idle_timeout,
+                },
+                gc: DcxGcConfig {
+                    max_image_size: raw.gc.max_image_size.map(|s| match s {
+                        SizeRaw::Bytes(n) => n.to_string(),
+                        SizeRaw::Text(t) => t,
+                    }),
+                    max_idle_workspaces: raw.gc.max_idle_workspaces,
+                    keep: raw.gc.keep,
+                },
+            }
+        }
         Err(_) => DcxConfig::default(),
     }
 }
@@ -119,16 +238,35 @@ mod tests {
             vec![
                 FileMount {
                     path: "~/.gitconfig".to_string(),
-                    sync: false
+                    sync: false,
+                    merge: None
                 },
                 FileMount {
                     path: "~/.claude.json".to_string(),
-                    sync: false
+                    sync: false,
+                    merge: None
                 },
             ]
         );
     }
 
+    #[test]
+    fn parse_dcx_config_reads_gc_policy() {
+        let yaml = "gc:\n  max_image_size: 20GB\n  max_idle_workspaces: 3\n  keep: 5\n";
+        let cfg = parse_dcx_config(yaml);
+        assert_eq!(
+            cfg.gc,
+            DcxGcConfig {
+                max_image_size: Some("20GB".to_string()),
+                max_idle_workspaces: Some(3),
+                keep: Some(5),
+            }
+        );
+        let bytes = parse_dcx_config("gc:\n  max_image_size: 1000000\n");
+        assert_eq!(bytes.gc.max_image_size.as_deref(), Some("1000000"));
+        assert_eq!(parse_dcx_config("up: {}\n").gc, DcxGcConfig::default());
+    }
+
     #[test]
     fn parse_dcx_config_empty_yaml_returns_empty() {
         let cfg = parse_dcx_config("");
@@ -158,6 +296,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn parse_dcx_config_file_sync_defaults_to_false() {
         let yaml = "up:\n  files:\n    - path: ~/.gitconfig\n";
         let cfg = parse_dcx_config(yaml);
@@ -165,6 +304,17 @@ mod tests {
     }
 
     #[test]
+    fn parse_dcx_config_file_merge() {
+        let yaml =
+            "up:\n  files:\n    - path: ~/.claude.json\n      sync: true\n      merge: json\n";
+        let cfg = parse_dcx_config(yaml);
+        assert_eq!(cfg.up.files[0].merge.as_deref(), Some("json"));
+        let yaml = "up:\n  files:\n    - path: ~/.gitconfig\n";
+        assert_eq!(parse_dcx_config(yaml).up.files[0].merge, None);
+    }
+
+    #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn parse_dcx_config_file_sync_true_when_specified() {
         let yaml = "up:\n  files:\n    - path: ~/.claude.json\n      sync: true\n";
         let cfg = parse_dcx_config(yaml);
@@ -179,6 +329,62 @@ mod tests {
         assert_eq!(cfg.up.network, Some("open".to_string()));
     }
 
+    #[test]
+    fn parse_dcx_config_reads_up_network_phases() {
+        let yaml = "up:\n  network:\n    build: open\n    create: open\n    run: restricted\n";
+        let cfg = parse_dcx_config(yaml);
+        assert_eq!(cfg.up.network, None);
+        assert_eq!(
+            cfg.up.network_phases,
+            Some(NetworkPhases {
+                build: Some("open".to_string()),
+                create: Some("open".to_string()),
+                run: Some("restricted".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_dcx_config_network_phases_omitted_are_none() {
+        let yaml = "up:\n  network:\n    run: restricted\n";
+        let cfg = parse_dcx_config(yaml);
+        let phases = cfg.up.network_phases.unwrap();
+        assert_eq!(phases.build, None);
+        assert_eq!(phases.create, None);
+        assert_eq!(phases.run, Some("restricted".to_string()));
+    }
+
+    #[test]
+    fn parse_dcx_config_reads_up_shell() {
+        let cfg = parse_dcx_config("up:\n  shell: /bin/bash\n");
+        assert_eq!(cfg.up.shell, Some("/bin/bash".to_string()));
+    }
+
+    #[test]
+    fn parse_dcx_config_reads_idle_timeout() {
+        assert_eq!(
+            parse_dcx_config("up:\n  idle_timeout: 2h\n")
+                .up
+                .idle_timeout,
+            Some("2h".to_string())
+        );
+        assert_eq!(parse_dcx_config("up: {}\n").up.idle_timeout, None);
+    }
+
+    #[test]
+    fn parse_dcx_config_reads_record_sessions() {
+        assert!(
+            parse_dcx_config("up:\n  record_sessions: true\n")
+                .up
+                .record_sessions
+        );
+        assert!(
+            !parse_dcx_config("up:\n  shell: /bin/bash\n")
+                .up
+                .record_sessions
+        );
+    }
+
     #[test]
     fn parse_dcx_config_reads_up_yes() {
         let yaml = "up:\n  yes: true\n";
@@ -211,7 +417,8 @@ mod tests {
             cfg.up.files,
             vec![FileMount {
                 path: "~/.gitconfig".to_string(),
-                sync: false
+                sync: false,
+                merge: None
             }]
         );
     }
@@ -0,0 +1,255 @@
+#![allow(dead_code)]
+
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+
+use crate::docker::{self, ImageInfo};
+use crate::exit_codes;
+use crate::format::{DfRow, DfTotals, format_df_table};
+use crate::mount_table;
+use crate::naming::{relay_dir, scan_relay};
+use crate::output;
+use crate::platform;
+use crate::progress;
+use crate::size::parse_size;
+use crate::up::staging_dir;
+
+// ── Pure functions ────────────────────────────────────────────────────────────
+
+/// Image ID and size of a tag, if it exists.
+pub fn image_size(images: &[ImageInfo], reference: &str) -> Option<(String, u64)> {
+    images
+        .iter()
+        .find(|i| i.reference == reference)
+        .map(|i| (i.id.clone(), parse_size(&i.size).unwrap_or(0)))
+}
+
+/// Whether a tag other than `reference` points at image `id`.
+pub fn is_shared(images: &[ImageInfo], id: &str, reference: &str) -> bool {
+    images
+        .iter()
+        .any(|i| i.id == id && i.reference != reference)
+}
+
+/// Estimate what `dcx clean --purge` frees for one workspace.
+///
+/// Images are layered (runtime on build on base), so the largest image of the chain is
+/// counted rather than the sum; a shared base image stays, so its size is subtracted.
+/// Volumes, staged copies and the writable layer are freed in full.
+pub fn reclaimable(row: &DfRow) -> u64 {
+    let top = row
+        .runtime_image
+        .or(row.build_image)
+        .or(row.base_image)
+        .unwrap_or(0);
+    let kept = if row.base_image_shared {
+        row.base_image.unwrap_or(0)
+    } else {
+        0
+    };
+    top.saturating_sub(kept) + row.volumes + row.staging + row.writable.unwrap_or(0)
+}
+
+/// Column totals. Base images behind the same image ID are counted once.
+pub fn df_totals(rows: &[DfRow]) -> DfTotals {
+    let mut totals = DfTotals::default();
+    let mut seen_bases: Vec<&str> = Vec::new();
+    for row in rows {
+        totals.runtime_images += row.runtime_image.unwrap_or(0);
+        match row.base_image_id.as_deref() {
+            Some(id) if seen_bases.contains(&id) => {}
+            id => {
+                seen_bases.extend(id);
+                totals.base_images += row.base_image.unwrap_or(0);
+            }
+        }
+        totals.build_images += row.build_image.unwrap_or(0);
+        totals.volumes += row.volumes;
+        totals.staging += row.staging;
+        totals.writable += row.writable.unwrap_or(0);
+        totals.reclaimable += row.reclaimable;
+    }
+    totals
+}
+
+// ── Filesystem ────────────────────────────────────────────────────────────────
+
+/// Bytes used by staged copies. Hardlinked files share the host file's inode and take no
+/// extra space, so only files with a single link are counted.
+pub fn staging_bytes(staging: &Path) -> u64 {
+    std::fs::read_dir(staging)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| e.metadata().ok())
+                .filter(|m| m.is_file() && m.nlink() == 1)
+                .map(|m| m.len())
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+// ── Entry point ───────────────────────────────────────────────────────────────
+
+/// Run `dcx df`: attribute dcx disk usage to workspaces.
+///
+/// Returns the exit code that `main` should pass to `std::process::exit`.
+pub fn run_df(home: &Path) -> i32 {
+    if !docker::is_docker_available() {
+        eprintln!("Docker is not available. Is Colima running?");
+        return exit_codes::RUNTIME_ERROR;
+    }
+
+    progress::step("Scanning workspaces...");
+    let table = platform::read_mount_table().unwrap_or_default();
+    let images = docker::list_images();
+    let volume_sizes = docker::volume_sizes();
+    let writable_sizes = docker::container_writable_sizes();
+
+    let rows: Vec<DfRow> = scan_relay(&relay_dir(home))
+        .into_iter()
+        .map(|mount_point| {
+            let mount = mount_point
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let container = docker::query_container_any(&mount_point).into_iter().next();
+            let runtime_ref = container
+                .as_deref()
+                .and_then(|c| docker::get_runtime_image_ref(c).ok());
+            let runtime_image = runtime_ref
+                .as_deref()
+                .and_then(|r| image_size(&images, r))
+                .map(|(_, size)| size);
+            let build_image = runtime_ref
+                .as_deref()
+                .map(docker::runtime_image_to_build_image)
+                .filter(|b| docker::is_build_image_tag(b))
+                .and_then(|b| image_size(&images, &b))
+                .map(|(_, size)| size);
+            let base_ref = format!("dcx-base:{mount}");
+            let base = image_size(&images, &base_ref);
+            let volumes = container
+                .as_deref()
+                .and_then(|c| docker::get_container_volumes(c).ok())
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|v| volume_sizes.iter().find(|(name, _)| name == v))
+                .filter_map(|(_, size)| parse_size(size))
+                .sum();
+            let writable = container.as_deref().and_then(|c| {
+                writable_sizes
+                    .iter()
+                    .find(|(id, _)| c.starts_with(id.as_str()) || id.starts_with(c))
+                    .and_then(|(_, size)| parse_size(size))
+            });
+            let mut row = DfRow {
+                workspace: mount_table::find_mount_source(&table, &mount_point).map(str::to_string),
+                runtime_image,
+                base_image: base.as_ref().map(|(_, size)| *size),
+                base_image_shared: base
+                    .as_ref()
+                    .is_some_and(|(id, _)| is_shared(&images, id, &base_ref)),
+                base_image_id: base.map(|(id, _)| id),
+                build_image,
+                volumes,
+                staging: staging_bytes(&staging_dir(&mount_point)),
+                writable,
+                mount,
+                reclaimable: 0,
+            };
+            row.reclaimable = reclaimable(&row);
+            row
+        })
+        .collect();
+
+    let totals = df_totals(&rows);
+    if output::is_structured() {
+        output::emit(&serde_json::json!({ "workspaces": rows, "totals": totals }));
+    } else {
+        println!("{}", format_df_table(&rows, &totals));
+    }
+    exit_codes::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(reference: &str, id: &str, size: &str) -> ImageInfo {
+        ImageInfo {
+            reference: reference.to_string(),
+            id: id.to_string(),
+            size: size.to_string(),
+        }
+    }
+
+    #[test]
+    fn base_image_is_shared_when_another_tag_points_at_it() {
+        let images = vec![
+            image("dcx-base:dcx-a-1", "abc", "500MB"),
+            image("ubuntu:22.04", "abc", "500MB"),
+            image("dcx-base:dcx-b-2", "def", "1GB"),
+        ];
+        assert_eq!(
+            image_size(&images, "dcx-base:dcx-a-1"),
+            Some(("abc".to_string(), 500_000_000))
+        );
+        assert!(is_shared(&images, "abc", "dcx-base:dcx-a-1"));
+        assert!(!is_shared(&images, "def", "dcx-base:dcx-b-2"));
+        assert_eq!(image_size(&images, "missing:latest"), None);
+    }
+
+    #[test]
+    fn reclaimable_counts_the_image_chain_once_and_keeps_shared_bases() {
+        let row = DfRow {
+            runtime_image: Some(1_000),
+            base_image: Some(800),
+            build_image: Some(900),
+            volumes: 10,
+            staging: 5,
+            writable: Some(20),
+            ..Default::default()
+        };
+        assert_eq!(reclaimable(&row), 1_000 + 10 + 5 + 20);
+        let shared = DfRow {
+            base_image_shared: true,
+            ..row
+        };
+        assert_eq!(reclaimable(&shared), 200 + 10 + 5 + 20);
+        // No container left: only an exclusive base image tag.
+        let base_only = DfRow {
+            base_image: Some(800),
+            ..Default::default()
+        };
+        assert_eq!(reclaimable(&base_only), 800);
+    }
+
+    #[test]
+    fn df_totals_count_shared_base_images_once() {
+        let row = |base_id: &str| DfRow {
+            runtime_image: Some(100),
+            base_image: Some(50),
+            base_image_id: Some(base_id.to_string()),
+            reclaimable: 7,
+            ..Default::default()
+        };
+        let totals = df_totals(&[row("abc"), row("abc"), row("def")]);
+        assert_eq!(totals.runtime_images, 300);
+        assert_eq!(totals.base_images, 100);
+        assert_eq!(totals.reclaimable, 21);
+    }
+
+    #[test]
+    fn staging_bytes_skips_hardlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let host = dir.path().join("host");
+        std::fs::write(&host, "0123456789").unwrap();
+        let staging = dir.path().join("staging");
+        std::fs::create_dir(&staging).unwrap();
+        std::fs::hard_link(&host, staging.join("linked")).unwrap();
+        std::fs::write(staging.join("copied"), "abc").unwrap();
+        assert_eq!(staging_bytes(&staging), 3);
+        assert_eq!(staging_bytes(&dir.path().join("missing")), 0);
+    }
+}
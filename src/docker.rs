@@ -3,6 +3,7 @@
 use std::path::Path;
 
 use crate::cmd;
+use crate::network_mode::{self, NetworkMode};
 
 /// Return `true` if Docker (or Colima) is running and reachable.
 ///
@@ -30,19 +31,27 @@ pub fn query_container(mount_point: &Path) -> Option<String> {
 ///
 /// Returns a vector of all matching container IDs. If no containers are found, returns an empty vector.
 pub fn query_container_any(mount_point: &Path) -> Vec<String> {
+    try_query_container_any(mount_point).unwrap_or_default()
+}
+
+/// Like `query_container_any`, but tells "no container" apart from "Docker unreachable".
+///
+/// Returns `Err(message)` if `docker ps` cannot run or exits non-zero.
+pub fn try_query_container_any(mount_point: &Path) -> Result<Vec<String>, String> {
     let label = format!("label=devcontainer.local_folder={}", mount_point.display());
-    let out = match cmd::run_capture(
+    let out = cmd::run_capture(
         "docker",
         &["ps", "-a", "--filter", &label, "--format", "{{.ID}}"],
-    ) {
-        Ok(out) => out,
-        Err(_) => return vec![],
-    };
-    out.stdout
+    )?;
+    if out.status != 0 {
+        return Err(format!("Failed to list containers: {}", out.stderr.trim()));
+    }
+    Ok(out
+        .stdout
         .lines()
         .map(|line| line.trim().to_string())
         .filter(|id| !id.is_empty())
-        .collect()
+        .collect())
 }
 
 /// Stop a running container associated with `mount_point` using `docker stop`.
@@ -60,6 +69,17 @@ pub fn stop_container(mount_point: &Path) -> Result<(), String> {
     Ok(())
 }
 
+/// Start a stopped container by ID using `docker start`.
+///
+/// Returns `Err(message)` if the start command fails.
+pub fn start_container(container_id: &str) -> Result<(), String> {
+    let out = cmd::run_capture("docker", &["start", container_id])?;
+    if out.status != 0 {
+        return Err(format!("Failed to start container: {}", out.stderr.trim()));
+    }
+    Ok(())
+}
+
 /// Remove a container by ID using `docker rm`.
 ///
 /// Returns `Err(message)` if the remove command fails.
@@ -71,27 +91,62 @@ pub fn remove_container(container_id: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Read a label from a container by inspecting it.
+///
+/// Returns `Some(value)` if the label exists, `None` if not found or container doesn't exist.
+pub fn read_label(container_id: &str, label: &str) -> Option<String> {
+    let format = format!("--format={{{{index .Config.Labels \"{label}\"}}}}");
+    let out = cmd::run_capture("docker", &["inspect", &format, container_id]).ok()?;
+
+    let value = out.stdout.trim();
+    // "no value" means the label doesn't exist
+    if value.is_empty() || value.contains("no value") {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
 /// Get the network mode label from a container by inspecting it.
 ///
 /// Returns `Some(mode)` if the label exists, `None` if not found or container doesn't exist.
 pub fn read_network_mode(container_id: &str) -> Option<String> {
+    read_label(container_id, "dcx.network-mode")
+}
+
+/// The network mode the container should be enforcing: the run-phase mode of a phased
+/// policy, the `dcx.network-mode` label otherwise. See `network_mode::runtime_mode`.
+pub fn read_runtime_network_mode(container_id: &str) -> Option<NetworkMode> {
+    network_mode::runtime_mode(
+        read_network_mode(container_id).as_deref(),
+        read_label(container_id, network_mode::POLICY_LABEL).as_deref(),
+    )
+}
+
+/// Switch a running container's firewall to `mode` by re-running the firewall script as root.
+///
+/// Returns `Err(message)` if the script is missing or exits non-zero.
+pub fn apply_network_mode(container_id: &str, mode: NetworkMode) -> Result<(), String> {
+    let env = format!("DCX_NETWORK_MODE={mode}");
     let out = cmd::run_capture(
         "docker",
         &[
-            "inspect",
-            "--format={{index .Config.Labels \"dcx.network-mode\"}}",
+            "exec",
+            "-u",
+            "root",
+            "-e",
+            &env,
             container_id,
+            network_mode::FIREWALL_SCRIPT,
         ],
-    )
-    .ok()?;
-
-    let mode = out.stdout.trim();
-    // "no value" means the label doesn't exist
-    if mode.is_empty() || mode.contains("no value") {
-        None
-    } else {
-        Some(mode.to_string())
+    )?;
+    if out.status != 0 {
+        return Err(format!(
+            "Failed to apply network mode {mode}: {}",
+            out.stderr.trim()
+        ));
     }
+    Ok(())
 }
 
 /// Get the image ID from a container by inspecting it.
@@ -286,6 +341,57 @@ pub fn strip_jsonc_comments(content: &str) -> String {
     result
 }
 
+/// Remove trailing commas before `}` or `]`, preserving string literals.
+///
+/// JSONC (devcontainer.json) allows trailing commas; strict JSON parsers do not.
+fn strip_trailing_commas(content: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            result.push(c);
+            if c == '\\' {
+                if let Some(next) = chars.next() {
+                    result.push(next);
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == ',' {
+            // Look past whitespace: drop the comma if the next token closes a container.
+            let mut ws = String::new();
+            while let Some(&n) = chars.peek() {
+                if n.is_whitespace() {
+                    ws.push(n);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            if !matches!(chars.peek(), Some('}') | Some(']')) {
+                result.push(',');
+            }
+            result.push_str(&ws);
+        } else {
+            if c == '"' {
+                in_string = true;
+            }
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Parse devcontainer.json (JSONC) content into a JSON value.
+///
+/// Strips comments and trailing commas first. Returns `None` if the result is not valid JSON.
+pub fn parse_jsonc(content: &str) -> Option<serde_json::Value> {
+    let clean = strip_trailing_commas(&strip_jsonc_comments(content));
+    serde_json::from_str(&clean).ok()
+}
+
 /// Extract the top-level `"image"` field value from devcontainer JSON content.
 ///
 /// Strips JSONC comments first so that commented-out `"image"` keys are ignored.
@@ -402,6 +508,49 @@ pub fn find_devcontainer_by_workspace(mount_point: &Path) -> Option<String> {
     if id.is_empty() { None } else { Some(id) }
 }
 
+/// Find where the relay `mount_point` is mounted inside a container.
+///
+/// This is the container's effective `workspaceFolder` root, including configs that
+/// remap it (e.g. `/workspace`). Returns `None` if the inspect fails or no mount matches.
+pub fn container_workspace_folder(container_id: &str, mount_point: &Path) -> Option<String> {
+    let mount_str = mount_point.to_string_lossy();
+    let format = format!(
+        "--format={{{{range .Mounts}}}}{{{{if eq .Source \"{mount_str}\"}}}}{{{{.Destination}}}}\n{{{{end}}}}{{{{end}}}}"
+    );
+    let out = cmd::run_capture("docker", &["inspect", &format, container_id]).ok()?;
+    if out.status != 0 {
+        return None;
+    }
+    out.stdout
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .map(str::to_string)
+}
+
+/// Read a container's configured environment (`Config.Env`) as key/value pairs.
+///
+/// Returns an empty list if the inspect fails.
+pub fn container_env(container_id: &str) -> Vec<(String, String)> {
+    let Ok(out) = cmd::run_capture(
+        "docker",
+        &["inspect", "--format={{json .Config.Env}}", container_id],
+    ) else {
+        return Vec::new();
+    };
+    if out.status != 0 {
+        return Vec::new();
+    }
+    serde_json::from_str::<Vec<String>>(out.stdout.trim())
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|e| {
+            e.split_once('=')
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+        })
+        .collect()
+}
+
 /// Find all dcx-managed stopped containers and remove them.
 ///
 /// This finds containers with devcontainer labels matching the naming pattern
@@ -483,6 +632,16 @@ pub fn is_build_image_tag(name: &str) -> bool {
     repo.starts_with("vsc-") && !repo.ends_with("-uid")
 }
 
+/// Derive the build image name from a runtime image name (inverse of
+/// `build_image_to_runtime_image`): `vsc-X-uid:tag` → `vsc-X:tag`.
+pub fn runtime_image_to_build_image(runtime_image: &str) -> String {
+    let (repo, tag) = match runtime_image.find(':') {
+        Some(pos) => runtime_image.split_at(pos),
+        None => (runtime_image, ""),
+    };
+    format!("{}{tag}", repo.strip_suffix("-uid").unwrap_or(repo))
+}
+
 /// Derive the corresponding runtime image name from a build image name.
 ///
 /// Build image `vsc-X:tag` → runtime image `vsc-X-uid:tag`
@@ -749,10 +908,306 @@ pub fn clean_all_dcx_volumes() -> Result<usize, String> {
     Ok(removed)
 }
 
+/// Run `docker inspect --format=<template>` on a container and return the trimmed output.
+///
+/// Returns `None` if the inspect fails or prints nothing.
+fn inspect_field(container_id: &str, template: &str) -> Option<String> {
+    let format = format!("--format={template}");
+    let out = cmd::run_capture("docker", &["inspect", &format, container_id]).ok()?;
+    let value = out.stdout.trim();
+    if out.status != 0 || value.is_empty() || value == "<no value>" {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+/// Container state as reported by Docker (e.g. `running`, `exited`).
+pub fn container_state(container_id: &str) -> Option<String> {
+    inspect_field(container_id, "{{.State.Status}}")
+}
+
+/// Image the container was created from, as named in its config (e.g. `vsc-proj-abc-uid`).
+pub fn container_image(container_id: &str) -> Option<String> {
+    inspect_field(container_id, "{{.Config.Image}}")
+}
+
+/// When the container was created, as a Unix timestamp.
+pub fn container_created(container_id: &str) -> Option<u64> {
+    inspect_field(container_id, "{{.Created}}").and_then(|t| crate::duration::parse_timestamp(&t))
+}
+
+/// When the container was last started, as a Unix timestamp (`None` if it never started).
+pub fn container_started_at(container_id: &str) -> Option<u64> {
+    inspect_field(container_id, "{{.State.StartedAt}}")
+        .and_then(|t| crate::duration::parse_timestamp(&t))
+}
+
+/// A bind mount of a container.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BindMount {
+    /// Host (VM) path.
+    pub source: String,
+    /// Path inside the container.
+    pub destination: String,
+    /// Whether the mount is writable.
+    pub rw: bool,
+}
+
+/// Parse the bind mounts out of `docker inspect --format={{json .Mounts}}` output.
+pub fn parse_bind_mounts(json: &str) -> Vec<BindMount> {
+    let Ok(serde_json::Value::Array(mounts)) = serde_json::from_str(json.trim()) else {
+        return Vec::new();
+    };
+    mounts
+        .iter()
+        .filter(|m| m["Type"] == "bind")
+        .filter_map(|m| {
+            Some(BindMount {
+                source: m["Source"].as_str()?.to_string(),
+                destination: m["Destination"].as_str()?.to_string(),
+                rw: m["RW"].as_bool().unwrap_or(false),
+            })
+        })
+        .collect()
+}
+
+/// Bind mounts of a container (empty if the inspect fails).
+pub fn container_bind_mounts(container_id: &str) -> Vec<BindMount> {
+    inspect_field(container_id, "{{json .Mounts}}")
+        .map(|json| parse_bind_mounts(&json))
+        .unwrap_or_default()
+}
+
+/// Live resource usage of one container, as printed by `docker stats`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContainerStats {
+    /// Short container ID.
+    pub id: String,
+    /// CPU usage (e.g. `12.34%`).
+    pub cpu_percent: String,
+    /// Memory usage and limit (e.g. `512MiB / 7.7GiB`).
+    pub memory: String,
+    /// Number of processes.
+    pub pids: String,
+    /// Network received / sent (e.g. `1.2MB / 340kB`).
+    pub net_io: String,
+}
+
+/// Parse one line of `docker stats --format "{{.ID}}\t{{.CPUPerc}}\t{{.MemUsage}}\t{{.PIDs}}\t{{.NetIO}}"`.
+pub fn parse_stats_line(line: &str) -> Option<ContainerStats> {
+    let mut fields = line.split('\t').map(str::trim);
+    let stats = ContainerStats {
+        id: fields.next().filter(|id| !id.is_empty())?.to_string(),
+        cpu_percent: fields.next()?.to_string(),
+        memory: fields.next()?.to_string(),
+        pids: fields.next()?.to_string(),
+        net_io: fields.next()?.to_string(),
+    };
+    Some(stats)
+}
+
+/// Take a single `docker stats` sample for the given running containers.
+///
+/// Containers that stopped in the meantime are simply missing from the result.
+pub fn container_stats(container_ids: &[String]) -> Vec<ContainerStats> {
+    if container_ids.is_empty() {
+        return vec![];
+    }
+    let mut args = vec![
+        "stats",
+        "--no-stream",
+        "--format",
+        "{{.ID}}\t{{.CPUPerc}}\t{{.MemUsage}}\t{{.PIDs}}\t{{.NetIO}}",
+    ];
+    args.extend(container_ids.iter().map(String::as_str));
+    match cmd::run_capture("docker", &args) {
+        Ok(out) => out.stdout.lines().filter_map(parse_stats_line).collect(),
+        Err(_) => vec![],
+    }
+}
+
+/// Parse one line of `docker ps --size --format "{{.ID}}\t{{.Size}}"` into
+/// `(id, writable layer size)`. Docker appends the virtual size in parentheses; it is dropped.
+pub fn parse_size_line(line: &str) -> Option<(String, String)> {
+    let (id, size) = line.split_once('\t')?;
+    let id = id.trim();
+    if id.is_empty() {
+        return None;
+    }
+    let writable = size.split(" (").next().unwrap_or(size).trim();
+    Some((id.to_string(), writable.to_string()))
+}
+
+/// Writable layer size of every container (running or stopped), keyed by short ID.
+pub fn container_writable_sizes() -> Vec<(String, String)> {
+    match cmd::run_capture(
+        "docker",
+        &["ps", "-a", "--size", "--format", "{{.ID}}\t{{.Size}}"],
+    ) {
+        Ok(out) => out.stdout.lines().filter_map(parse_size_line).collect(),
+        Err(_) => vec![],
+    }
+}
+
+/// A local image tag with its size, as listed by `docker images`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageInfo {
+    /// `repository:tag`.
+    pub reference: String,
+    /// Short image ID (shared by tags that alias the same image).
+    pub id: String,
+    /// Size as printed by Docker (e.g. `1.21GB`).
+    pub size: String,
+}
+
+/// Parse one line of `docker images --format "{{.Repository}}:{{.Tag}}\t{{.ID}}\t{{.Size}}"`.
+pub fn parse_image_line(line: &str) -> Option<ImageInfo> {
+    let mut fields = line.split('\t').map(str::trim);
+    let info = ImageInfo {
+        reference: fields.next().filter(|r| !r.is_empty())?.to_string(),
+        id: fields.next()?.to_string(),
+        size: fields.next()?.to_string(),
+    };
+    Some(info)
+}
+
+/// All local image tags with their sizes.
+pub fn list_images() -> Vec<ImageInfo> {
+    match cmd::run_capture(
+        "docker",
+        &[
+            "images",
+            "--format",
+            "{{.Repository}}:{{.Tag}}\t{{.ID}}\t{{.Size}}",
+        ],
+    ) {
+        Ok(out) => out.stdout.lines().filter_map(parse_image_line).collect(),
+        Err(_) => vec![],
+    }
+}
+
+/// Whether an image tag is owned by dcx: a `dcx-base:*` tag or a devcontainer runtime image.
+pub fn is_dcx_image(reference: &str) -> bool {
+    reference.starts_with(&format!("{BASE_IMAGE_REPO}:")) || is_runtime_image_tag(reference)
+}
+
+/// Parse the "Local Volumes space usage" table of `docker system df -v` into
+/// `(volume name, size)` pairs.
+pub fn parse_df_volumes(text: &str) -> Vec<(String, String)> {
+    let mut volumes = Vec::new();
+    let mut in_section = false;
+    for line in text.lines() {
+        if line.starts_with("Local Volumes space usage") {
+            in_section = true;
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.is_empty() {
+            if !volumes.is_empty() {
+                break;
+            }
+            continue;
+        }
+        if fields[0] == "VOLUME" || fields.len() < 2 {
+            continue;
+        }
+        volumes.push((fields[0].to_string(), fields[fields.len() - 1].to_string()));
+    }
+    volumes
+}
+
+/// Size of every local volume, from `docker system df -v`.
+pub fn volume_sizes() -> Vec<(String, String)> {
+    match cmd::run_capture("docker", &["system", "df", "-v"]) {
+        Ok(out) if out.status == 0 => parse_df_volumes(&out.stdout),
+        _ => vec![],
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // --- parse_bind_mounts ---
+
+    #[test]
+    fn parse_bind_mounts_keeps_only_bind_mounts() {
+        let json = r#"[
+            {"Type":"bind","Source":"/home/u/.colima-mounts/.dcx-a-1-files/.gitconfig","Destination":"/home/u/.gitconfig","RW":false},
+            {"Type":"volume","Name":"dcx-cache","Source":"/var/lib/docker/volumes/x","Destination":"/cache","RW":true},
+            {"Type":"bind","Source":"/home/u/.claude","Destination":"/home/node/.claude","RW":true}
+        ]"#;
+        let mounts = parse_bind_mounts(json);
+        assert_eq!(mounts.len(), 2);
+        assert_eq!(mounts[0].destination, "/home/u/.gitconfig");
+        assert!(!mounts[0].rw);
+        assert!(mounts[1].rw);
+        assert!(parse_bind_mounts("not json").is_empty());
+    }
+
+    // --- resource usage parsers ---
+
+    #[test]
+    fn parse_stats_line_splits_tab_separated_fields() {
+        let stats = parse_stats_line("abc123\t12.34%\t512MiB / 7.7GiB\t42\t1.2MB / 340kB").unwrap();
+        assert_eq!(stats.id, "abc123");
+        assert_eq!(stats.cpu_percent, "12.34%");
+        assert_eq!(stats.memory, "512MiB / 7.7GiB");
+        assert_eq!(stats.pids, "42");
+        assert_eq!(stats.net_io, "1.2MB / 340kB");
+        assert_eq!(parse_stats_line("abc123\t1%"), None);
+        assert_eq!(parse_stats_line(""), None);
+    }
+
+    #[test]
+    fn parse_size_line_drops_virtual_size() {
+        assert_eq!(
+            parse_size_line("abc123\t12.3kB (virtual 1.2GB)"),
+            Some(("abc123".to_string(), "12.3kB".to_string()))
+        );
+        assert_eq!(parse_size_line("no-tab"), None);
+    }
+
+    #[test]
+    fn parse_image_line_reads_reference_id_and_size() {
+        let info = parse_image_line("dcx-base:dcx-a-1\tsha1234\t1.21GB").unwrap();
+        assert_eq!(info.reference, "dcx-base:dcx-a-1");
+        assert_eq!(info.id, "sha1234");
+        assert_eq!(info.size, "1.21GB");
+    }
+
+    #[test]
+    fn is_dcx_image_matches_base_tags_and_runtime_images() {
+        assert!(is_dcx_image("dcx-base:dcx-a-1"));
+        assert!(is_dcx_image("vsc-proj-abc-uid:latest"));
+        assert!(!is_dcx_image("vsc-proj-abc:latest"));
+        assert!(!is_dcx_image("ubuntu:22.04"));
+    }
+
+    #[test]
+    fn parse_df_volumes_reads_only_volume_section() {
+        let text = "Images space usage:\n\n\
+                    REPOSITORY TAG IMAGE ID CREATED SIZE SHARED SIZE UNIQUE SIZE CONTAINERS\n\
+                    ubuntu 22.04 abc 2 weeks ago 77MB 0B 77MB 1\n\n\
+                    Local Volumes space usage:\n\n\
+                    VOLUME NAME LINKS SIZE\n\
+                    dcx-cache-1 1 540MB\n\
+                    other 0 0B\n\n\
+                    Build cache usage: 0B\n";
+        assert_eq!(
+            parse_df_volumes(text),
+            vec![
+                ("dcx-cache-1".to_string(), "540MB".to_string()),
+                ("other".to_string(), "0B".to_string()),
+            ]
+        );
+        assert!(parse_df_volumes("").is_empty());
+    }
+
     // --- extract_image_field ---
 
     #[test]
@@ -817,6 +1272,19 @@ mod tests {
         assert_eq!(extract_image_field(json), Some("correct:tag".to_string()));
     }
 
+    #[test]
+    fn parse_jsonc_accepts_comments_and_trailing_commas() {
+        let input = "{\n  // comment\n  \"a\": [1, 2,],\n  \"b\": {\"c\": \"x,}\",},\n}";
+        let value = parse_jsonc(input).unwrap();
+        assert_eq!(value["a"], serde_json::json!([1, 2]));
+        assert_eq!(value["b"]["c"], "x,}");
+    }
+
+    #[test]
+    fn parse_jsonc_invalid_returns_none() {
+        assert!(parse_jsonc("{ not json").is_none());
+    }
+
     #[test]
     fn strip_jsonc_comments_removes_line_comments() {
         let input = "{\n  // this is a comment\n  \"key\": \"value\"\n}";
@@ -1025,6 +1493,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn runtime_image_to_build_image_inverts_the_uid_suffix() {
+        for build in ["vsc-dcx-a1b2c3d4", "vsc-dcx-a1b2c3d4:latest"] {
+            assert_eq!(
+                runtime_image_to_build_image(&build_image_to_runtime_image(build)),
+                build
+            );
+        }
+        assert_eq!(runtime_image_to_build_image("ubuntu:22.04"), "ubuntu:22.04");
+    }
+
     // --- get_base_image_name ---
 
     #[test]
@@ -201,8 +201,12 @@ pub fn run_doctor(home: &Path) -> i32 {
         check_relay_in_vm(home),
     ];
     let all_passed = checks.iter().all(|c| c.passed);
-    let report = crate::format::format_doctor_report(&checks);
-    println!("{report}");
+    if crate::output::is_structured() {
+        crate::output::emit(&serde_json::json!({ "all_passed": all_passed, "checks": checks }));
+    } else {
+        let report = crate::format::format_doctor_report(&checks);
+        println!("{report}");
+    }
     if all_passed {
         exit_codes::SUCCESS
     } else {
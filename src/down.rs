@@ -1,16 +1,24 @@
 #![allow(dead_code)]
 
+use std::io::{self, BufRead, Write};
 use std::path::{Path, PathBuf};
-use std::sync::atomic::Ordering;
+use std::sync::atomic::{AtomicBool, Ordering};
 
+use crate::clean::confirm_prompt;
 use crate::cmd;
+use crate::daemon;
 use crate::docker;
 use crate::exit_codes;
+use crate::format::{CleanEntry, format_down_summary};
+use crate::lifetime;
 use crate::mount_table;
-use crate::naming::{is_dcx_managed_path, mount_name, relay_dir};
+use crate::naming::{is_dcx_managed_path, mount_name, relay_dir, scan_relay};
+use crate::output;
 use crate::platform;
 use crate::progress;
 use crate::signals;
+use crate::state;
+use crate::status::mount_state_label;
 use crate::up::{staging_dir, tilde_path};
 use crate::workspace::resolve_workspace;
 
@@ -26,6 +34,15 @@ pub fn workspace_missing_error() -> &'static str {
     "Workspace directory does not exist. Use `dcx clean` to remove stale mounts."
 }
 
+/// Action reported in the `--output json` result of a completed `dcx down`.
+pub fn down_action(had_container: bool) -> &'static str {
+    if had_container {
+        "stopped, removed, unmounted"
+    } else {
+        "unmounted"
+    }
+}
+
 // ── Entry point ───────────────────────────────────────────────────────────────
 
 /// Run `dcx down`.
@@ -71,74 +88,112 @@ pub fn run_down(home: &Path, workspace_folder: Option<PathBuf>) -> i32 {
     let name = mount_name(&workspace);
     let mount_point = relay.join(&name);
 
+    // 6-9b. Take the workspace down (nothing to do if there is no mount and no container).
+    let entry = match down_mount(
+        home,
+        &mount_point,
+        Some(workspace.to_string_lossy().into_owned()),
+        &interrupted,
+    ) {
+        Ok(entry) => entry,
+        Err(e) => {
+            eprintln!("{e}");
+            return exit_codes::RUNTIME_ERROR;
+        }
+    };
+    if entry.action == "none" {
+        if output::is_structured() {
+            output::emit(&entry);
+        } else {
+            println!("{}", nothing_to_do(&workspace));
+        }
+        return exit_codes::SUCCESS;
+    }
+
+    if interrupted.load(Ordering::Relaxed) {
+        return exit_codes::RUNTIME_ERROR;
+    }
+
+    progress::step("Done.");
+    if output::is_structured() {
+        output::emit(&entry);
+    }
+    exit_codes::SUCCESS
+}
+
+/// Take one workspace down: stop and remove its containers, stop the sync daemon,
+/// unmount, and remove the mount and staging directories.
+///
+/// Returns the result as a `CleanEntry` (`was: "not found"`, `action: "none"` when there
+/// is no mount and no container). If `interrupted` is set during the container stop,
+/// the unmount is still completed; the caller decides the exit code.
+fn down_mount(
+    home: &Path,
+    mount_point: &Path,
+    workspace: Option<String>,
+    interrupted: &AtomicBool,
+) -> Result<CleanEntry, String> {
+    let name = mount_point
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
     // 6. If no mount AND no container: nothing to do.
     let table = platform::read_mount_table().unwrap_or_default();
-    let source_in_table = mount_table::find_mount_source(&table, &mount_point);
-    let containers = docker::query_container_any(&mount_point);
+    let source_in_table = mount_table::find_mount_source(&table, mount_point);
+    let containers = docker::query_container_any(mount_point);
     if source_in_table.is_none() && containers.is_empty() {
-        println!("{}", nothing_to_do(&workspace));
-        return exit_codes::SUCCESS;
+        return Ok(CleanEntry {
+            workspace,
+            mount: name,
+            was: "not found".to_string(),
+            action: "none".to_string(),
+        });
     }
+    let had_container = !containers.is_empty();
+    let running = docker::query_container(mount_point).is_some();
+    let was = mount_state_label(
+        source_in_table.is_some(),
+        running,
+        had_container && !running,
+    );
 
     // 7. Stop + remove the container using Docker.
     // Note: docker::stop_container uses run_capture (not run_stream), so SIGINT is not
     // forwarded to docker stop. Check interrupted flag after the call returns.
     progress::step("Stopping devcontainer...");
-    if let Err(e) = docker::stop_container(&mount_point) {
-        eprintln!("{e}");
-        return exit_codes::RUNTIME_ERROR;
-    }
+    docker::stop_container(mount_point)?;
     for container_id in containers {
-        if let Err(e) = docker::remove_container(&container_id) {
-            eprintln!("{e}");
-            return exit_codes::RUNTIME_ERROR;
-        }
+        docker::remove_container(&container_id)?;
     }
 
-    // 7b. Kill sync daemon if it's running.
-    // The daemon writes its PID to .sync-daemon.pid in the staging dir.
-    let staging = staging_dir(&mount_point);
-    let pid_file = staging.join(".sync-daemon.pid");
-    if let Ok(pid_str) = std::fs::read_to_string(&pid_file) {
-        let pid = pid_str.trim();
-        if !pid.is_empty() {
-            let _ = std::process::Command::new("kill")
-                .arg("-TERM")
-                .arg(pid)
-                .status();
-            // Brief wait for daemon to remove its own PID file
-            std::thread::sleep(std::time::Duration::from_millis(200));
-        }
-    }
+    // 7b. Stop syncing: unregister from the dcx daemon (and stop a legacy per-workspace
+    // sync daemon, if any).
+    daemon::stop_sync(home, mount_point);
+    let staging = staging_dir(mount_point);
 
     // 8. Unmount bindfs. If SIGINT arrived between steps 7 and 8 (or during unmount),
     // log the message and complete the unmount before exiting.
-    let was_interrupted = interrupted.load(Ordering::Relaxed);
-    if was_interrupted {
+    if interrupted.load(Ordering::Relaxed) {
         eprintln!("Signal received, finishing unmount...");
     }
-    let tilde_mp = tilde_path(&mount_point, home);
+    let tilde_mp = tilde_path(mount_point, home);
     progress::step(&format!("Unmounting {tilde_mp}..."));
     let prog = platform::unmount_prog();
-    let args = platform::unmount_args(&mount_point);
+    let args = platform::unmount_args(mount_point);
     let args_str: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-    match cmd::run_capture(prog, &args_str) {
-        Ok(out) if out.status != 0 => {
-            eprintln!("{prog} failed (exit {}): {}", out.status, out.stderr.trim());
-            return exit_codes::RUNTIME_ERROR;
-        }
-        Err(e) => {
-            eprintln!("{e}");
-            return exit_codes::RUNTIME_ERROR;
-        }
-        Ok(_) => {}
+    let out = cmd::run_capture(prog, &args_str)?;
+    if out.status != 0 {
+        return Err(format!(
+            "{prog} failed (exit {}): {}",
+            out.status,
+            out.stderr.trim()
+        ));
     }
 
     // 9. Remove mount directory.
-    if let Err(e) = std::fs::remove_dir(&mount_point) {
-        eprintln!("Failed to remove {}: {e}", mount_point.display());
-        return exit_codes::RUNTIME_ERROR;
-    }
+    std::fs::remove_dir(mount_point)
+        .map_err(|e| format!("Failed to remove {}: {e}", mount_point.display()))?;
 
     // 9b. Remove staging directory for this workspace (non-fatal).
     if staging.exists()
@@ -150,12 +205,140 @@ pub fn run_down(home: &Path, workspace_folder: Option<PathBuf>) -> i32 {
         );
     }
 
-    if was_interrupted {
+    lifetime::clear(home, &name);
+    state::record_event(home, &name, "down: container removed, workspace unmounted");
+    Ok(CleanEntry {
+        workspace,
+        mount: name,
+        was: was.to_string(),
+        action: down_action(had_container).to_string(),
+    })
+}
+
+/// Run `dcx down --all` or `dcx down <ws>...`: take several workspaces down.
+///
+/// Running containers are listed and confirmed (unless `yes`). Unlike
+/// `dcx clean --all`, images, volumes and mount history are left alone.
+/// Continues on failure; returns `RUNTIME_ERROR` if any workspace failed.
+pub fn run_down_many(home: &Path, workspaces: Vec<PathBuf>, all: bool, yes: bool) -> i32 {
+    let interrupted = signals::interrupted_flag();
+
+    // Resolve the named workspaces before touching Docker, so typos fail fast.
+    let relay = relay_dir(home);
+    let mut named = Vec::new();
+    for ws in &workspaces {
+        let workspace = match resolve_workspace(Some(ws)) {
+            Ok(p) => p,
+            Err(_) => {
+                eprintln!("{}: {}", ws.display(), workspace_missing_error());
+                return exit_codes::USAGE_ERROR;
+            }
+        };
+        if is_dcx_managed_path(&workspace, &relay) {
+            eprintln!(
+                "Cannot use a dcx-managed mount point as a workspace. \
+                 Use the original workspace path instead."
+            );
+            return exit_codes::USAGE_ERROR;
+        }
+        named.push(workspace);
+    }
+
+    if !docker::is_docker_available() {
+        eprintln!("Docker is not available. Is Colima running?");
         return exit_codes::RUNTIME_ERROR;
     }
 
-    progress::step("Done.");
-    exit_codes::SUCCESS
+    // (mount point, workspace display) of every target.
+    progress::step("Scanning workspaces...");
+    let table = platform::read_mount_table().unwrap_or_default();
+    let targets: Vec<(PathBuf, Option<String>)> = if all {
+        scan_relay(&relay)
+            .into_iter()
+            .filter(|mp| {
+                mount_table::find_mount_source(&table, mp).is_some()
+                    || !docker::query_container_any(mp).is_empty()
+            })
+            .map(|mp| {
+                let ws = mount_table::find_mount_source(&table, &mp).map(str::to_string);
+                (mp, ws)
+            })
+            .collect()
+    } else {
+        named
+            .iter()
+            .map(|ws| {
+                (
+                    relay.join(mount_name(ws)),
+                    Some(ws.to_string_lossy().into_owned()),
+                )
+            })
+            .collect()
+    };
+    if all && targets.is_empty() {
+        if output::is_structured() {
+            output::emit(&serde_json::json!({ "workspaces": [] }));
+        } else {
+            println!("No active workspaces.");
+        }
+        return exit_codes::SUCCESS;
+    }
+
+    // Confirm before stopping running containers.
+    let running: Vec<(String, String, String)> = targets
+        .iter()
+        .filter_map(|(mp, ws)| {
+            let container = docker::query_container(mp)?;
+            let mount = mp.file_name()?.to_string_lossy().into_owned();
+            Some((
+                ws.clone().unwrap_or_else(|| "(unknown)".to_string()),
+                mount,
+                container,
+            ))
+        })
+        .collect();
+    if !running.is_empty() && !yes {
+        eprintln!("{}", confirm_prompt(&running));
+        eprint!("\nContinue? [y/N] ");
+        let _ = io::stderr().flush();
+        let mut input = String::new();
+        if io::stdin().lock().read_line(&mut input).is_err() {
+            return exit_codes::RUNTIME_ERROR;
+        }
+        if !matches!(input.trim().to_ascii_lowercase().as_str(), "y" | "yes") {
+            return exit_codes::USER_ABORTED;
+        }
+    }
+
+    let mut entries: Vec<CleanEntry> = Vec::new();
+    let mut failures: Vec<String> = Vec::new();
+    for (mount_point, ws) in targets {
+        progress::step(&format!(
+            "Taking down {}...",
+            ws.as_deref().unwrap_or(&mount_point.to_string_lossy())
+        ));
+        match down_mount(home, &mount_point, ws, &interrupted) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => failures.push(format!("{}: {e}", mount_point.display())),
+        }
+        if interrupted.load(Ordering::Relaxed) {
+            break;
+        }
+    }
+
+    if output::is_structured() {
+        output::emit(&serde_json::json!({ "workspaces": entries }));
+    } else {
+        println!("{}", format_down_summary(&entries));
+    }
+    for f in &failures {
+        eprintln!("Error: {f}");
+    }
+    if failures.is_empty() && !interrupted.load(Ordering::Relaxed) {
+        exit_codes::SUCCESS
+    } else {
+        exit_codes::RUNTIME_ERROR
+    }
 }
 
 #[cfg(test)]
@@ -173,6 +356,14 @@ mod tests {
         assert!(msg.contains("Nothing to do"), "got: {msg}");
     }
 
+    // --- down_action ---
+
+    #[test]
+    fn down_action_mentions_container_only_when_present() {
+        assert_eq!(down_action(true), "stopped, removed, unmounted");
+        assert_eq!(down_action(false), "unmounted");
+    }
+
     // --- workspace_missing_error ---
 
     #[test]
@@ -0,0 +1,298 @@
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use crate::colima::{self, ColimaMount};
+use crate::docker;
+
+/// Container label holding the configuration fingerprint recorded at `dcx up` time.
+pub const CONFIG_HASH_LABEL: &str = "dcx.config-hash";
+
+/// Hash recorded for an input file that does not exist.
+const MISSING: &str = "-";
+
+// ── Pure functions ────────────────────────────────────────────────────────────
+
+/// Per-input hashes of the configuration a container was created from.
+///
+/// Label form: `devcontainer.json=1a2b3c4d,Dockerfile=-,dcx_config.yaml=…,colima.yaml=…`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigFingerprint(pub Vec<(String, String)>);
+
+impl ConfigFingerprint {
+    /// Serialize for the container label.
+    pub fn to_label(&self) -> String {
+        self.0
+            .iter()
+            .map(|(name, hash)| format!("{name}={hash}"))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Parse a label written by `to_label`. Returns `None` for anything else.
+    pub fn parse_label(label: &str) -> Option<Self> {
+        let entries = label
+            .split(',')
+            .map(|entry| {
+                let (name, hash) = entry.split_once('=')?;
+                (!name.is_empty() && !hash.is_empty()).then(|| (name.to_string(), hash.to_string()))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        (!entries.is_empty()).then_some(Self(entries))
+    }
+
+    /// Inputs whose hash differs from (or is absent in) `recorded`, in `self` order.
+    pub fn changed_since(&self, recorded: &ConfigFingerprint) -> Vec<String> {
+        self.0
+            .iter()
+            .filter(|(name, hash)| {
+                recorded
+                    .0
+                    .iter()
+                    .find(|(n, _)| n == name)
+                    .is_none_or(|(_, h)| h != hash)
+            })
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+}
+
+/// Short content hash of one input (`-` when missing).
+pub fn content_hash(content: Option<&[u8]>) -> String {
+    match content {
+        Some(bytes) => Sha256::digest(bytes)
+            .iter()
+            .take(4)
+            .map(|b| format!("{b:02x}"))
+            .collect(),
+        None => MISSING.to_string(),
+    }
+}
+
+/// Canonical text of the colima mounts dcx injects: one `location writable` line per mount,
+/// sorted so reordering colima.yaml does not count as a change.
+pub fn colima_mounts_text(mounts: &[ColimaMount]) -> String {
+    let mut lines: Vec<String> = mounts
+        .iter()
+        .map(|m| format!("{} {}", m.location, m.writable))
+        .collect();
+    lines.sort();
+    lines.join("\n")
+}
+
+/// Dockerfile referenced by devcontainer.json (`build.dockerfile`, or the legacy
+/// `dockerFile`), resolved relative to the devcontainer.json directory.
+pub fn dockerfile_path(devcontainer_json: &Path, content: &str) -> Option<PathBuf> {
+    let value = docker::parse_jsonc(content)?;
+    let dockerfile = value
+        .get("build")
+        .and_then(|b| b.get("dockerfile"))
+        .or_else(|| value.get("dockerFile"))
+        .and_then(|d| d.as_str())?;
+    Some(devcontainer_json.parent()?.join(dockerfile))
+}
+
+/// Decide whether a container is outdated.
+///
+/// Returns the changed inputs, or an empty list when the container is current or was
+/// created before dcx recorded fingerprints (no or unparsable label).
+pub fn drifted_inputs(label: Option<&str>, current: &ConfigFingerprint) -> Vec<String> {
+    match label.and_then(ConfigFingerprint::parse_label) {
+        Some(recorded) => current.changed_since(&recorded),
+        None => Vec::new(),
+    }
+}
+
+// ── Filesystem ────────────────────────────────────────────────────────────────
+
+/// Fingerprint the configuration `dcx up` would use for a container.
+///
+/// `devcontainer_json` is the config in use; `dcx_config.yaml` is looked up next to it,
+/// matching `up::find_dcx_config_path`.
+pub fn fingerprint(home: &Path, devcontainer_json: Option<&Path>) -> ConfigFingerprint {
+    let json = devcontainer_json.and_then(|p| std::fs::read_to_string(p).ok());
+    let dockerfile = devcontainer_json
+        .zip(json.as_deref())
+        .and_then(|(path, content)| dockerfile_path(path, content));
+    let read = |path: Option<PathBuf>| path.and_then(|p| std::fs::read(p).ok());
+    let dcx_config =
+        read(devcontainer_json.and_then(|p| p.parent().map(|d| d.join("dcx_config.yaml"))));
+    let colima = std::fs::read_to_string(colima::colima_config_path(home))
+        .ok()
+        .map(|yaml| {
+            colima_mounts_text(&colima::filter_relay_mounts(colima::parse_colima_mounts(
+                &yaml,
+            )))
+        });
+    ConfigFingerprint(vec![
+        (
+            "devcontainer.json".to_string(),
+            content_hash(json.as_deref().map(str::as_bytes)),
+        ),
+        (
+            "Dockerfile".to_string(),
+            content_hash(read(dockerfile).as_deref()),
+        ),
+        (
+            "dcx_config.yaml".to_string(),
+            content_hash(dcx_config.as_deref()),
+        ),
+        (
+            "colima.yaml".to_string(),
+            content_hash(colima.as_deref().map(str::as_bytes)),
+        ),
+    ])
+}
+
+/// Map the devcontainer config path recorded on a container (which lives under the relay
+/// mount) back to the host path.
+pub fn host_config_path(label: &str, mount_point: &Path, workspace: &Path) -> PathBuf {
+    match Path::new(label).strip_prefix(mount_point) {
+        Ok(rel) => workspace.join(rel),
+        Err(_) => PathBuf::from(label),
+    }
+}
+
+/// Configuration inputs that changed since `container_id` was created (empty if current).
+///
+/// The devcontainer.json is taken from the container's `devcontainer.config_file` label
+/// (so `--config-dir` is honoured), falling back to the workspace's auto-detected config.
+pub fn container_drift(
+    home: &Path,
+    container_id: &str,
+    mount_point: &Path,
+    workspace: &Path,
+) -> Vec<String> {
+    let label = docker::read_label(container_id, CONFIG_HASH_LABEL);
+    if label.is_none() {
+        return Vec::new();
+    }
+    let config = docker::read_label(container_id, "devcontainer.config_file")
+        .map(|l| host_config_path(&l, mount_point, workspace))
+        .or_else(|| crate::workspace::find_devcontainer_config(workspace));
+    drifted_inputs(label.as_deref(), &fingerprint(home, config.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fp(entries: &[(&str, &str)]) -> ConfigFingerprint {
+        ConfigFingerprint(
+            entries
+                .iter()
+                .map(|(n, h)| (n.to_string(), h.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn label_round_trips() {
+        let f = fp(&[("devcontainer.json", "1a2b3c4d"), ("Dockerfile", "-")]);
+        assert_eq!(f.to_label(), "devcontainer.json=1a2b3c4d,Dockerfile=-");
+        assert_eq!(ConfigFingerprint::parse_label(&f.to_label()), Some(f));
+    }
+
+    #[test]
+    fn parse_label_rejects_malformed_values() {
+        assert_eq!(ConfigFingerprint::parse_label(""), None);
+        assert_eq!(ConfigFingerprint::parse_label("abc"), None);
+        assert_eq!(ConfigFingerprint::parse_label("a=1,b"), None);
+    }
+
+    #[test]
+    fn changed_since_lists_changed_and_new_inputs() {
+        let recorded = fp(&[("devcontainer.json", "aa"), ("Dockerfile", "bb")]);
+        let current = fp(&[
+            ("devcontainer.json", "aa"),
+            ("Dockerfile", "cc"),
+            ("colima.yaml", "dd"),
+        ]);
+        assert_eq!(
+            current.changed_since(&recorded),
+            vec!["Dockerfile", "colima.yaml"]
+        );
+        assert!(recorded.changed_since(&recorded).is_empty());
+    }
+
+    #[test]
+    fn drifted_inputs_ignores_unlabelled_containers() {
+        let current = fp(&[("devcontainer.json", "aa")]);
+        assert!(drifted_inputs(None, &current).is_empty());
+        assert!(drifted_inputs(Some("garbage"), &current).is_empty());
+        assert_eq!(
+            drifted_inputs(Some("devcontainer.json=bb"), &current),
+            vec!["devcontainer.json"]
+        );
+    }
+
+    #[test]
+    fn content_hash_is_short_and_marks_missing() {
+        assert_eq!(content_hash(Some(b"{}")).len(), 8);
+        assert_ne!(content_hash(Some(b"{}")), content_hash(Some(b"{ }")));
+        assert_eq!(content_hash(None), "-");
+    }
+
+    #[test]
+    fn colima_mounts_text_is_order_independent() {
+        let a = ColimaMount {
+            location: "~/.claude".to_string(),
+            writable: true,
+        };
+        let b = ColimaMount {
+            location: "~/.gitconfig".to_string(),
+            writable: false,
+        };
+        assert_eq!(
+            colima_mounts_text(&[a.clone(), b.clone()]),
+            colima_mounts_text(&[b, a])
+        );
+    }
+
+    #[test]
+    fn dockerfile_path_resolves_build_and_legacy_keys() {
+        let json = Path::new("/ws/.devcontainer/devcontainer.json");
+        assert_eq!(
+            dockerfile_path(json, r#"{ "build": { "dockerfile": "Dockerfile" } }"#),
+            Some(PathBuf::from("/ws/.devcontainer/Dockerfile"))
+        );
+        assert_eq!(
+            dockerfile_path(json, r#"{ "dockerFile": "../Dockerfile", }"#),
+            Some(PathBuf::from("/ws/.devcontainer/../Dockerfile"))
+        );
+        assert_eq!(dockerfile_path(json, r#"{ "image": "ubuntu" }"#), None);
+    }
+
+    #[test]
+    fn fingerprint_changes_when_dockerfile_changes() {
+        let home = tempfile::tempdir().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let json = dir.path().join("devcontainer.json");
+        std::fs::write(&json, r#"{ "build": { "dockerfile": "Dockerfile" } }"#).unwrap();
+        std::fs::write(dir.path().join("Dockerfile"), "FROM ubuntu").unwrap();
+        let before = fingerprint(home.path(), Some(&json));
+        std::fs::write(dir.path().join("Dockerfile"), "FROM debian").unwrap();
+        let after = fingerprint(home.path(), Some(&json));
+        assert_eq!(after.changed_since(&before), vec!["Dockerfile"]);
+    }
+
+    #[test]
+    fn host_config_path_maps_relay_path_to_workspace() {
+        let mp = Path::new("/home/u/.colima-mounts/dcx-proj-a1b2c3d4");
+        let ws = Path::new("/home/u/proj");
+        assert_eq!(
+            host_config_path(
+                "/home/u/.colima-mounts/dcx-proj-a1b2c3d4/.devcontainer/devcontainer.json",
+                mp,
+                ws
+            ),
+            PathBuf::from("/home/u/proj/.devcontainer/devcontainer.json")
+        );
+        assert_eq!(
+            host_config_path("/elsewhere/devcontainer.json", mp, ws),
+            PathBuf::from("/elsewhere/devcontainer.json")
+        );
+    }
+}
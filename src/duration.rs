@@ -0,0 +1,185 @@
+#![allow(dead_code)]
+
+// ── Parsing ───────────────────────────────────────────────────────────────────
+
+/// Parse a duration like `30s`, `15m`, `2h`, `7d` or `1w` into seconds.
+///
+/// A bare number is taken as seconds. Returns `Err` for empty, negative or unknown units.
+pub fn parse_duration(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (num, unit) = value.split_at(split);
+    let invalid = || {
+        format!(
+            "Invalid duration '{value}'. Use a number with a unit: s, m, h, d or w (e.g. 2h, 7d)."
+        )
+    };
+    let n: u64 = num.parse().map_err(|_| invalid())?;
+    let scale = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86_400,
+        "w" => 604_800,
+        _ => return Err(invalid()),
+    };
+    n.checked_mul(scale).ok_or_else(invalid)
+}
+
+// ── Formatting ────────────────────────────────────────────────────────────────
+
+/// Format seconds as a compact, single-unit duration: `45s`, `12m`, `3h`, `2d`.
+///
+/// Rounds down to the largest unit that fits.
+pub fn format_duration(secs: u64) -> String {
+    if secs < 60 {
+        format!("{secs}s")
+    } else if secs < 3600 {
+        format!("{}m", secs / 60)
+    } else if secs < 86_400 {
+        format!("{}h", secs / 3600)
+    } else {
+        format!("{}d", secs / 86_400)
+    }
+}
+
+/// Format an elapsed time as `<duration> ago`.
+pub fn format_age(secs: u64) -> String {
+    format!("{} ago", format_duration(secs))
+}
+
+// ── Timestamps ────────────────────────────────────────────────────────────────
+
+/// Days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's algorithm).
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = i64::from((m + 9) % 12);
+    let doy = (153 * mp + 2) / 5 + i64::from(d) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Inverse of `days_from_civil`: (year, month, day).
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    (y, m, d)
+}
+
+/// Format a Unix timestamp as RFC 3339 UTC: `2026-10-18T09:30:00Z`.
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    let (y, m, d) = civil_from_days(days);
+    format!(
+        "{y:04}-{m:02}-{d:02}T{:02}:{:02}:{:02}Z",
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+/// Parse an RFC 3339 UTC timestamp (`YYYY-MM-DDTHH:MM:SS[.frac]Z`) into Unix seconds.
+///
+/// Fractional seconds are truncated. Returns `None` for other formats or offsets.
+pub fn parse_timestamp(value: &str) -> Option<u64> {
+    let value = value.strip_suffix('Z')?;
+    let (date, time) = value.split_once('T')?;
+    let mut date_parts = date.splitn(3, '-');
+    let y: i64 = date_parts.next()?.parse().ok()?;
+    let m: u32 = date_parts.next()?.parse().ok()?;
+    let d: u32 = date_parts.next()?.parse().ok()?;
+    let time = time.split('.').next()?;
+    let mut time_parts = time.splitn(3, ':');
+    let hh: u64 = time_parts.next()?.parse().ok()?;
+    let mm: u64 = time_parts.next()?.parse().ok()?;
+    let ss: u64 = time_parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&m) || !(1..=31).contains(&d) || hh > 23 || mm > 59 || ss > 60 {
+        return None;
+    }
+    let days = u64::try_from(days_from_civil(y, m, d)).ok()?;
+    Some(days * 86_400 + hh * 3600 + mm * 60 + ss)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // --- parse_duration ---
+
+    #[test]
+    fn parse_duration_units() {
+        assert_eq!(parse_duration("30s"), Ok(30));
+        assert_eq!(parse_duration("15m"), Ok(900));
+        assert_eq!(parse_duration("2h"), Ok(7200));
+        assert_eq!(parse_duration("7d"), Ok(604_800));
+        assert_eq!(parse_duration("1w"), Ok(604_800));
+    }
+
+    #[test]
+    fn parse_duration_bare_number_is_seconds() {
+        assert_eq!(parse_duration("90"), Ok(90));
+    }
+
+    #[test]
+    fn parse_duration_rejects_invalid() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("2x").is_err());
+        assert!(parse_duration("-2h").is_err());
+        assert!(parse_duration("2h30m").is_err());
+    }
+
+    // --- format_duration ---
+
+    #[test]
+    fn format_duration_picks_largest_unit() {
+        assert_eq!(format_duration(45), "45s");
+        assert_eq!(format_duration(125), "2m");
+        assert_eq!(format_duration(7300), "2h");
+        assert_eq!(format_duration(200_000), "2d");
+    }
+
+    #[test]
+    fn format_age_appends_ago() {
+        assert_eq!(format_age(120), "2m ago");
+    }
+
+    // --- format_timestamp / parse_timestamp ---
+
+    #[test]
+    fn format_timestamp_is_rfc3339_utc() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(1_700_000_000), "2023-11-14T22:13:20Z");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+    }
+
+    #[test]
+    fn parse_timestamp_round_trips_and_accepts_fraction() {
+        for secs in [0, 951_782_400, 1_700_000_000, 1_792_300_000] {
+            assert_eq!(parse_timestamp(&format_timestamp(secs)), Some(secs));
+        }
+        assert_eq!(
+            parse_timestamp("2023-11-14T22:13:20.123456789Z"),
+            Some(1_700_000_000)
+        );
+    }
+
+    #[test]
+    fn parse_timestamp_rejects_other_formats() {
+        assert_eq!(parse_timestamp("2023-11-14 22:13:20"), None);
+        assert_eq!(parse_timestamp("2023-11-14T22:13:20+01:00"), None);
+        assert_eq!(parse_timestamp("2023-13-14T22:13:20Z"), None);
+    }
+}
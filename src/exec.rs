@@ -4,13 +4,21 @@ use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
 
 use crate::cmd;
+use crate::dcx_config;
 use crate::docker;
 use crate::exit_codes;
+use crate::format::{ExecAllRow, format_exec_all_summary};
 use crate::mount_table;
-use crate::naming::{is_dcx_managed_path, mount_name, relay_dir};
+use crate::naming::{is_dcx_managed_path, mount_name, relay_dir, scan_relay, workspace_labels};
 use crate::platform;
 use crate::progress;
-use crate::workspace::resolve_workspace;
+use crate::remote_env;
+use crate::sessions;
+use crate::state;
+use crate::up::{self, parse_remote_user};
+use crate::workspace::{
+    container_workdir, find_devcontainer_config, find_enclosing_workspace, resolve_workspace,
+};
 
 // ── Pure functions ────────────────────────────────────────────────────────────
 
@@ -40,13 +48,148 @@ pub fn mount_not_found_error(workspace: &Path, mount_dir_exists: bool) -> String
     }
 }
 
+/// `dcx exec` flags that shape the `docker exec` invocation, as given on the CLI.
+#[derive(Debug, Default, Clone)]
+pub struct ExecFlags {
+    /// `--user`: overrides remoteUser from devcontainer.json.
+    pub user: Option<String>,
+    /// `-e KEY=VAL` entries, in order.
+    pub env: Vec<String>,
+    /// `--env-file` paths, in order.
+    pub env_files: Vec<PathBuf>,
+    /// `--workdir`: absolute container path, or relative to the container workspace.
+    pub workdir: Option<String>,
+    /// `--record`: record the interactive session (see `sessions`).
+    pub record: bool,
+}
+
+/// Resolved options for a single `docker exec` invocation.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ExecOptions {
+    /// Working directory inside the container (`-w`).
+    pub workdir: PathBuf,
+    /// User to run as (`-u`); None inherits the container's default user.
+    pub user: Option<String>,
+    /// Environment variables (`-e`), as `KEY=VAL` or `KEY`.
+    pub env: Vec<String>,
+    /// Env files (`--env-file`), read by the docker client on the host.
+    pub env_files: Vec<PathBuf>,
+}
+
+impl ExecOptions {
+    /// Options that only set the working directory.
+    pub fn new(workdir: impl Into<PathBuf>) -> Self {
+        Self {
+            workdir: workdir.into(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Validate a `-e` entry: `KEY=VAL` or bare `KEY` (passed through from the host env).
+///
+/// Keys must be non-empty and consist of letters, digits and `_`, not starting with a digit.
+pub fn validate_env_entry(entry: &str) -> Result<(), String> {
+    let key = entry.split_once('=').map_or(entry, |(k, _)| k);
+    let valid = !key.is_empty()
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid environment variable '{entry}'. Expected KEY=VAL with KEY matching [A-Za-z_][A-Za-z0-9_]*."
+        ))
+    }
+}
+
+/// Variable names set by a docker env file: `KEY=VAL` or bare `KEY` lines; blank lines and
+/// `#` comments are skipped.
+pub fn env_file_keys(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim_start)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| l.split_once('=').map_or(l, |(k, _)| k).trim().to_string())
+        .collect()
+}
+
+/// Drop the implicit entries (remoteEnv, userEnvProbe) whose key the user set explicitly.
+/// docker gives `-e` precedence over `--env-file`, so an implicit `-e` would otherwise
+/// override a value from the user's `--env-file`.
+pub fn without_explicit_keys(implicit: Vec<String>, explicit_keys: &[String]) -> Vec<String> {
+    implicit
+        .into_iter()
+        .filter(|entry| {
+            let key = entry.split_once('=').map_or(entry.as_str(), |(k, _)| k);
+            !explicit_keys.iter().any(|k| k == key)
+        })
+        .collect()
+}
+
+/// Validate a `--user` value: non-empty, no whitespace (`user`, `uid`, `user:group`).
+pub fn validate_user(user: &str) -> Result<(), String> {
+    if user.is_empty() || user.chars().any(char::is_whitespace) {
+        Err(format!(
+            "Invalid user '{user}'. Expected a user name, uid or user:group."
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Resolve `--workdir` against the container workspace root.
+///
+/// Absolute paths are used as-is; relative paths are joined onto `container_root`.
+/// Returns `Err` for an empty value.
+pub fn resolve_workdir(workdir: &str, container_root: &Path) -> Result<PathBuf, String> {
+    if workdir.is_empty() {
+        return Err("Invalid --workdir: path is empty.".to_string());
+    }
+    let path = Path::new(workdir);
+    if path.is_absolute() {
+        Ok(path.to_path_buf())
+    } else {
+        Ok(container_root.join(path))
+    }
+}
+
+/// Validate flag values that don't need the container: `--user`, `-e` and `--env-file`.
+///
+/// Relative env-file paths are resolved against `cwd`. Returns the absolute env-file
+/// paths on success.
+pub fn validate_exec_flags(flags: &ExecFlags, cwd: &Path) -> Result<Vec<PathBuf>, String> {
+    if let Some(ref user) = flags.user {
+        validate_user(user)?;
+    }
+    for entry in &flags.env {
+        validate_env_entry(entry)?;
+    }
+    flags
+        .env_files
+        .iter()
+        .map(|f| {
+            let abs = if f.is_absolute() {
+                f.clone()
+            } else {
+                cwd.join(f)
+            };
+            if abs.is_file() {
+                Ok(abs)
+            } else {
+                Err(format!("Env file not found: {}", abs.display()))
+            }
+        })
+        .collect()
+}
+
 /// Build the argument list for `docker exec`.
 ///
-/// Uses `docker exec -w <workspace>` to set the working directory directly,
-/// bypassing devcontainer exec entirely. The container's default user (set by
-/// devcontainer during creation to `remoteUser`) is inherited automatically.
-/// This avoids devcontainer exec's config resolution and lifecycle hook
-/// re-execution, which caused concurrent session conflicts.
+/// Uses `docker exec -w <workdir>` to set the working directory directly,
+/// bypassing devcontainer exec entirely. `-u` is only passed when a user is set;
+/// otherwise the container's default user (set by devcontainer during creation to
+/// `remoteUser`) is inherited. This avoids devcontainer exec's config resolution and
+/// lifecycle hook re-execution, which caused concurrent session conflicts.
 ///
 /// TTY flags:
 /// - `-i` (stdin open): always included for input passthrough
@@ -54,7 +197,7 @@ pub fn mount_not_found_error(workspace: &Path, mount_dir_exists: bool) -> String
 ///   omitted when stdin is a pipe (non-interactive commands)
 pub fn build_exec_args(
     container_id: &str,
-    workspace_path: &Path,
+    opts: &ExecOptions,
     tty: bool,
     command: &[String],
 ) -> Vec<String> {
@@ -63,7 +206,19 @@ pub fn build_exec_args(
         args.push("-t".to_string());
     }
     args.push("-w".to_string());
-    args.push(workspace_path.to_string_lossy().into_owned());
+    args.push(opts.workdir.to_string_lossy().into_owned());
+    if let Some(ref user) = opts.user {
+        args.push("-u".to_string());
+        args.push(user.clone());
+    }
+    for f in &opts.env_files {
+        args.push("--env-file".to_string());
+        args.push(f.to_string_lossy().into_owned());
+    }
+    for e in &opts.env {
+        args.push("-e".to_string());
+        args.push(e.clone());
+    }
     args.push(container_id.to_string());
     for c in command {
         args.push(c.clone());
@@ -71,35 +226,249 @@ pub fn build_exec_args(
     args
 }
 
-// ── Entry point ───────────────────────────────────────────────────────────────
+/// Line prefix used by `dcx exec --all` to attribute output to a workspace.
+pub fn prefix_line(label: &str, line: &str) -> String {
+    format!("[{label}] {line}")
+}
 
-/// Run `dcx exec`.
+/// Combined exit code for `dcx exec --all`: success only if every workspace succeeded.
+pub fn combined_exit_code(codes: &[Option<i32>]) -> i32 {
+    if codes.iter().all(|c| *c == Some(0)) {
+        exit_codes::SUCCESS
+    } else {
+        exit_codes::RUNTIME_ERROR
+    }
+}
+
+// ── Exec targets ──────────────────────────────────────────────────────────────
+
+/// A running workspace container that `dcx exec` can target.
+#[derive(Debug, Clone)]
+pub struct ExecTarget {
+    /// Display name (see `naming::workspace_labels`).
+    pub label: String,
+    pub workspace: PathBuf,
+    pub mount_point: PathBuf,
+    pub container_id: String,
+}
+
+/// Find every active workspace (healthy mount + running container) using the same
+/// scan as `dcx status`.
+pub fn find_exec_targets(home: &Path) -> Vec<ExecTarget> {
+    let table = platform::read_mount_table().unwrap_or_default();
+    let mut targets: Vec<ExecTarget> = scan_relay(&relay_dir(home))
+        .into_iter()
+        .filter_map(|mount_point| {
+            let workspace = PathBuf::from(mount_table::find_mount_source(&table, &mount_point)?);
+            if mount_point.metadata().is_err() {
+                return None;
+            }
+            let container_id = docker::query_container(&mount_point)?;
+            Some(ExecTarget {
+                label: String::new(),
+                workspace,
+                mount_point,
+                container_id,
+            })
+        })
+        .collect();
+    let entries: Vec<(Option<PathBuf>, String)> = targets
+        .iter()
+        .map(|t| {
+            let mount = t
+                .mount_point
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            (Some(t.workspace.clone()), mount)
+        })
+        .collect();
+    for (target, label) in targets.iter_mut().zip(workspace_labels(&entries)) {
+        target.label = label;
+    }
+    targets
+}
+
+/// Resolve workdir, user and environment for one target container.
 ///
-/// Returns the exit code that `main` should pass to `std::process::exit`.
-pub fn run_exec(
+/// Working directory: the container-side workspace root (wherever the relay is mounted,
+/// which honours remapped workspaceFolders), plus cwd's path relative to the workspace;
+/// `--workdir` overrides this. `--user` defaults to remoteUser from devcontainer.json.
+/// remoteEnv + userEnvProbe (cached per container) come first and explicit `-e`
+/// entries last, so they override; implicit keys also set by an `--env-file` are dropped,
+/// so the file wins over them.
+fn exec_options_for(
+    home: &Path,
+    target: &ExecTarget,
+    config_json: Option<&Path>,
+    flags: &ExecFlags,
+    env_files: &[PathBuf],
+    cwd: Option<&Path>,
+) -> Result<ExecOptions, String> {
+    let container_root =
+        docker::container_workspace_folder(&target.container_id, &target.mount_point)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| target.workspace.clone());
+    let workdir = match (flags.workdir.as_deref(), cwd) {
+        (Some(w), _) => resolve_workdir(w, &container_root)?,
+        (None, Some(cwd)) => container_workdir(&container_root, &target.workspace, cwd),
+        (None, None) => container_root,
+    };
+
+    let user = flags.user.clone().or_else(|| {
+        config_json
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|json| parse_remote_user(&json))
+    });
+
+    let name = target
+        .mount_point
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let explicit_keys: Vec<String> = flags
+        .env
+        .iter()
+        .map(|e| e.split_once('=').map_or(e.as_str(), |(k, _)| k).to_string())
+        .chain(
+            env_files
+                .iter()
+                .filter_map(|f| std::fs::read_to_string(f).ok())
+                .flat_map(|content| env_file_keys(&content)),
+        )
+        .collect();
+    let mut env = without_explicit_keys(
+        remote_env::exec_env(
+            home,
+            &name,
+            &target.container_id,
+            user.as_deref(),
+            config_json,
+        ),
+        &explicit_keys,
+    );
+    env.extend(flags.env.iter().cloned());
+
+    Ok(ExecOptions {
+        workdir,
+        user,
+        env,
+        env_files: env_files.to_vec(),
+    })
+}
+
+/// Run `docker` with `args` (stdin closed), prefixing each stdout/stderr line with `label`.
+///
+/// Returns the exit code, or `None` if the process could not be spawned.
+fn run_prefixed(label: &str, args: &[String]) -> Option<i32> {
+    use std::io::BufRead;
+    use std::process::{Command, Stdio};
+
+    let mut child = Command::new("docker")
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .ok()?;
+    let stdout = child.stdout.take()?;
+    let stderr = child.stderr.take()?;
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            for line in std::io::BufReader::new(stdout)
+                .lines()
+                .map_while(Result::ok)
+            {
+                println!("{}", prefix_line(label, &line));
+            }
+        });
+        scope.spawn(|| {
+            for line in std::io::BufReader::new(stderr)
+                .lines()
+                .map_while(Result::ok)
+            {
+                eprintln!("{}", prefix_line(label, &line));
+            }
+        });
+    });
+    child.wait().ok().map(|s| s.code().unwrap_or(1))
+}
+
+// ── Entry point ───────────────────────────────────────────────────────────────
+
+/// Resolve the workspace of `dcx exec`/`dcx shell`. Without `--workspace-folder`, walk up
+/// from `cwd` to the nearest directory with an active dcx mount, so both work from any
+/// subdirectory of the workspace; otherwise resolve as every other command does.
+pub fn resolve_exec_workspace(
+    home: &Path,
+    workspace_folder: Option<&Path>,
+    cwd: Option<&Path>,
+) -> Result<PathBuf, String> {
+    if workspace_folder.is_none()
+        && let Some(cwd) = cwd
+    {
+        let relay = relay_dir(home);
+        let table = platform::read_mount_table().unwrap_or_default();
+        let enclosing = find_enclosing_workspace(cwd, |dir| {
+            mount_table::find_mount_source(&table, &relay.join(mount_name(dir))).is_some()
+        });
+        if let Some(p) = enclosing {
+            return Ok(p);
+        }
+    }
+    resolve_workspace(workspace_folder)
+}
+
+/// A resolved `dcx exec` invocation: the target container and `docker exec` options.
+pub struct PreparedExec {
+    pub target: ExecTarget,
+    pub opts: ExecOptions,
+}
+
+/// Resolve and validate everything `dcx exec` needs before running a command: flags,
+/// workspace (walking up from cwd), mount health, running container, workdir, user and
+/// environment. Errors are printed; `Err` carries the exit code.
+pub fn prepare_exec(
     home: &Path,
     workspace_folder: Option<PathBuf>,
     config_dir: Option<PathBuf>,
-    command: Vec<String>,
-) -> i32 {
+    flags: ExecFlags,
+) -> Result<PreparedExec, i32> {
+    // 0. Validate --user, -e and --env-file up front: bad values are usage errors
+    // regardless of Docker or mount state.
+    let cwd = std::env::current_dir()
+        .ok()
+        .and_then(|d| d.canonicalize().ok());
+    let env_files = match validate_exec_flags(&flags, cwd.as_deref().unwrap_or(Path::new("."))) {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("{e}");
+            return Err(exit_codes::USAGE_ERROR);
+        }
+    };
+
     // 1. Validate Docker/Colima is available.
     if !docker::is_docker_available() {
         eprintln!("Docker is not available. Is Colima running?");
-        return exit_codes::RUNTIME_ERROR;
+        return Err(exit_codes::RUNTIME_ERROR);
     }
 
     // 2. Resolve workspace path to absolute canonical path.
-    let workspace = match resolve_workspace(workspace_folder.as_deref()) {
+    let workspace = match resolve_exec_workspace(home, workspace_folder.as_deref(), cwd.as_deref())
+    {
         Ok(p) => p,
         Err(e) => {
             eprintln!("{e}");
-            return exit_codes::USAGE_ERROR;
+            return Err(exit_codes::USAGE_ERROR);
         }
     };
+    let relay = relay_dir(home);
+    let table = platform::read_mount_table().unwrap_or_default();
 
-    // 2b. Validate --config-dir if provided (kept for CLI compatibility).
-    // With docker exec, config resolution is unnecessary — the container was already
-    // configured by dcx up. We still validate the path exists for user feedback.
+    // 2b. Validate --config-dir if provided.
+    // The container was already configured by dcx up; the config is only read for
+    // remoteUser (the default --user). We still validate the path exists for user feedback.
+    let mut devcontainer_json = None;
     if let Some(ref dir) = config_dir {
         let abs_dir = if dir.is_absolute() {
             dir.clone()
@@ -110,37 +479,37 @@ pub fn run_exec(
         };
         if !abs_dir.exists() {
             eprintln!("Config directory not found: {}", abs_dir.display());
-            return exit_codes::USAGE_ERROR;
+            return Err(exit_codes::USAGE_ERROR);
         }
         if !abs_dir.is_dir() {
             eprintln!("Config path is not a directory: {}", abs_dir.display());
-            return exit_codes::USAGE_ERROR;
+            return Err(exit_codes::USAGE_ERROR);
         }
         let json = abs_dir.join("devcontainer.json");
         if !json.exists() {
             eprintln!("devcontainer.json not found in: {}", abs_dir.display());
-            return exit_codes::USAGE_ERROR;
+            return Err(exit_codes::USAGE_ERROR);
         }
+        devcontainer_json = Some(json);
     }
+
     progress::step(&format!(
         "Resolving workspace path: {}",
         workspace.display()
     ));
 
     // 3. Recursive mount guard — block nested dcx mounts.
-    let relay = relay_dir(home);
     if is_dcx_managed_path(&workspace, &relay) {
         eprintln!(
             "Cannot use a dcx-managed mount point as a workspace. \
              Use the original workspace path instead."
         );
-        return exit_codes::USAGE_ERROR;
+        return Err(exit_codes::USAGE_ERROR);
     }
 
     // 4. Verify mount exists in the mount table.
     let name = mount_name(&workspace);
     let mount_point = relay.join(&name);
-    let table = platform::read_mount_table().unwrap_or_default();
     let source_in_table = mount_table::find_mount_source(&table, &mount_point);
 
     if source_in_table.is_none() {
@@ -149,13 +518,13 @@ pub fn run_exec(
             "{}",
             mount_not_found_error(&workspace, mount_point.exists())
         );
-        return exit_codes::RUNTIME_ERROR;
+        return Err(exit_codes::RUNTIME_ERROR);
     }
 
     // 5. Verify mount is healthy (accessible). In table but not accessible = zombie FUSE.
     if !mount_point.exists() {
         eprintln!("{}", stale_mount_error());
-        return exit_codes::RUNTIME_ERROR;
+        return Err(exit_codes::RUNTIME_ERROR);
     }
 
     // 6. Find the running container by its devcontainer.local_folder label.
@@ -164,28 +533,208 @@ pub fn run_exec(
     let container_id = docker::find_devcontainer_by_workspace(&mount_point);
     let Some(container_id) = container_id else {
         eprintln!("No running devcontainer found for this workspace. Run `dcx up` first.");
-        return exit_codes::RUNTIME_ERROR;
+        return Err(exit_codes::RUNTIME_ERROR);
     };
 
-    // 7. Print network mode if available
+    // 7. Re-apply the run-phase network mode if the container was restarted outside dcx
+    // (fail closed: the container is stopped if that fails), then print it.
+    if let Err(e) = up::ensure_network_mode(home, &mount_point, &container_id) {
+        eprintln!("{e}");
+        return Err(exit_codes::RUNTIME_ERROR);
+    }
     if let Some(network_mode) = docker::read_network_mode(&container_id) {
         progress::step(&format!("Network: {}", network_mode));
     }
 
+    let config_json = devcontainer_json.or_else(|| find_devcontainer_config(&workspace));
+    let label = workspace
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| name.clone());
+    let target = ExecTarget {
+        label,
+        workspace,
+        mount_point,
+        container_id,
+    };
+    let opts = match exec_options_for(
+        home,
+        &target,
+        config_json.as_deref(),
+        &flags,
+        &env_files,
+        cwd.as_deref(),
+    ) {
+        Ok(opts) => opts,
+        Err(e) => {
+            eprintln!("{e}");
+            return Err(exit_codes::USAGE_ERROR);
+        }
+    };
+    state::touch_active(home, &name);
+    state::register_session(home, &name);
+    Ok(PreparedExec { target, opts })
+}
+
+/// Run `dcx exec`.
+///
+/// Returns the exit code that `main` should pass to `std::process::exit`.
+pub fn run_exec(
+    home: &Path,
+    workspace_folder: Option<PathBuf>,
+    config_dir: Option<PathBuf>,
+    flags: ExecFlags,
+    command: Vec<String>,
+) -> i32 {
+    let record_flag = flags.record;
+    let PreparedExec { target, opts } =
+        match prepare_exec(home, workspace_folder, config_dir.clone(), flags) {
+            Ok(p) => p,
+            Err(code) => return code,
+        };
+
     // 8. Delegate to `docker exec` with `-w` to set working directory.
     // Uses docker exec directly instead of devcontainer exec to avoid:
     // - Config resolution issues (devcontainer reads source config, not override)
     // - Lifecycle hook re-execution (postAttachCommand races in concurrent sessions)
-    // The container's default user is already set to remoteUser by devcontainer during
-    // creation, so no `-u` flag is needed. SIGINT is forwarded naturally (same process group).
+    // SIGINT is forwarded naturally (same process group).
     progress::step("Running exec in container...");
 
     let tty = std::io::stdin().is_terminal();
-    let args = build_exec_args(&container_id, &workspace, tty, &command);
+    let args = build_exec_args(&target.container_id, &opts, tty, &command);
+
+    // Interactive sessions are recorded when asked for by --record or up.record_sessions.
+    let record = record_flag
+        || up::find_dcx_config_path(&target.workspace, config_dir.as_deref())
+            .is_some_and(|p| dcx_config::read_dcx_config(&p).up.record_sessions);
+    if record && tty {
+        let mount = target
+            .mount_point
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        return sessions::run_recorded(home, &mount, &target.workspace, &args, &command);
+    }
+    if record_flag {
+        eprintln!("Warning: --record requires an interactive terminal; session not recorded.");
+    }
+
     let args_str: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
     cmd::run_stream("docker", &args_str).unwrap_or(exit_codes::PREREQ_NOT_FOUND)
 }
 
+/// Run `dcx exec --all`: run `command` in every active workspace, `parallel` at a time.
+///
+/// Output lines are prefixed with the workspace name; a per-workspace summary table is
+/// printed at the end. Returns `SUCCESS` only if the command succeeded everywhere.
+pub fn run_exec_all(home: &Path, flags: ExecFlags, parallel: usize, command: Vec<String>) -> i32 {
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    if command.is_empty() {
+        eprintln!("dcx exec --all requires a command.");
+        return exit_codes::USAGE_ERROR;
+    }
+    let cwd = std::env::current_dir()
+        .ok()
+        .and_then(|d| d.canonicalize().ok());
+    let env_files = match validate_exec_flags(&flags, cwd.as_deref().unwrap_or(Path::new("."))) {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("{e}");
+            return exit_codes::USAGE_ERROR;
+        }
+    };
+
+    if !docker::is_docker_available() {
+        eprintln!("Docker is not available. Is Colima running?");
+        return exit_codes::RUNTIME_ERROR;
+    }
+
+    progress::step("Scanning workspaces...");
+    let targets = find_exec_targets(home);
+    if targets.is_empty() {
+        println!("No active workspaces.");
+        return exit_codes::SUCCESS;
+    }
+    progress::step(&format!(
+        "Running in {} workspaces ({} at a time)...",
+        targets.len(),
+        parallel.max(1)
+    ));
+
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<ExecAllRow>>> =
+        Mutex::new((0..targets.len()).map(|_| None).collect());
+    std::thread::scope(|scope| {
+        for _ in 0..parallel.clamp(1, targets.len()) {
+            scope.spawn(|| {
+                loop {
+                    let i = next.fetch_add(1, Ordering::SeqCst);
+                    let Some(target) = targets.get(i) else {
+                        break;
+                    };
+                    let started = std::time::Instant::now();
+                    let mount = target
+                        .mount_point
+                        .file_name()
+                        .map(|n| n.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    // Each workspace uses its remembered --config-dir for remoteUser/remoteEnv.
+                    let config_json = up::load_remembered_options(home, &mount)
+                        .and_then(|o| o.config_dir)
+                        .map(|d| d.join("devcontainer.json"))
+                        .or_else(|| find_devcontainer_config(&target.workspace));
+                    let prepared =
+                        up::ensure_network_mode(home, &target.mount_point, &target.container_id)
+                            .and_then(|_| {
+                                exec_options_for(
+                                    home,
+                                    target,
+                                    config_json.as_deref(),
+                                    &flags,
+                                    &env_files,
+                                    None,
+                                )
+                            });
+                    let exit_code = match prepared {
+                        Ok(opts) => {
+                            state::touch_active(home, &mount);
+                            let args =
+                                build_exec_args(&target.container_id, &opts, false, &command);
+                            run_prefixed(&target.label, &args)
+                        }
+                        Err(e) => {
+                            eprintln!("{}", prefix_line(&target.label, &e));
+                            None
+                        }
+                    };
+                    let row = ExecAllRow {
+                        workspace: target.label.clone(),
+                        container: target.container_id.clone(),
+                        exit_code,
+                        seconds: started.elapsed().as_secs_f64(),
+                    };
+                    if let Ok(mut results) = results.lock() {
+                        results[i] = Some(row);
+                    }
+                }
+            });
+        }
+    });
+
+    let rows: Vec<ExecAllRow> = results
+        .into_inner()
+        .unwrap_or_default()
+        .into_iter()
+        .flatten()
+        .collect();
+    println!();
+    println!("{}", format_exec_all_summary(&rows));
+    let codes: Vec<Option<i32>> = rows.iter().map(|r| r.exit_code).collect();
+    combined_exit_code(&codes)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -216,14 +765,14 @@ mod tests {
     #[test]
     fn exec_args_includes_container_id() {
         let ws = Path::new("/home/user/myproject");
-        let args = build_exec_args("abc123", ws, false, &[]);
+        let args = build_exec_args("abc123", &ExecOptions::new(ws), false, &[]);
         assert!(args.contains(&"abc123".to_string()));
     }
 
     #[test]
     fn exec_args_sets_working_directory() {
         let ws = Path::new("/home/user/myproject");
-        let args = build_exec_args("abc123", ws, false, &[]);
+        let args = build_exec_args("abc123", &ExecOptions::new(ws), false, &[]);
         let wi = args.iter().position(|a| a == "-w").unwrap();
         assert_eq!(args[wi + 1], "/home/user/myproject");
     }
@@ -232,7 +781,7 @@ mod tests {
     fn exec_args_appends_command_directly() {
         let ws = Path::new("/home/user/myproject");
         let cmd = vec!["bash".to_string(), "-c".to_string(), "echo hi".to_string()];
-        let args = build_exec_args("abc123", ws, false, &cmd);
+        let args = build_exec_args("abc123", &ExecOptions::new(ws), false, &cmd);
         // Command follows container ID directly (no -- separator needed for docker exec)
         let cid_pos = args.iter().position(|a| a == "abc123").unwrap();
         assert_eq!(args[cid_pos + 1], "bash");
@@ -243,7 +792,7 @@ mod tests {
     #[test]
     fn exec_args_no_command_when_empty() {
         let ws = Path::new("/home/user/myproject");
-        let args = build_exec_args("abc123", ws, false, &[]);
+        let args = build_exec_args("abc123", &ExecOptions::new(ws), false, &[]);
         // exec -i -w <workspace> <container_id> (5 elements when tty=false)
         assert_eq!(args.len(), 5);
     }
@@ -252,7 +801,7 @@ mod tests {
     fn exec_args_uses_docker_exec_format() {
         let ws = Path::new("/home/user/myproject");
         let cmd = vec!["echo".to_string(), "hello".to_string()];
-        let args = build_exec_args("abc123", ws, false, &cmd);
+        let args = build_exec_args("abc123", &ExecOptions::new(ws), false, &cmd);
         assert_eq!(args[0], "exec");
         assert_eq!(args[1], "-i");
         assert_eq!(args[2], "-w");
@@ -285,8 +834,8 @@ mod tests {
     #[test]
     fn exec_args_always_includes_interactive_flag() {
         let ws = Path::new("/home/user/myproject");
-        let args_tty = build_exec_args("abc123", ws, true, &[]);
-        let args_no_tty = build_exec_args("abc123", ws, false, &[]);
+        let args_tty = build_exec_args("abc123", &ExecOptions::new(ws), true, &[]);
+        let args_no_tty = build_exec_args("abc123", &ExecOptions::new(ws), false, &[]);
         assert!(args_tty.contains(&"-i".to_string()), "got: {:?}", args_tty);
         assert!(
             args_no_tty.contains(&"-i".to_string()),
@@ -298,14 +847,164 @@ mod tests {
     #[test]
     fn exec_args_includes_tty_flag_when_true() {
         let ws = Path::new("/home/user/myproject");
-        let args = build_exec_args("abc123", ws, true, &[]);
+        let args = build_exec_args("abc123", &ExecOptions::new(ws), true, &[]);
         assert!(args.contains(&"-t".to_string()), "got: {:?}", args);
     }
 
     #[test]
     fn exec_args_no_tty_flag_when_false() {
         let ws = Path::new("/home/user/myproject");
-        let args = build_exec_args("abc123", ws, false, &[]);
+        let args = build_exec_args("abc123", &ExecOptions::new(ws), false, &[]);
         assert!(!args.contains(&"-t".to_string()), "got: {:?}", args);
     }
+
+    // --- ExecOptions ---
+
+    #[test]
+    fn exec_args_includes_user_when_set() {
+        let opts = ExecOptions {
+            user: Some("root".to_string()),
+            ..ExecOptions::new("/w")
+        };
+        let args = build_exec_args("abc123", &opts, false, &[]);
+        let ui = args.iter().position(|a| a == "-u").unwrap();
+        assert_eq!(args[ui + 1], "root");
+    }
+
+    #[test]
+    fn exec_args_env_and_env_file_precede_container_id() {
+        let opts = ExecOptions {
+            env: vec!["FOO=bar".to_string()],
+            env_files: vec![PathBuf::from("/tmp/vars.env")],
+            ..ExecOptions::new("/w")
+        };
+        let cmd = vec!["env".to_string()];
+        let args = build_exec_args("abc123", &opts, false, &cmd);
+        let cid = args.iter().position(|a| a == "abc123").unwrap();
+        let ei = args.iter().position(|a| a == "-e").unwrap();
+        let fi = args.iter().position(|a| a == "--env-file").unwrap();
+        assert_eq!(args[ei + 1], "FOO=bar");
+        assert_eq!(args[fi + 1], "/tmp/vars.env");
+        assert!(ei < cid && fi < cid);
+        assert_eq!(args[cid + 1], "env");
+    }
+
+    #[test]
+    fn exec_args_no_user_flag_by_default() {
+        let args = build_exec_args("abc123", &ExecOptions::new("/w"), false, &[]);
+        assert!(!args.contains(&"-u".to_string()), "got: {:?}", args);
+    }
+
+    #[test]
+    fn env_file_keys_skips_comments_and_blank_lines() {
+        let content = "# comment\nFOO=bar\n\n  BAZ\nEMPTY=\nURL=a=b\n";
+        assert_eq!(env_file_keys(content), vec!["FOO", "BAZ", "EMPTY", "URL"]);
+    }
+
+    #[test]
+    fn explicit_env_file_keys_win_over_implicit_env() {
+        let implicit = vec![
+            "PATH=/usr/bin".to_string(),
+            "TOKEN=probed".to_string(),
+            "EDITOR=vi".to_string(),
+        ];
+        let mut explicit = env_file_keys("TOKEN=from-file\n");
+        explicit.push("EDITOR".to_string());
+        assert_eq!(
+            without_explicit_keys(implicit, &explicit),
+            vec!["PATH=/usr/bin".to_string()]
+        );
+    }
+
+    // --- validation ---
+
+    #[test]
+    fn validate_env_entry_accepts_key_val_and_bare_key() {
+        assert!(validate_env_entry("FOO=bar").is_ok());
+        assert!(validate_env_entry("FOO=").is_ok());
+        assert!(validate_env_entry("_FOO1=a=b").is_ok());
+        assert!(validate_env_entry("FOO").is_ok());
+    }
+
+    #[test]
+    fn validate_env_entry_rejects_bad_keys() {
+        assert!(validate_env_entry("=bar").is_err());
+        assert!(validate_env_entry("1FOO=bar").is_err());
+        assert!(validate_env_entry("FO O=bar").is_err());
+        assert!(validate_env_entry("").is_err());
+    }
+
+    #[test]
+    fn validate_user_rejects_empty_and_whitespace() {
+        assert!(validate_user("root").is_ok());
+        assert!(validate_user("1000:1000").is_ok());
+        assert!(validate_user("").is_err());
+        assert!(validate_user("ro ot").is_err());
+    }
+
+    #[test]
+    fn resolve_workdir_absolute_is_unchanged() {
+        let wd = resolve_workdir("/tmp", Path::new("/workspace")).unwrap();
+        assert_eq!(wd, PathBuf::from("/tmp"));
+    }
+
+    #[test]
+    fn resolve_workdir_relative_joins_container_root() {
+        let wd = resolve_workdir("crates/foo", Path::new("/workspace")).unwrap();
+        assert_eq!(wd, PathBuf::from("/workspace/crates/foo"));
+    }
+
+    #[test]
+    fn resolve_workdir_empty_is_err() {
+        assert!(resolve_workdir("", Path::new("/workspace")).is_err());
+    }
+
+    #[test]
+    fn validate_exec_flags_resolves_relative_env_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("vars.env"), "FOO=1\n").unwrap();
+        let flags = ExecFlags {
+            env_files: vec![PathBuf::from("vars.env")],
+            ..ExecFlags::default()
+        };
+        let files = validate_exec_flags(&flags, dir.path()).unwrap();
+        assert_eq!(files, vec![dir.path().join("vars.env")]);
+    }
+
+    #[test]
+    fn validate_exec_flags_missing_env_file_is_err() {
+        let dir = tempfile::tempdir().unwrap();
+        let flags = ExecFlags {
+            env_files: vec![PathBuf::from("missing.env")],
+            ..ExecFlags::default()
+        };
+        let err = validate_exec_flags(&flags, dir.path()).unwrap_err();
+        assert!(err.contains("Env file not found"), "got: {err}");
+    }
+
+    // --- exec --all helpers ---
+
+    #[test]
+    fn prefix_line_wraps_label_in_brackets() {
+        assert_eq!(
+            prefix_line("proj", "On branch main"),
+            "[proj] On branch main"
+        );
+    }
+
+    #[test]
+    fn combined_exit_code_success_only_when_all_zero() {
+        assert_eq!(combined_exit_code(&[Some(0), Some(0)]), exit_codes::SUCCESS);
+        assert_eq!(
+            combined_exit_code(&[Some(0), Some(3)]),
+            exit_codes::RUNTIME_ERROR
+        );
+        assert_eq!(combined_exit_code(&[None]), exit_codes::RUNTIME_ERROR);
+    }
+
+    #[test]
+    fn find_exec_targets_empty_relay_is_empty() {
+        let home = tempfile::tempdir().unwrap();
+        assert!(find_exec_targets(home.path()).is_empty());
+    }
 }
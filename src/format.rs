@@ -1,6 +1,11 @@
 #![allow(dead_code)]
 
+use serde::Serialize;
+
 /// A row in the `dcx status` table.
+///
+/// Field names are part of the `--output json` schema.
+#[derive(Serialize)]
 pub struct StatusRow {
     /// Original workspace path, or None if it cannot be resolved.
     pub workspace: Option<String>,
@@ -9,11 +14,49 @@ pub struct StatusRow {
     /// Docker container short ID, or None if no container.
     pub container: Option<String>,
     /// Network mode (e.g. `minimal`, `open`, `restricted`, `host`), or None if unknown.
+    /// Phased policies append the current phase (e.g. `restricted (run)`).
     pub network: Option<String>,
     /// Human-readable state string (e.g. `running`, `stale mount`).
     pub state: String,
     /// Sync daemon status (e.g. `running`, `stopped`, or `–` if N/A).
     pub daemon: String,
+    /// Last error from the sync daemon log, already formatted (e.g. `5m ago: sync: ...`).
+    pub daemon_error: Option<String>,
+    /// Live resource usage (`dcx status --resources` with a container), otherwise None.
+    pub resources: Option<ContainerResources>,
+    /// Seconds until the TTL or idle timeout brings the workspace down, if one applies.
+    pub expires_in: Option<u64>,
+}
+
+/// Resource usage of a workspace container, shown by `dcx status --resources`.
+///
+/// Values are kept as Docker prints them; `–` marks a value Docker did not report
+/// (e.g. CPU of a stopped container).
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ContainerResources {
+    /// CPU usage (e.g. `12.34%`).
+    pub cpu_percent: String,
+    /// Memory usage and limit (e.g. `512MiB / 7.7GiB`).
+    pub memory: String,
+    /// Number of processes.
+    pub pids: String,
+    /// Network received / sent (e.g. `1.2MB / 340kB`).
+    pub net_io: String,
+    /// Size of the container's writable layer (e.g. `12.3kB`).
+    pub writable_size: String,
+}
+
+/// Disk used by dcx-owned images and volumes (the `dcx status --resources` footer).
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct DiskUsage {
+    /// Distinct dcx images (`dcx-base:*` tags and runtime images).
+    pub images: usize,
+    /// Total size of those images in bytes.
+    pub image_bytes: u64,
+    /// Number of `dcx-*` volumes.
+    pub volumes: usize,
+    /// Total size of those volumes in bytes.
+    pub volume_bytes: u64,
 }
 
 /// Format the `dcx status` output table.
@@ -24,8 +67,8 @@ pub fn format_status_table(rows: &[StatusRow]) -> String {
         return "No active workspaces.".to_string();
     }
     let header = format!(
-        "{:<30} {:<30} {:<12} {:<12} {:<10} {}",
-        "WORKSPACE", "MOUNT", "CONTAINER", "NETWORK", "DAEMON", "STATE"
+        "{:<30} {:<30} {:<12} {:<18} {:<10} {:<12} {}",
+        "WORKSPACE", "MOUNT", "CONTAINER", "NETWORK", "DAEMON", "STATE", "EXPIRES"
     );
     let mut lines = vec![header];
     for row in rows {
@@ -33,14 +76,115 @@ pub fn format_status_table(rows: &[StatusRow]) -> String {
         let container = row.container.as_deref().unwrap_or("(none)");
         let network = row.network.as_deref().unwrap_or("–");
         lines.push(format!(
-            "{:<30} {:<30} {:<12} {:<12} {:<10} {}",
-            workspace, row.mount, container, network, row.daemon, row.state
+            "{:<30} {:<30} {:<12} {:<18} {:<10} {:<12} {}",
+            workspace,
+            row.mount,
+            container,
+            network,
+            row.daemon,
+            row.state,
+            expires_label(row.expires_in)
         ));
     }
+    push_daemon_errors(&mut lines, rows);
+    lines.join("\n")
+}
+
+/// EXPIRES column value: time left before the workspace is brought down, or `–`.
+pub fn expires_label(expires_in: Option<u64>) -> String {
+    match expires_in {
+        Some(0) => "now".to_string(),
+        Some(secs) => format!("in {}", crate::duration::format_duration(secs)),
+        None => "–".to_string(),
+    }
+}
+
+/// Format the `dcx status --resources` table: the status columns plus live resource usage,
+/// followed by the dcx disk usage footer.
+pub fn format_status_resources_table(rows: &[StatusRow], usage: &DiskUsage) -> String {
+    let mut lines = Vec::new();
+    if rows.is_empty() {
+        lines.push("No active workspaces.".to_string());
+    } else {
+        lines.push(format!(
+            "{:<30} {:<30} {:<12} {:<18} {:<10} {:<12} {:<10} {:<8} {:<22} {:<6} {:<20} {}",
+            "WORKSPACE",
+            "MOUNT",
+            "CONTAINER",
+            "NETWORK",
+            "DAEMON",
+            "STATE",
+            "EXPIRES",
+            "CPU %",
+            "MEMORY",
+            "PIDS",
+            "NET I/O",
+            "WRITABLE"
+        ));
+        for row in rows {
+            let workspace = row.workspace.as_deref().unwrap_or("(unknown)");
+            let container = row.container.as_deref().unwrap_or("(none)");
+            let network = row.network.as_deref().unwrap_or("–");
+            let none = "–".to_string();
+            let (cpu, memory, pids, net_io, writable) = match &row.resources {
+                Some(r) => (
+                    &r.cpu_percent,
+                    &r.memory,
+                    &r.pids,
+                    &r.net_io,
+                    &r.writable_size,
+                ),
+                None => (&none, &none, &none, &none, &none),
+            };
+            lines.push(format!(
+                "{:<30} {:<30} {:<12} {:<18} {:<10} {:<12} {:<10} {:<8} {:<22} {:<6} {:<20} {}",
+                workspace,
+                row.mount,
+                container,
+                network,
+                row.daemon,
+                row.state,
+                expires_label(row.expires_in),
+                cpu,
+                memory,
+                pids,
+                net_io,
+                writable
+            ));
+        }
+        push_daemon_errors(&mut lines, rows);
+    }
+    lines.push(String::new());
+    lines.push(format!(
+        "dcx disk usage: images {} ({} image{}), volumes {} ({} volume{})",
+        crate::size::format_size(usage.image_bytes),
+        usage.images,
+        if usage.images == 1 { "" } else { "s" },
+        crate::size::format_size(usage.volume_bytes),
+        usage.volumes,
+        if usage.volumes == 1 { "" } else { "s" }
+    ));
     lines.join("\n")
 }
 
+/// Append the "Sync daemon errors" section for rows that have one.
+fn push_daemon_errors(lines: &mut Vec<String>, rows: &[StatusRow]) {
+    let errors: Vec<&StatusRow> = rows.iter().filter(|r| r.daemon_error.is_some()).collect();
+    if !errors.is_empty() {
+        lines.push(String::new());
+        lines.push("Sync daemon errors (see `dcx logs --daemon`):".to_string());
+        for row in errors {
+            lines.push(format!(
+                "  {}  {}",
+                row.mount,
+                row.daemon_error.as_deref().unwrap_or_default()
+            ));
+        }
+    }
+}
+
 /// A single prerequisite check result for `dcx doctor`.
+#[derive(Serialize)]
 pub struct DoctorCheck {
     /// Short description of the check (e.g. `bindfs installed`).
     pub name: String,
@@ -78,7 +222,8 @@ pub fn format_doctor_report(checks: &[DoctorCheck]) -> String {
     lines.join("\n")
 }
 
-/// An entry in the `dcx clean` summary.
+/// An entry in the `dcx clean` summary (also the `dcx down` JSON result).
+#[derive(Serialize)]
 pub struct CleanEntry {
     /// Original workspace path, or None if not recoverable.
     pub workspace: Option<String>,
@@ -91,11 +236,11 @@ pub struct CleanEntry {
 }
 
 /// A plan for cleaning a mount (used by dry-run preview).
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct DryRunPlan {
     /// Mount name (e.g. dcx-myproject-a1b2c3d4)
     pub mount_name: String,
-    /// State before cleaning: "running", "orphaned", "stale", or "empty dir"
+    /// State before cleaning: "running", "stopped", "orphaned", "stale", or "empty dir"
     pub state: String,
     /// Container IDs if present
     pub container_ids: Vec<String>,
@@ -142,6 +287,31 @@ pub fn format_dry_run(plans: &[DryRunPlan]) -> String {
     lines.join("\n")
 }
 
+/// An orphaned staging directory (no matching `dcx-*` mount) that `dcx clean` removes.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct StagingPlan {
+    /// Directory name (e.g. `.dcx-myproject-a1b2c3d4-files`).
+    pub staging_dir: String,
+    /// PID of a sync daemon still running for it, if any.
+    pub daemon_pid: Option<u32>,
+}
+
+/// Format the orphaned staging part of the `dcx clean --dry-run` preview (empty if none).
+pub fn format_staging_dry_run(plans: &[StagingPlan]) -> String {
+    if plans.is_empty() {
+        return String::new();
+    }
+    let mut lines = vec!["Would remove orphaned staging directories:".to_string()];
+    for plan in plans {
+        lines.push(format!("  {}", plan.staging_dir));
+        if let Some(pid) = plan.daemon_pid {
+            lines.push(format!("    - Stop sync daemon (pid {pid})"));
+        }
+        lines.push("    - Remove staging directory".to_string());
+    }
+    lines.join("\n")
+}
+
 /// Format the `dcx clean` summary.
 pub fn format_clean_summary(entries: &[CleanEntry], active_left: usize) -> String {
     let header = if active_left > 0 {
@@ -168,10 +338,535 @@ pub fn format_clean_summary(entries: &[CleanEntry], active_left: usize) -> Strin
     lines.join("\n")
 }
 
+/// Format the `dcx down --all` / `dcx down <ws>...` summary.
+pub fn format_down_summary(entries: &[CleanEntry]) -> String {
+    let done = entries.iter().filter(|e| e.action != "none").count();
+    let mut lines = vec![format!(
+        "Took down {} workspace{}:",
+        done,
+        if done == 1 { "" } else { "s" }
+    )];
+    for entry in entries {
+        let left = match &entry.workspace {
+            Some(ws) => format!("{}  \u{2192}  {}", ws, entry.mount),
+            None => entry.mount.clone(),
+        };
+        let action = if entry.action == "none" {
+            "nothing to do"
+        } else {
+            entry.action.as_str()
+        };
+        lines.push(format!(
+            "  {:<52} was: {:<12} \u{2192} {}",
+            left, entry.was, action
+        ));
+    }
+    lines.join("\n")
+}
+
+/// A row in the `dcx exec --all` summary.
+pub struct ExecAllRow {
+    /// Workspace display name (prefix used on output lines).
+    pub workspace: String,
+    /// Docker container short ID.
+    pub container: String,
+    /// Command exit code, or None if `docker exec` could not be started.
+    pub exit_code: Option<i32>,
+    /// Wall-clock duration in seconds.
+    pub seconds: f64,
+}
+
+/// Format the `dcx exec --all` per-workspace summary table.
+pub fn format_exec_all_summary(rows: &[ExecAllRow]) -> String {
+    let header = format!(
+        "{:<30} {:<12} {:<8} {}",
+        "WORKSPACE", "CONTAINER", "EXIT", "TIME"
+    );
+    let mut lines = vec![header];
+    for row in rows {
+        let exit = row
+            .exit_code
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| "error".to_string());
+        lines.push(format!(
+            "{:<30} {:<12} {:<8} {:.1}s",
+            row.workspace, row.container, exit, row.seconds
+        ));
+    }
+    let failed = rows.iter().filter(|r| r.exit_code != Some(0)).count();
+    lines.push(String::new());
+    if failed == 0 {
+        lines.push(format!(
+            "{} workspace{} succeeded.",
+            rows.len(),
+            if rows.len() == 1 { "" } else { "s" }
+        ));
+    } else {
+        lines.push(format!("{} of {} workspaces failed.", failed, rows.len()));
+    }
+    lines.join("\n")
+}
+
+/// A row in the `dcx daemon status` table.
+pub struct DaemonRow {
+    /// Mount name.
+    pub workspace: String,
+    /// Number of synced files.
+    pub files: usize,
+    /// Sync thread state ("running" or "failed").
+    pub sync: String,
+}
+
+/// Format the `dcx daemon status` table.
+pub fn format_daemon_table(rows: &[DaemonRow]) -> String {
+    let header = format!("{:<32} {:<6} {}", "WORKSPACE", "FILES", "SYNC");
+    let mut lines = vec![header];
+    for row in rows {
+        lines.push(format!(
+            "{:<32} {:<6} {}",
+            row.workspace, row.files, row.sync
+        ));
+    }
+    lines.join("\n")
+}
+
+/// A row in the `dcx jobs` table.
+pub struct JobRow {
+    /// Job ID printed by `dcx run --detach`.
+    pub id: String,
+    /// Workspace display name.
+    pub workspace: String,
+    /// "running", "exited" or "lost".
+    pub state: String,
+    /// Exit code once the job has finished.
+    pub exit_code: Option<i32>,
+    /// Start time relative to now (e.g. "5m ago").
+    pub started: String,
+    /// The command as typed by the user.
+    pub command: String,
+}
+
+/// Format the `dcx jobs` table.
+pub fn format_jobs_table(rows: &[JobRow]) -> String {
+    let header = format!(
+        "{:<10} {:<24} {:<9} {:<6} {:<10} {}",
+        "ID", "WORKSPACE", "STATE", "EXIT", "STARTED", "COMMAND"
+    );
+    let mut lines = vec![header];
+    for row in rows {
+        let exit = row
+            .exit_code
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        lines.push(format!(
+            "{:<10} {:<24} {:<9} {:<6} {:<10} {}",
+            row.id, row.workspace, row.state, exit, row.started, row.command
+        ));
+    }
+    lines.join("\n")
+}
+
+/// A row in the `dcx sessions list` table.
+pub struct SessionRow {
+    /// Transcript ID (file stem) passed to `dcx sessions replay`.
+    pub id: String,
+    /// Workspace display name.
+    pub workspace: String,
+    /// Start time relative to now (e.g. "2h ago").
+    pub started: String,
+    /// Recording length (e.g. "12m").
+    pub length: String,
+    /// The command run in the session.
+    pub command: String,
+}
+
+/// Format the `dcx sessions list` table.
+pub fn format_sessions_table(rows: &[SessionRow]) -> String {
+    let header = format!(
+        "{:<18} {:<24} {:<10} {:<8} {}",
+        "ID", "WORKSPACE", "STARTED", "LENGTH", "COMMAND"
+    );
+    let mut lines = vec![header];
+    for row in rows {
+        lines.push(format!(
+            "{:<18} {:<24} {:<10} {:<8} {}",
+            row.id, row.workspace, row.started, row.length, row.command
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Everything dcx knows about one workspace (`dcx inspect`).
+#[derive(Serialize)]
+pub struct InspectReport {
+    /// Absolute workspace path on the host.
+    pub workspace: String,
+    /// Mount name (e.g. `dcx-myproject-a1b2c3d4`).
+    pub mount_name: String,
+    /// Mount point, `~`-abbreviated.
+    pub mount_point: String,
+    /// bindfs source from the mount table, or None if not mounted.
+    pub bindfs_source: Option<String>,
+    /// Same labels as `dcx status` (e.g. `running`, `orphaned`, `stale mount`).
+    pub state: String,
+    /// Containers for the mount, running or stopped.
+    pub containers: Vec<InspectContainer>,
+    /// `dcx-base:<mount>` if that tag exists.
+    pub base_image_tag: Option<String>,
+    /// Network mode of the running container (phase appended for phased policies).
+    pub network: Option<String>,
+    /// `dcx-*` volumes attached to the container.
+    pub volumes: Vec<String>,
+    /// Staging directory, `~`-abbreviated.
+    pub staging_dir: String,
+    /// Files in the staging directory.
+    pub staged_files: Vec<StagedFile>,
+    /// Sync daemon PID, if a PID file exists.
+    pub daemon_pid: Option<u32>,
+    /// Sync daemon status (`running` / `stopped` / `–`).
+    pub daemon: String,
+    /// Last daemon log error, already formatted.
+    pub daemon_error: Option<String>,
+    /// Bind mounts dcx injected from colima.yaml and the staging directory.
+    pub injected_mounts: Vec<InjectedMount>,
+    /// Environment variables dcx injected (e.g. `GIT_CONFIG_GLOBAL`).
+    pub injected_env: Vec<InjectedEnv>,
+    /// Configuration files used for the workspace.
+    pub config_files: Vec<ConfigFile>,
+}
+
+/// A container in the `dcx inspect` report.
+#[derive(Serialize)]
+pub struct InspectContainer {
+    /// Docker container short ID.
+    pub id: String,
+    /// Docker state (e.g. `running`, `exited`).
+    pub state: String,
+    /// Image the container runs (e.g. `vsc-proj-abc-uid`).
+    pub image: Option<String>,
+}
+
+/// A file in the staging directory.
+#[derive(Serialize)]
+pub struct StagedFile {
+    /// File name in the staging directory.
+    pub name: String,
+    /// Host file it was staged from, `~`-abbreviated, if known.
+    pub source: Option<String>,
+    /// How it tracks the source (e.g. `hardlink`, `synced`, `out of sync`).
+    pub sync: String,
+}
+
+/// A bind mount injected by dcx.
+#[derive(Serialize)]
+pub struct InjectedMount {
+    /// Host path.
+    pub source: String,
+    /// Path inside the container.
+    pub target: String,
+    /// Whether the mount is writable.
+    pub writable: bool,
+}
+
+/// An environment variable injected by dcx.
+#[derive(Serialize)]
+pub struct InjectedEnv {
+    pub key: String,
+    pub value: String,
+}
+
+/// A configuration file and whether it exists.
+#[derive(Serialize)]
+pub struct ConfigFile {
+    /// Role of the file (e.g. `devcontainer.json`, `dcx_config.yaml`, `colima.yaml`).
+    pub kind: String,
+    /// Path, `~`-abbreviated, or None if the file was not found.
+    pub path: Option<String>,
+}
+
+/// Format the `dcx inspect` report.
+pub fn format_inspect_report(r: &InspectReport) -> String {
+    let none = "(none)";
+    let mut lines = vec![
+        format!("Workspace:    {}", r.workspace),
+        format!(
+            "Mount:        {} ({})",
+            r.mount_point,
+            r.bindfs_source
+                .as_deref()
+                .map(|s| format!("bindfs from {s}"))
+                .unwrap_or_else(|| "not mounted".to_string())
+        ),
+        format!("State:        {}", r.state),
+    ];
+    if r.containers.is_empty() {
+        lines.push(format!("Containers:   {none}"));
+    } else {
+        lines.push("Containers:".to_string());
+        for c in &r.containers {
+            lines.push(format!(
+                "  {:<12} {:<10} {}",
+                c.id,
+                c.state,
+                c.image.as_deref().unwrap_or("–")
+            ));
+        }
+    }
+    lines.push(format!(
+        "Base image:   {}",
+        r.base_image_tag.as_deref().unwrap_or(none)
+    ));
+    lines.push(format!(
+        "Network:      {}",
+        r.network.as_deref().unwrap_or("–")
+    ));
+    lines.push(format!(
+        "Volumes:      {}",
+        if r.volumes.is_empty() {
+            none.to_string()
+        } else {
+            r.volumes.join(", ")
+        }
+    ));
+    lines.push(format!(
+        "Sync daemon:  {}{}",
+        r.daemon,
+        r.daemon_pid
+            .map(|pid| format!(" (PID {pid})"))
+            .unwrap_or_default()
+    ));
+    if let Some(err) = &r.daemon_error {
+        lines.push(format!("  last error: {err}"));
+    }
+    if r.staged_files.is_empty() {
+        lines.push(format!("Staged files: {none}"));
+    } else {
+        lines.push(format!("Staged files: {}", r.staging_dir));
+        for f in &r.staged_files {
+            lines.push(format!(
+                "  {:<24} {:<14} {}",
+                f.name,
+                f.sync,
+                f.source.as_deref().unwrap_or("(unknown source)")
+            ));
+        }
+    }
+    if r.injected_mounts.is_empty() {
+        lines.push(format!("Mounts:       {none}"));
+    } else {
+        lines.push("Mounts:".to_string());
+        for m in &r.injected_mounts {
+            let mode = if m.writable { "rw" } else { "ro" };
+            lines.push(format!("  {} \u{2192} {} ({mode})", m.source, m.target));
+        }
+    }
+    if r.injected_env.is_empty() {
+        lines.push(format!("Env:          {none}"));
+    } else {
+        lines.push("Env:".to_string());
+        for e in &r.injected_env {
+            lines.push(format!("  {}={}", e.key, e.value));
+        }
+    }
+    lines.push("Config files:".to_string());
+    for f in &r.config_files {
+        lines.push(format!(
+            "  {:<18} {}",
+            f.kind,
+            f.path.as_deref().unwrap_or("(not found)")
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Disk usage attributed to one workspace (`dcx df`). Sizes are in bytes.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct DfRow {
+    /// Original workspace path, or None if it cannot be resolved.
+    pub workspace: Option<String>,
+    /// Mount point name (e.g. `dcx-myproject-a1b2c3d4`).
+    pub mount: String,
+    /// Runtime image (`vsc-*-uid`) of the container.
+    pub runtime_image: Option<u64>,
+    /// `dcx-base:<mount>` image.
+    pub base_image: Option<u64>,
+    /// Image ID behind `dcx-base:<mount>`, used to count shared base images once.
+    #[serde(skip)]
+    pub base_image_id: Option<String>,
+    /// Whether another tag references the base image, so `--purge` only removes the tag.
+    pub base_image_shared: bool,
+    /// Build image (`vsc-*` without `-uid`).
+    pub build_image: Option<u64>,
+    /// `dcx-*` volumes attached to the container.
+    pub volumes: u64,
+    /// Staged file copies (hardlinks to host files take no extra space).
+    pub staging: u64,
+    /// Container writable layer.
+    pub writable: Option<u64>,
+    /// Estimated space freed by `dcx clean --purge` for this workspace.
+    pub reclaimable: u64,
+}
+
+/// Column totals of `dcx df`. Base images shared by several workspaces count once.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct DfTotals {
+    pub runtime_images: u64,
+    pub base_images: u64,
+    pub build_images: u64,
+    pub volumes: u64,
+    pub staging: u64,
+    pub writable: u64,
+    pub reclaimable: u64,
+}
+
+/// Format the `dcx df` table, a totals row and the reclaimable estimate.
+pub fn format_df_table(rows: &[DfRow], totals: &DfTotals) -> String {
+    use crate::size::format_size;
+
+    if rows.is_empty() {
+        return "No dcx workspaces.".to_string();
+    }
+    let opt = |v: Option<u64>| v.map(format_size).unwrap_or_else(|| "–".to_string());
+    let header = format!(
+        "{:<30} {:<30} {:<9} {:<16} {:<9} {:<9} {:<9} {:<9} {}",
+        "WORKSPACE",
+        "MOUNT",
+        "RUNTIME",
+        "BASE",
+        "BUILD",
+        "VOLUMES",
+        "STAGING",
+        "WRITABLE",
+        "RECLAIMABLE"
+    );
+    let mut lines = vec![header];
+    for row in rows {
+        let base = match row.base_image {
+            Some(b) if row.base_image_shared => format!("{} (shared)", format_size(b)),
+            other => opt(other),
+        };
+        lines.push(format!(
+            "{:<30} {:<30} {:<9} {:<16} {:<9} {:<9} {:<9} {:<9} {}",
+            row.workspace.as_deref().unwrap_or("(unknown)"),
+            row.mount,
+            opt(row.runtime_image),
+            base,
+            opt(row.build_image),
+            format_size(row.volumes),
+            format_size(row.staging),
+            opt(row.writable),
+            format_size(row.reclaimable)
+        ));
+    }
+    lines.push(format!(
+        "{:<30} {:<30} {:<9} {:<16} {:<9} {:<9} {:<9} {:<9} {}",
+        "TOTAL",
+        "",
+        format_size(totals.runtime_images),
+        format_size(totals.base_images),
+        format_size(totals.build_images),
+        format_size(totals.volumes),
+        format_size(totals.staging),
+        format_size(totals.writable),
+        format_size(totals.reclaimable)
+    ));
+    lines.push(String::new());
+    lines.push(format!(
+        "Reclaimable with `dcx clean --all --purge`: {} (estimate)",
+        format_size(totals.reclaimable)
+    ));
+    lines.join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // --- JSON field names (the `--output json` schema) ---
+
+    #[test]
+    fn df_row_serializes_without_internal_ids() {
+        let row = DfRow {
+            mount: "dcx-a-1".to_string(),
+            base_image_id: Some("abc".to_string()),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&row).unwrap();
+        let mut keys: Vec<&str> = json
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(|k| k.as_str())
+            .collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                "base_image",
+                "base_image_shared",
+                "build_image",
+                "mount",
+                "reclaimable",
+                "runtime_image",
+                "staging",
+                "volumes",
+                "workspace",
+                "writable"
+            ]
+        );
+    }
+
+    #[test]
+    fn status_row_serializes_with_stable_field_names() {
+        let row = StatusRow {
+            workspace: None,
+            mount: "dcx-a-1".to_string(),
+            container: Some("abc".to_string()),
+            network: None,
+            state: "running".to_string(),
+            daemon: "running".to_string(),
+            daemon_error: None,
+            resources: None,
+            expires_in: None,
+        };
+        let v = serde_json::to_value(&row).unwrap();
+        let mut keys: Vec<&str> = v.as_object().unwrap().keys().map(|k| k.as_str()).collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                "container",
+                "daemon",
+                "daemon_error",
+                "expires_in",
+                "mount",
+                "network",
+                "resources",
+                "state",
+                "workspace"
+            ]
+        );
+        assert!(v["workspace"].is_null());
+    }
+
+    #[test]
+    fn dry_run_plan_serializes_with_stable_field_names() {
+        let plan = DryRunPlan {
+            mount_name: "dcx-a-1".to_string(),
+            state: "orphaned".to_string(),
+            container_ids: vec!["abc".to_string()],
+            runtime_image_id: None,
+            has_base_image_tag: false,
+            volumes: vec![],
+            is_mounted: true,
+        };
+        let v = serde_json::to_value(&plan).unwrap();
+        assert_eq!(v["mount_name"], "dcx-a-1");
+        assert_eq!(v["container_ids"][0], "abc");
+        assert_eq!(v["is_mounted"], true);
+        assert!(v["runtime_image_id"].is_null());
+    }
+
     // --- format_status_table ---
 
     #[test]
@@ -179,6 +874,29 @@ mod tests {
         assert_eq!(format_status_table(&[]), "No active workspaces.");
     }
 
+    #[test]
+    fn status_table_shows_time_until_expiry() {
+        let row = |expires_in| StatusRow {
+            workspace: Some("/home/user/project-a".to_string()),
+            mount: "dcx-project-a-a1b2c3d4".to_string(),
+            container: Some("abc123".to_string()),
+            network: Some("minimal".to_string()),
+            state: "running".to_string(),
+            daemon: "running".to_string(),
+            daemon_error: None,
+            resources: None,
+            expires_in,
+        };
+        let out = format_status_table(&[row(Some(5400)), row(Some(0)), row(None)]);
+        assert!(
+            out.lines().next().unwrap().ends_with("EXPIRES"),
+            "got: {out}"
+        );
+        assert!(out.lines().nth(1).unwrap().ends_with("in 1h"), "got: {out}");
+        assert!(out.lines().nth(2).unwrap().ends_with("now"), "got: {out}");
+        assert!(out.lines().nth(3).unwrap().ends_with("–"), "got: {out}");
+    }
+
     #[test]
     fn status_table_header_present() {
         let rows = vec![StatusRow {
@@ -188,6 +906,9 @@ mod tests {
             network: Some("minimal".to_string()),
             state: "running".to_string(),
             daemon: "running".to_string(),
+            daemon_error: None,
+            resources: None,
+            expires_in: None,
         }];
         let out = format_status_table(&rows);
         assert!(out.contains("WORKSPACE"), "missing WORKSPACE header");
@@ -206,6 +927,9 @@ mod tests {
             network: Some("minimal".to_string()),
             state: "running".to_string(),
             daemon: "running".to_string(),
+            daemon_error: None,
+            resources: None,
+            expires_in: None,
         }];
         let out = format_status_table(&rows);
         assert!(out.contains("/home/user/project-a"));
@@ -224,6 +948,9 @@ mod tests {
             network: Some("minimal".to_string()),
             state: "running".to_string(),
             daemon: "running".to_string(),
+            daemon_error: None,
+            resources: None,
+            expires_in: None,
         }];
         let out = format_status_table(&rows);
         let mut lines = out.lines();
@@ -246,6 +973,9 @@ mod tests {
             network: None,
             state: "stale mount".to_string(),
             daemon: "–".to_string(),
+            daemon_error: None,
+            resources: None,
+            expires_in: None,
         }];
         let out = format_status_table(&rows);
         assert!(out.contains("(unknown)"));
@@ -263,6 +993,9 @@ mod tests {
                 network: Some("minimal".to_string()),
                 state: "running".to_string(),
                 daemon: "running".to_string(),
+                daemon_error: None,
+                resources: None,
+                expires_in: None,
             },
             StatusRow {
                 workspace: Some("/home/user/project-b".to_string()),
@@ -271,6 +1004,9 @@ mod tests {
                 network: Some("open".to_string()),
                 state: "orphaned".to_string(),
                 daemon: "stopped".to_string(),
+                daemon_error: None,
+                resources: None,
+                expires_in: None,
             },
         ];
         let out = format_status_table(&rows);
@@ -443,6 +1179,51 @@ mod tests {
         assert!(!out.contains("→  dcx-old-thing"));
     }
 
+    // --- format_down_summary ---
+
+    #[test]
+    fn format_down_summary_counts_only_workspaces_taken_down() {
+        let entries = vec![
+            CleanEntry {
+                workspace: Some("/home/u/a".to_string()),
+                mount: "dcx-a-a1b2c3d4".to_string(),
+                was: "running".to_string(),
+                action: "stopped, removed, unmounted".to_string(),
+            },
+            CleanEntry {
+                workspace: Some("/home/u/b".to_string()),
+                mount: "dcx-b-e5f6g7h8".to_string(),
+                was: "not found".to_string(),
+                action: "none".to_string(),
+            },
+        ];
+        let out = format_down_summary(&entries);
+        assert!(out.starts_with("Took down 1 workspace:"), "got: {out}");
+        assert!(out.contains("/home/u/a  \u{2192}  dcx-a-a1b2c3d4"));
+        assert!(out.contains("\u{2192} nothing to do"));
+    }
+
+    // --- format_staging_dry_run ---
+
+    #[test]
+    fn format_staging_dry_run_lists_daemon_and_directory() {
+        assert_eq!(format_staging_dry_run(&[]), "");
+        let out = format_staging_dry_run(&[
+            StagingPlan {
+                staging_dir: ".dcx-a-a1b2c3d4-files".to_string(),
+                daemon_pid: Some(4242),
+            },
+            StagingPlan {
+                staging_dir: ".dcx-b-e5f6g7h8-files".to_string(),
+                daemon_pid: None,
+            },
+        ]);
+        assert!(out.starts_with("Would remove orphaned staging directories:"));
+        assert!(out.contains("  .dcx-a-a1b2c3d4-files\n    - Stop sync daemon (pid 4242)"));
+        assert_eq!(out.matches("Remove staging directory").count(), 2);
+        assert_eq!(out.matches("Stop sync daemon").count(), 1);
+    }
+
     // --- format_dry_run ---
 
     #[test]
@@ -604,4 +1385,287 @@ mod tests {
             "third container ID missing: {out}"
         );
     }
+
+    // --- format_exec_all_summary ---
+
+    #[test]
+    fn exec_all_summary_lists_rows_and_success_footer() {
+        let rows = vec![ExecAllRow {
+            workspace: "proj".to_string(),
+            container: "abc123".to_string(),
+            exit_code: Some(0),
+            seconds: 1.25,
+        }];
+        let out = format_exec_all_summary(&rows);
+        assert!(out.contains("WORKSPACE"), "got: {out}");
+        assert!(out.contains("proj"), "got: {out}");
+        assert!(out.contains("1.2s") || out.contains("1.3s"), "got: {out}");
+        assert!(out.contains("1 workspace succeeded."), "got: {out}");
+    }
+
+    #[test]
+    fn exec_all_summary_counts_failures_and_spawn_errors() {
+        let rows = vec![
+            ExecAllRow {
+                workspace: "a".to_string(),
+                container: "c1".to_string(),
+                exit_code: Some(2),
+                seconds: 0.1,
+            },
+            ExecAllRow {
+                workspace: "b".to_string(),
+                container: "c2".to_string(),
+                exit_code: None,
+                seconds: 0.0,
+            },
+            ExecAllRow {
+                workspace: "c".to_string(),
+                container: "c3".to_string(),
+                exit_code: Some(0),
+                seconds: 0.2,
+            },
+        ];
+        let out = format_exec_all_summary(&rows);
+        assert!(out.contains("error"), "got: {out}");
+        assert!(out.contains("2 of 3 workspaces failed."), "got: {out}");
+    }
+
+    // --- format_daemon_table ---
+
+    #[test]
+    fn daemon_table_lists_workspaces() {
+        let rows = vec![DaemonRow {
+            workspace: "dcx-proj-abcd1234".to_string(),
+            files: 2,
+            sync: "running".to_string(),
+        }];
+        let out = format_daemon_table(&rows);
+        let lines: Vec<&str> = out.lines().collect();
+        assert!(lines[0].starts_with("WORKSPACE"), "got: {out}");
+        assert!(lines[1].starts_with("dcx-proj-abcd1234"), "got: {out}");
+        assert!(lines[1].ends_with(" 2      running"), "got: {out}");
+    }
+
+    // --- format_jobs_table ---
+
+    #[test]
+    fn jobs_table_shows_exit_code_or_dash() {
+        let rows = vec![
+            JobRow {
+                id: "deadbeef".to_string(),
+                workspace: "proj".to_string(),
+                state: "running".to_string(),
+                exit_code: None,
+                started: "5m ago".to_string(),
+                command: "cargo watch".to_string(),
+            },
+            JobRow {
+                id: "cafef00d".to_string(),
+                workspace: "proj".to_string(),
+                state: "exited".to_string(),
+                exit_code: Some(1),
+                started: "1h ago".to_string(),
+                command: "make test".to_string(),
+            },
+        ];
+        let out = format_jobs_table(&rows);
+        let lines: Vec<&str> = out.lines().collect();
+        assert!(lines[0].starts_with("ID"), "got: {out}");
+        assert!(
+            lines[1].contains("running") && lines[1].contains(" - "),
+            "got: {out}"
+        );
+        assert!(
+            lines[2].contains("exited") && lines[2].contains(" 1 "),
+            "got: {out}"
+        );
+        assert!(lines[2].ends_with("make test"), "got: {out}");
+    }
+
+    // --- format_sessions_table ---
+
+    #[test]
+    fn sessions_table_lists_rows() {
+        let rows = vec![SessionRow {
+            id: "1700000000-00ab".to_string(),
+            workspace: "proj".to_string(),
+            started: "2h ago".to_string(),
+            length: "12m".to_string(),
+            command: "bash".to_string(),
+        }];
+        let out = format_sessions_table(&rows);
+        assert!(out.lines().next().unwrap().starts_with("ID"), "got: {out}");
+        assert!(out.contains("1700000000-00ab"), "got: {out}");
+        assert!(out.lines().nth(1).unwrap().ends_with("bash"), "got: {out}");
+    }
+
+    #[test]
+    fn status_resources_table_adds_usage_columns_and_footer() {
+        let rows = vec![
+            StatusRow {
+                workspace: Some("/home/user/project-a".to_string()),
+                mount: "dcx-project-a-a1b2c3d4".to_string(),
+                container: Some("abc123".to_string()),
+                network: Some("minimal".to_string()),
+                state: "running".to_string(),
+                daemon: "running".to_string(),
+                daemon_error: None,
+                resources: Some(ContainerResources {
+                    cpu_percent: "12.34%".to_string(),
+                    memory: "512MiB / 7.7GiB".to_string(),
+                    pids: "42".to_string(),
+                    net_io: "1.2MB / 340kB".to_string(),
+                    writable_size: "12.3kB".to_string(),
+                }),
+                expires_in: None,
+            },
+            StatusRow {
+                workspace: None,
+                mount: "dcx-project-b-b2c3d4e5".to_string(),
+                container: None,
+                network: None,
+                state: "stale mount".to_string(),
+                daemon: "–".to_string(),
+                daemon_error: None,
+                resources: None,
+                expires_in: None,
+            },
+        ];
+        let usage = DiskUsage {
+            images: 2,
+            image_bytes: 1_210_000_000,
+            volumes: 1,
+            volume_bytes: 540_000_000,
+        };
+        let out = format_status_resources_table(&rows, &usage);
+        let header = out.lines().next().unwrap();
+        for col in ["CPU %", "MEMORY", "PIDS", "NET I/O", "WRITABLE"] {
+            assert!(header.contains(col), "missing {col}: {header}");
+        }
+        assert!(out.contains("512MiB / 7.7GiB"), "got: {out}");
+        assert!(out.contains("12.3kB"), "got: {out}");
+        assert_eq!(
+            out.lines().last().unwrap(),
+            "dcx disk usage: images 1.21GB (2 images), volumes 540MB (1 volume)"
+        );
+    }
+
+    #[test]
+    fn status_resources_table_without_rows_still_prints_footer() {
+        let out = format_status_resources_table(&[], &DiskUsage::default());
+        assert!(out.starts_with("No active workspaces."), "got: {out}");
+        assert!(out.contains("dcx disk usage: images 0B"), "got: {out}");
+    }
+
+    #[test]
+    fn inspect_report_lists_sections() {
+        let report = InspectReport {
+            workspace: "/home/user/proj".to_string(),
+            mount_name: "dcx-proj-a1b2c3d4".to_string(),
+            mount_point: "~/.colima-mounts/dcx-proj-a1b2c3d4".to_string(),
+            bindfs_source: Some("/home/user/proj".to_string()),
+            state: "running".to_string(),
+            containers: vec![InspectContainer {
+                id: "abc123".to_string(),
+                state: "running".to_string(),
+                image: Some("vsc-proj-uid".to_string()),
+            }],
+            base_image_tag: None,
+            network: Some("minimal".to_string()),
+            volumes: vec!["dcx-cache".to_string()],
+            staging_dir: "~/.colima-mounts/.dcx-proj-a1b2c3d4-files".to_string(),
+            staged_files: vec![StagedFile {
+                name: ".gitconfig".to_string(),
+                source: Some("~/.gitconfig".to_string()),
+                sync: "hardlink".to_string(),
+            }],
+            daemon_pid: Some(4242),
+            daemon: "running".to_string(),
+            daemon_error: None,
+            injected_mounts: vec![InjectedMount {
+                source: "~/.claude".to_string(),
+                target: "/home/node/.claude".to_string(),
+                writable: true,
+            }],
+            injected_env: vec![],
+            config_files: vec![ConfigFile {
+                kind: "dcx_config.yaml".to_string(),
+                path: None,
+            }],
+        };
+        let out = format_inspect_report(&report);
+        assert!(
+            out.contains(
+                "Mount:        ~/.colima-mounts/dcx-proj-a1b2c3d4 (bindfs from /home/user/proj)"
+            ),
+            "got: {out}"
+        );
+        assert!(out.contains("abc123"), "got: {out}");
+        assert!(out.contains("Base image:   (none)"), "got: {out}");
+        assert!(out.contains("Volumes:      dcx-cache"), "got: {out}");
+        assert!(
+            out.contains("Sync daemon:  running (PID 4242)"),
+            "got: {out}"
+        );
+        assert!(out.contains(".gitconfig"), "got: {out}");
+        assert!(
+            out.contains("~/.claude → /home/node/.claude (rw)"),
+            "got: {out}"
+        );
+        assert!(out.contains("Env:          (none)"), "got: {out}");
+        assert!(out.contains("(not found)"), "got: {out}");
+    }
+
+    #[test]
+    fn status_table_lists_daemon_errors_below_table() {
+        let rows = vec![StatusRow {
+            workspace: Some("/home/user/project-a".to_string()),
+            mount: "dcx-project-a-a1b2c3d4".to_string(),
+            container: Some("abc123".to_string()),
+            network: Some("minimal".to_string()),
+            state: "running".to_string(),
+            daemon: "running".to_string(),
+            daemon_error: Some("5m ago: sync: rejecting staging->source".to_string()),
+            resources: None,
+            expires_in: None,
+        }];
+        let out = format_status_table(&rows);
+        assert!(out.contains("Sync daemon errors"), "got: {out}");
+        assert!(
+            out.lines()
+                .last()
+                .unwrap()
+                .contains("dcx-project-a-a1b2c3d4  5m ago: sync: rejecting"),
+            "got: {out}"
+        );
+    }
+
+    // --- format_df_table ---
+
+    #[test]
+    fn format_df_table_marks_shared_bases_and_prints_totals() {
+        let rows = vec![DfRow {
+            workspace: Some("/home/u/proj".to_string()),
+            mount: "dcx-proj-a1b2c3d4".to_string(),
+            runtime_image: Some(1_210_000_000),
+            base_image: Some(890_000_000),
+            base_image_shared: true,
+            reclaimable: 372_000_000,
+            ..Default::default()
+        }];
+        let totals = DfTotals {
+            runtime_images: 1_210_000_000,
+            base_images: 890_000_000,
+            reclaimable: 372_000_000,
+            ..Default::default()
+        };
+        let out = format_df_table(&rows, &totals);
+        assert!(out.contains("890MB (shared)"), "got: {out}");
+        assert!(out.lines().any(|l| l.starts_with("TOTAL")), "got: {out}");
+        assert!(out.contains("Reclaimable with `dcx clean --all --purge`: 372MB"));
+        assert_eq!(
+            format_df_table(&[], &DfTotals::default()),
+            "No dcx workspaces."
+        );
+    }
 }
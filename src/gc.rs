@@ -0,0 +1,442 @@
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use crate::clean;
+use crate::dcx_config::{self, DcxGcConfig};
+use crate::docker;
+use crate::exit_codes;
+use crate::format::{self, CleanEntry};
+use crate::naming::{relay_dir, scan_relay};
+use crate::progress;
+use crate::size::{format_size, parse_size};
+use crate::state;
+use crate::status::dcx_disk_usage;
+use crate::workspace::resolve_workspace;
+
+/// Minimum time between two opportunistic GC runs from `dcx up`, in seconds.
+const UP_CHECK_INTERVAL: u64 = 3600;
+
+// ── Data structures ───────────────────────────────────────────────────────────
+
+/// A validated GC policy. Workspaces with a running container are never collected.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GcPolicy {
+    /// Maximum total size of dcx images (runtime images and `dcx-base:*` tags), in bytes.
+    pub max_image_bytes: Option<u64>,
+    /// Maximum number of workspaces without a running container.
+    pub max_idle: Option<usize>,
+    /// Number of most recently used workspaces that are always kept.
+    pub keep: usize,
+}
+
+impl GcPolicy {
+    /// Validate `gc:` from dcx_config.yaml.
+    pub fn from_config(cfg: &DcxGcConfig) -> Result<Self, String> {
+        let max_image_bytes = cfg
+            .max_image_size
+            .as_deref()
+            .map(|v| {
+                parse_size(v).ok_or_else(|| {
+                    format!("Invalid gc.max_image_size '{v}'. Use a size like 500MB or 20GB.")
+                })
+            })
+            .transpose()?;
+        Ok(Self {
+            max_image_bytes,
+            max_idle: cfg.max_idle_workspaces,
+            keep: cfg.keep.unwrap_or(0),
+        })
+    }
+
+    /// True when no limit is set (`keep` alone never collects anything).
+    pub fn is_empty(&self) -> bool {
+        self.max_image_bytes.is_none() && self.max_idle.is_none()
+    }
+}
+
+/// A workspace considered for collection.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GcCandidate {
+    pub mount_name: String,
+    pub mount_point: PathBuf,
+    /// Containers (running or stopped) of the workspace.
+    pub container_ids: Vec<String>,
+    pub running: bool,
+    /// Last `dcx up`/`dcx exec`, falling back to the container's creation time.
+    pub last_active: Option<u64>,
+    /// Size of the workspace's runtime image and `dcx-base:<mount>` tag, in bytes.
+    pub image_bytes: u64,
+}
+
+// ── Pure functions ────────────────────────────────────────────────────────────
+
+/// Choose the workspaces to collect, oldest activity first.
+///
+/// Running workspaces and the `keep` most recently used ones are protected. Idle
+/// workspaces are removed while there are more than `max_idle`, then while the estimated
+/// image total (each removal subtracts `image_bytes`) is over `max_image_bytes`.
+/// Returns `(candidate index, reason)` pairs.
+pub fn plan_gc(
+    policy: &GcPolicy,
+    candidates: &[GcCandidate],
+    total_image_bytes: u64,
+) -> Vec<(usize, String)> {
+    // Most recent first; never-used workspaces sort last.
+    let mut order: Vec<usize> = (0..candidates.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(candidates[i].last_active));
+    // Removable workspaces, oldest activity first.
+    let mut removable = order
+        .into_iter()
+        .skip(policy.keep)
+        .filter(|&i| !candidates[i].running)
+        .rev();
+
+    let mut victims = Vec::new();
+    let mut total = total_image_bytes;
+    if let Some(max) = policy.max_idle {
+        let mut idle = candidates.iter().filter(|c| !c.running).count();
+        while idle > max {
+            let Some(i) = removable.next() else { break };
+            victims.push((i, format!("more than {max} idle workspaces")));
+            total = total.saturating_sub(candidates[i].image_bytes);
+            idle -= 1;
+        }
+    }
+    if let Some(max) = policy.max_image_bytes {
+        while total > max {
+            let Some(i) = removable.next() else { break };
+            victims.push((i, format!("dcx images over {}", format_size(max))));
+            total = total.saturating_sub(candidates[i].image_bytes);
+        }
+    }
+    victims
+}
+
+// ── Docker queries ────────────────────────────────────────────────────────────
+
+/// Scan the relay for GC candidates and measure the total dcx image size.
+fn collect_candidates(home: &Path) -> (Vec<GcCandidate>, u64) {
+    let images = docker::list_images();
+    let size_of = |reference: &str| {
+        images
+            .iter()
+            .find(|i| i.reference == reference)
+            .and_then(|i| parse_size(&i.size))
+            .unwrap_or(0)
+    };
+    let candidates = scan_relay(&relay_dir(home))
+        .into_iter()
+        .map(|mount_point| {
+            let mount_name = mount_point
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let container_ids = docker::query_container_any(&mount_point);
+            let runtime_bytes = container_ids
+                .first()
+                .and_then(|id| docker::get_runtime_image_ref(id).ok())
+                .map(|r| size_of(&r))
+                .unwrap_or(0);
+            let last_active =
+                clean::last_activity(home, &mount_name, &container_ids).or_else(|| {
+                    container_ids
+                        .first()
+                        .and_then(|id| docker::container_created(id))
+                });
+            GcCandidate {
+                running: docker::query_container(&mount_point).is_some(),
+                image_bytes: runtime_bytes + size_of(&format!("dcx-base:{mount_name}")),
+                mount_name,
+                mount_point,
+                container_ids,
+                last_active,
+            }
+        })
+        .collect();
+    (candidates, dcx_disk_usage(&images, &[]).image_bytes)
+}
+
+/// Clean the chosen workspaces with `--purge` semantics, including the `dcx-*` volumes
+/// their containers use.
+///
+/// Like `dcx clean --idle-for`, no global orphan sweep runs: those are not scoped to a
+/// workspace and would also remove stopped containers and volumes of workspaces the
+/// policy keeps. Returns the cleaned entries and any failures.
+fn apply_plan(
+    home: &Path,
+    candidates: &[GcCandidate],
+    victims: &[(usize, String)],
+) -> (Vec<CleanEntry>, Vec<String>) {
+    let mut cleaned = Vec::new();
+    let mut failures = Vec::new();
+    for (i, reason) in victims {
+        let c = &candidates[*i];
+        progress::step(&format!("Collecting {} ({reason})...", c.mount_name));
+        // Read the volumes before clean_one removes the containers.
+        let volumes = c
+            .container_ids
+            .first()
+            .and_then(|id| docker::get_container_volumes(id).ok())
+            .unwrap_or_default();
+        match clean::clean_one(home, &c.mount_point, &c.container_ids, true) {
+            Ok((was, action)) => {
+                for volume in &volumes {
+                    if let Err(e) = docker::remove_volume(volume) {
+                        eprintln!("Note: Could not remove volume {volume}: {e}");
+                    }
+                }
+                cleaned.push(CleanEntry {
+                    workspace: None,
+                    mount: c.mount_name.clone(),
+                    was,
+                    action,
+                });
+            }
+            Err(e) => failures.push(format!("{}: {e}", c.mount_point.display())),
+        }
+    }
+    (cleaned, failures)
+}
+
+// ── Entry points ──────────────────────────────────────────────────────────────
+
+/// Command-line overrides for the `gc:` section of dcx_config.yaml.
+#[derive(Clone, Debug, Default)]
+pub struct GcOverrides {
+    pub max_image_size: Option<String>,
+    pub max_idle: Option<usize>,
+    pub keep: Option<usize>,
+}
+
+/// Run `dcx gc`: apply the GC policy from the workspace's dcx_config.yaml (flags win).
+///
+/// Returns the exit code that `main` should pass to `std::process::exit`.
+pub fn run_gc(
+    home: &Path,
+    workspace_folder: Option<PathBuf>,
+    overrides: GcOverrides,
+    dry_run: bool,
+) -> i32 {
+    let mut cfg = resolve_workspace(workspace_folder.as_deref())
+        .ok()
+        .and_then(|ws| dcx_config::find_dcx_config(&ws))
+        .map(|p| dcx_config::read_dcx_config(&p).gc)
+        .unwrap_or_default();
+    if overrides.max_image_size.is_some() {
+        cfg.max_image_size = overrides.max_image_size;
+    }
+    if overrides.max_idle.is_some() {
+        cfg.max_idle_workspaces = overrides.max_idle;
+    }
+    if overrides.keep.is_some() {
+        cfg.keep = overrides.keep;
+    }
+    let policy = match GcPolicy::from_config(&cfg) {
+        Ok(p) if !p.is_empty() => p,
+        Ok(_) => {
+            eprintln!(
+                "No GC policy configured. Set `gc.max_image_size` or `gc.max_idle_workspaces` \
+                 in dcx_config.yaml, or pass --max-image-size / --max-idle."
+            );
+            return exit_codes::USAGE_ERROR;
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            return exit_codes::USAGE_ERROR;
+        }
+    };
+
+    if !docker::is_docker_available() {
+        eprintln!("Docker is not available. Is Colima running?");
+        return exit_codes::RUNTIME_ERROR;
+    }
+
+    progress::step("Scanning workspaces...");
+    let (candidates, total) = collect_candidates(home);
+    let victims = plan_gc(&policy, &candidates, total);
+    if victims.is_empty() {
+        println!(
+            "Within GC policy ({} of dcx images, {} idle workspace(s)). Nothing to collect.",
+            format_size(total),
+            candidates.iter().filter(|c| !c.running).count()
+        );
+        return exit_codes::SUCCESS;
+    }
+    if dry_run {
+        println!("Would collect:");
+        for (i, reason) in &victims {
+            println!("  {}  ({reason})", candidates[*i].mount_name);
+        }
+        return exit_codes::SUCCESS;
+    }
+
+    let (cleaned, failures) = apply_plan(home, &candidates, &victims);
+    if !cleaned.is_empty() {
+        println!("{}", format::format_clean_summary(&cleaned, 0));
+    }
+    for f in &failures {
+        eprintln!("Error: {f}");
+    }
+    if failures.is_empty() {
+        exit_codes::SUCCESS
+    } else {
+        exit_codes::RUNTIME_ERROR
+    }
+}
+
+/// Apply the GC policy from `dcx up`, at most once per `UP_CHECK_INTERVAL`.
+///
+/// Best effort: an invalid policy is reported and skipped, failures are warnings.
+/// The workspace being brought up is running, so it is never collected.
+pub fn auto_gc(home: &Path, cfg: &DcxGcConfig) {
+    let policy = match GcPolicy::from_config(cfg) {
+        Ok(p) if !p.is_empty() => p,
+        Ok(_) => return,
+        Err(e) => {
+            eprintln!("Warning: {e} Skipping garbage collection.");
+            return;
+        }
+    };
+    let stamp = state::state_root(home).join("gc-last-run");
+    let now = state::unix_now();
+    let last = std::fs::read_to_string(&stamp)
+        .ok()
+        .and_then(|s| s.trim().parse::<u64>().ok());
+    if last.is_some_and(|t| now.saturating_sub(t) < UP_CHECK_INTERVAL) {
+        return;
+    }
+    let _ = std::fs::create_dir_all(state::state_root(home));
+    let _ = std::fs::write(&stamp, format!("{now}\n"));
+
+    progress::step("Checking GC policy...");
+    let (candidates, total) = collect_candidates(home);
+    let victims = plan_gc(&policy, &candidates, total);
+    if victims.is_empty() {
+        return;
+    }
+    let (cleaned, failures) = apply_plan(home, &candidates, &victims);
+    if !cleaned.is_empty() {
+        progress::step(&format!(
+            "GC policy: collected {} idle workspace(s).",
+            cleaned.len()
+        ));
+    }
+    for f in &failures {
+        eprintln!("Warning: GC could not clean {f}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(name: &str, running: bool, last_active: Option<u64>, bytes: u64) -> GcCandidate {
+        GcCandidate {
+            mount_name: name.to_string(),
+            mount_point: PathBuf::from(format!("/relay/{name}")),
+            container_ids: vec![],
+            running,
+            last_active,
+            image_bytes: bytes,
+        }
+    }
+
+    fn names(candidates: &[GcCandidate], victims: &[(usize, String)]) -> Vec<String> {
+        victims
+            .iter()
+            .map(|(i, _)| candidates[*i].mount_name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn from_config_parses_size_and_rejects_garbage() {
+        let policy = GcPolicy::from_config(&DcxGcConfig {
+            max_image_size: Some("20GB".to_string()),
+            max_idle_workspaces: Some(3),
+            keep: None,
+        })
+        .unwrap();
+        assert_eq!(policy.max_image_bytes, Some(20_000_000_000));
+        assert_eq!(policy.max_idle, Some(3));
+        assert_eq!(policy.keep, 0);
+        assert!(
+            GcPolicy::from_config(&DcxGcConfig::default())
+                .unwrap()
+                .is_empty()
+        );
+        let err = GcPolicy::from_config(&DcxGcConfig {
+            max_image_size: Some("lots".to_string()),
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert!(err.contains("gc.max_image_size"), "got: {err}");
+    }
+
+    #[test]
+    fn plan_gc_removes_oldest_idle_over_limit() {
+        let c = vec![
+            candidate("a", false, Some(10), 0),
+            candidate("b", false, Some(30), 0),
+            candidate("c", false, Some(20), 0),
+            candidate("d", true, Some(1), 0),
+        ];
+        let policy = GcPolicy {
+            max_idle: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(names(&c, &plan_gc(&policy, &c, 0)), vec!["a", "c"]);
+    }
+
+    #[test]
+    fn plan_gc_never_touches_running_or_kept_workspaces() {
+        let c = vec![
+            candidate("old-running", true, Some(1), 100),
+            candidate("recent", false, Some(50), 100),
+            candidate("old", false, Some(5), 100),
+        ];
+        let policy = GcPolicy {
+            max_idle: Some(0),
+            keep: 2,
+            ..Default::default()
+        };
+        // The two most recently used are "recent" and "old"; the running one is protected anyway.
+        assert!(plan_gc(&policy, &c, 0).is_empty());
+        let policy = GcPolicy {
+            max_idle: Some(0),
+            keep: 1,
+            ..Default::default()
+        };
+        assert_eq!(names(&c, &plan_gc(&policy, &c, 0)), vec!["old"]);
+    }
+
+    #[test]
+    fn plan_gc_image_budget_stops_once_under_limit() {
+        let c = vec![
+            candidate("a", false, Some(10), 4_000),
+            candidate("b", false, Some(20), 4_000),
+            candidate("c", false, Some(30), 4_000),
+        ];
+        let policy = GcPolicy {
+            max_image_bytes: Some(6_000),
+            ..Default::default()
+        };
+        let victims = plan_gc(&policy, &c, 12_000);
+        assert_eq!(names(&c, &victims), vec!["a", "b"]);
+        assert!(victims[0].1.contains("dcx images over 6.00kB"));
+        assert!(plan_gc(&policy, &c, 5_000).is_empty());
+    }
+
+    #[test]
+    fn plan_gc_never_used_workspaces_go_first() {
+        let c = vec![
+            candidate("used", false, Some(10), 0),
+            candidate("never", false, None, 0),
+        ];
+        let policy = GcPolicy {
+            max_idle: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(names(&c, &plan_gc(&policy, &c, 0)), vec!["never"]);
+    }
+}
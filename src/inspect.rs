@@ -0,0 +1,344 @@
+#![allow(dead_code)]
+
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+use crate::colima;
+use crate::daemon;
+use crate::dcx_config;
+use crate::docker;
+use crate::drift;
+use crate::duration::format_age;
+use crate::exit_codes;
+use crate::format::{
+    ConfigFile, InjectedEnv, InjectedMount, InspectContainer, InspectReport, StagedFile,
+    format_inspect_report,
+};
+use crate::mount_table;
+use crate::naming::{is_dcx_managed_path, mount_name, relay_dir};
+use crate::network_mode::{self, NetworkPolicy};
+use crate::output;
+use crate::platform;
+use crate::progress;
+use crate::state;
+use crate::status::{daemon_status, mount_state_label, network_label};
+use crate::sync;
+use crate::up::{build_env_overrides, find_dcx_config_path, staging_dir, tilde_path};
+use crate::workspace::{find_devcontainer_config, resolve_workspace};
+
+/// Name of the sync daemon PID file inside the staging directory.
+const DAEMON_PID_FILE: &str = ".sync-daemon.pid";
+
+// ── Pure functions ────────────────────────────────────────────────────────────
+
+/// A host file that `dcx up` may have staged, and whether it is synced by the daemon.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StageSource {
+    pub path: PathBuf,
+    pub sync: bool,
+}
+
+/// How a staged file tracks its host source.
+///
+/// - `synced` / `out of sync`: a `sync: true` file, compared by content hash
+/// - `hardlink`: shares the source's inode, so writes go both ways
+/// - `copy` / `stale copy`: a readonly copy, equal or not to the source
+/// - `source missing`: the host file is gone
+pub fn staged_file_status(staged: &Path, source: &Path, sync: bool) -> String {
+    let Ok(src_meta) = std::fs::metadata(source) else {
+        return "source missing".to_string();
+    };
+    let same_content = || {
+        let a = sync::sha256_file(staged);
+        a.is_some() && a == sync::sha256_file(source)
+    };
+    let label = if sync {
+        if same_content() {
+            "synced"
+        } else {
+            "out of sync"
+        }
+    } else if std::fs::metadata(staged)
+        .is_ok_and(|m| m.dev() == src_meta.dev() && m.ino() == src_meta.ino())
+    {
+        "hardlink"
+    } else if same_content() {
+        "copy"
+    } else {
+        "stale copy"
+    };
+    label.to_string()
+}
+
+/// Files in the staging directory (excluding dcx's own bookkeeping files), sorted by name.
+pub fn list_staged(staging: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(staging)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().is_file())
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .filter(|n| n != DAEMON_PID_FILE && !n.ends_with(sync::CONFLICT_MARKER_SUFFIX))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+// ── Entry point ───────────────────────────────────────────────────────────────
+
+/// Run `dcx inspect`: print everything dcx knows about one workspace.
+///
+/// Returns the exit code that `main` should pass to `std::process::exit`.
+pub fn run_inspect(home: &Path, workspace_folder: Option<PathBuf>) -> i32 {
+    if !docker::is_docker_available() {
+        eprintln!("Docker is not available. Is Colima running?");
+        return exit_codes::RUNTIME_ERROR;
+    }
+
+    let workspace = match resolve_workspace(workspace_folder.as_deref()) {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("{e}");
+            return exit_codes::USAGE_ERROR;
+        }
+    };
+    let relay = relay_dir(home);
+    if is_dcx_managed_path(&workspace, &relay) {
+        eprintln!(
+            "Cannot use a dcx-managed mount point as a workspace. \
+             Use the original workspace path instead."
+        );
+        return exit_codes::USAGE_ERROR;
+    }
+    progress::step(&format!("Inspecting workspace: {}", workspace.display()));
+
+    let name = mount_name(&workspace);
+    let mount_point = relay.join(&name);
+    let staging = staging_dir(&mount_point);
+
+    // Mount and containers.
+    let table = platform::read_mount_table().unwrap_or_default();
+    let bindfs_source = mount_table::find_mount_source(&table, &mount_point).map(str::to_string);
+    let is_mounted = bindfs_source.is_some() && mount_point.metadata().is_ok();
+    let container_ids = docker::query_container_any(&mount_point);
+    let running = docker::query_container(&mount_point);
+    let containers: Vec<InspectContainer> = container_ids
+        .iter()
+        .map(|id| InspectContainer {
+            id: id.clone(),
+            state: docker::container_state(id).unwrap_or_else(|| "unknown".to_string()),
+            image: docker::container_image(id),
+        })
+        .collect();
+    let primary = running.clone().or_else(|| container_ids.first().cloned());
+
+    let base_tag = format!("dcx-base:{name}");
+    let base_image_tag = docker::image_exists(&base_tag).then_some(base_tag);
+    let network = running.as_ref().and_then(|c| {
+        let policy = docker::read_label(c, network_mode::POLICY_LABEL)
+            .and_then(|l| NetworkPolicy::parse_label(&l));
+        network_label(
+            docker::read_network_mode(c),
+            policy,
+            state::last_transition(home, &name),
+        )
+    });
+    let volumes = primary
+        .as_deref()
+        .and_then(|c| docker::get_container_volumes(c).ok())
+        .unwrap_or_default();
+
+    // Config files and the sources `dcx up` stages from.
+    let devcontainer_json = primary
+        .as_deref()
+        .and_then(|c| docker::read_label(c, "devcontainer.config_file"))
+        .map(|label| drift::host_config_path(&label, &mount_point, &workspace))
+        .or_else(|| find_devcontainer_config(&workspace));
+    let dcx_config_path = find_dcx_config_path(&workspace, None).filter(|p| p.exists());
+    let colima_path = colima::colima_config_path(home);
+    let colima_mounts = std::fs::read_to_string(&colima_path)
+        .map(|yaml| colima::filter_relay_mounts(colima::parse_colima_mounts(&yaml)))
+        .unwrap_or_default();
+
+    let mut sources: Vec<StageSource> = colima_mounts
+        .iter()
+        .map(|m| StageSource {
+            path: colima::expand_tilde(&m.location, home),
+            sync: false,
+        })
+        .collect();
+    if let Some(path) = &dcx_config_path {
+        for file in dcx_config::read_dcx_config(path).up.files {
+            sources.push(StageSource {
+                path: colima::expand_tilde(&file.path, home),
+                sync: file.sync,
+            });
+        }
+    }
+
+    let staged_files: Vec<StagedFile> = list_staged(&staging)
+        .into_iter()
+        .map(|file_name| {
+            let staged = staging.join(&file_name);
+            // Later sources win, matching `dcx up` (dcx_config.yaml files overwrite colima ones).
+            let source = sources
+                .iter()
+                .rev()
+                .find(|s| s.path.file_name() == Some(std::ffi::OsStr::new(&file_name)));
+            StagedFile {
+                name: file_name,
+                source: source.map(|s| tilde_path(&s.path, home)),
+                sync: source
+                    .map(|s| staged_file_status(&staged, &s.path, s.sync))
+                    .unwrap_or_else(|| "unknown".to_string()),
+            }
+        })
+        .collect();
+
+    // Sync daemon.
+    // The dcx daemon's PID when it syncs the workspace, else a legacy per-workspace daemon's.
+    let syncing_pid = daemon::health(home)
+        .filter(|h| h.is_syncing(&name))
+        .map(|h| h.pid);
+    let daemon_pid = syncing_pid.or_else(|| {
+        std::fs::read_to_string(staging.join(DAEMON_PID_FILE))
+            .ok()
+            .and_then(|s| s.trim().parse::<u32>().ok())
+    });
+    let daemon_error = sync::last_error(&state::daemon_log_file(home, &name)).map(|e| {
+        format!(
+            "{}: {}",
+            format_age(state::unix_now().saturating_sub(e.timestamp)),
+            e.message
+        )
+    });
+
+    // Mounts and env injected by `dcx up` (from colima.yaml and the staging directory).
+    let colima_paths: Vec<PathBuf> = colima_mounts
+        .iter()
+        .map(|m| colima::expand_tilde(&m.location, home))
+        .collect();
+    let injected_mounts: Vec<InjectedMount> = primary
+        .as_deref()
+        .map(docker::container_bind_mounts)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|m| {
+            let source = Path::new(&m.source);
+            source.starts_with(&staging) || colima_paths.iter().any(|p| p == source)
+        })
+        .map(|m| InjectedMount {
+            source: tilde_path(Path::new(&m.source), home),
+            target: m.destination,
+            writable: m.rw,
+        })
+        .collect();
+    let container_env = primary
+        .as_deref()
+        .map(docker::container_env)
+        .unwrap_or_default();
+    let injected_env: Vec<InjectedEnv> = build_env_overrides(&colima_mounts, home)
+        .into_iter()
+        .filter_map(|(key, _)| {
+            let value = container_env.iter().find(|(k, _)| *k == key)?.1.clone();
+            Some(InjectedEnv { key, value })
+        })
+        .collect();
+
+    let config_files = vec![
+        ConfigFile {
+            kind: "devcontainer.json".to_string(),
+            path: devcontainer_json.map(|p| tilde_path(&p, home)),
+        },
+        ConfigFile {
+            kind: "dcx_config.yaml".to_string(),
+            path: dcx_config_path.map(|p| tilde_path(&p, home)),
+        },
+        ConfigFile {
+            kind: "colima.yaml".to_string(),
+            path: colima_path.exists().then(|| tilde_path(&colima_path, home)),
+        },
+    ];
+
+    let report = InspectReport {
+        workspace: workspace.to_string_lossy().into_owned(),
+        mount_name: name,
+        mount_point: tilde_path(&mount_point, home),
+        bindfs_source,
+        state: mount_state_label(
+            is_mounted,
+            running.is_some(),
+            running.is_none() && !container_ids.is_empty(),
+        )
+        .to_string(),
+        containers,
+        base_image_tag,
+        network,
+        volumes,
+        staging_dir: tilde_path(&staging, home),
+        staged_files,
+        daemon_pid,
+        daemon: daemon_status(&mount_point, is_mounted, syncing_pid.is_some()),
+        daemon_error,
+        injected_mounts,
+        injected_env,
+        config_files,
+    };
+    if output::is_structured() {
+        output::emit(&report);
+    } else {
+        println!("{}", format_inspect_report(&report));
+    }
+    exit_codes::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn staged_file_status_detects_hardlinks_and_copies() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("src.txt");
+        std::fs::write(&source, "a").unwrap();
+
+        let linked = dir.path().join("linked.txt");
+        std::fs::hard_link(&source, &linked).unwrap();
+        assert_eq!(staged_file_status(&linked, &source, false), "hardlink");
+
+        let copied = dir.path().join("copied.txt");
+        std::fs::copy(&source, &copied).unwrap();
+        assert_eq!(staged_file_status(&copied, &source, false), "copy");
+        std::fs::write(&copied, "b").unwrap();
+        assert_eq!(staged_file_status(&copied, &source, false), "stale copy");
+    }
+
+    #[test]
+    fn staged_file_status_compares_synced_files_by_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("settings.json");
+        let staged = dir.path().join("staged.json");
+        std::fs::write(&source, "{}").unwrap();
+        std::fs::write(&staged, "{}").unwrap();
+        assert_eq!(staged_file_status(&staged, &source, true), "synced");
+        std::fs::write(&staged, "{\"a\":1}").unwrap();
+        assert_eq!(staged_file_status(&staged, &source, true), "out of sync");
+        assert_eq!(
+            staged_file_status(&staged, &dir.path().join("gone"), true),
+            "source missing"
+        );
+    }
+
+    #[test]
+    fn list_staged_skips_pid_file_and_sorts() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(".sync-daemon.pid"), "1").unwrap();
+        std::fs::write(dir.path().join("b.json.dcx-conflict"), "").unwrap();
+        std::fs::write(dir.path().join("b.json"), "").unwrap();
+        std::fs::write(dir.path().join(".gitconfig"), "").unwrap();
+        assert_eq!(list_staged(dir.path()), vec![".gitconfig", "b.json"]);
+        assert!(list_staged(&dir.path().join("missing")).is_empty());
+    }
+}
@@ -0,0 +1,541 @@
+#![allow(dead_code)]
+
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::cmd;
+use crate::duration::format_age;
+use crate::exec::{self, ExecFlags, PreparedExec};
+use crate::exit_codes;
+use crate::format::{JobRow, format_jobs_table};
+use crate::progress;
+use crate::signals;
+use crate::state;
+
+const META_FILE: &str = "meta.json";
+const OUTPUT_FILE: &str = "output.log";
+const EXIT_FILE: &str = "exit";
+const RUNNER_PID_FILE: &str = "runner.pid";
+
+/// Number of finished jobs kept per workspace; older ones are pruned when a job starts.
+pub const MAX_FINISHED_JOBS: usize = 20;
+
+// ── Job metadata ──────────────────────────────────────────────────────────────
+
+/// A detached command started by `dcx run --detach`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobMeta {
+    pub id: String,
+    /// Original workspace path.
+    pub workspace: PathBuf,
+    /// Mount name (state directory key).
+    pub mount_name: String,
+    pub container_id: String,
+    /// The user's command (for display).
+    pub command: Vec<String>,
+    /// Full `docker exec` argument list run by the job runner.
+    pub docker_args: Vec<String>,
+    /// Unix timestamp when the job was started.
+    pub started: u64,
+}
+
+/// Job lifecycle state derived from the runner process and exit file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobState {
+    Running,
+    Exited(i32),
+    /// Runner died without recording an exit code (e.g. host reboot).
+    Lost,
+}
+
+impl JobState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Exited(_) => "exited",
+            Self::Lost => "lost",
+        }
+    }
+}
+
+// ── Pure functions ────────────────────────────────────────────────────────────
+
+/// Generate an 8-hex-char job ID from the mount name and a nonce (time + pid).
+pub fn make_job_id(mount_name: &str, nonce: u128) -> String {
+    let digest = Sha256::digest(format!("{mount_name}:{nonce}").as_bytes());
+    digest.iter().take(4).map(|b| format!("{b:02x}")).collect()
+}
+
+/// Path of the PID file the wrapper writes inside the container.
+pub fn container_pid_path(id: &str) -> String {
+    format!("/tmp/dcx-job-{id}.pid")
+}
+
+/// Wrap `command` so its in-container PID is recorded before it starts, allowing
+/// `dcx jobs kill` to signal it. `exec` keeps the PID and exit code of the command.
+pub fn wrap_command(id: &str, command: &[String]) -> Vec<String> {
+    let mut wrapped = vec![
+        "sh".to_string(),
+        "-c".to_string(),
+        format!("echo $$ > {}; exec \"$@\"", container_pid_path(id)),
+        "sh".to_string(),
+    ];
+    wrapped.extend(command.iter().cloned());
+    wrapped
+}
+
+/// Parse the exit file: `<code> <finished_unix_ts>`.
+pub fn parse_exit_file(content: &str) -> Option<(i32, u64)> {
+    let mut parts = content.split_whitespace();
+    let code = parts.next()?.parse().ok()?;
+    let finished = parts.next().and_then(|t| t.parse().ok()).unwrap_or(0);
+    Some((code, finished))
+}
+
+/// Derive the job state: an exit file wins; otherwise running iff the runner is alive.
+pub fn job_state(exit_code: Option<i32>, runner_alive: bool) -> JobState {
+    match exit_code {
+        Some(code) => JobState::Exited(code),
+        None if runner_alive => JobState::Running,
+        None => JobState::Lost,
+    }
+}
+
+/// IDs of finished jobs to prune so that at most `keep` remain. `jobs` holds
+/// `(id, started, finished)`; running jobs are never pruned.
+pub fn jobs_to_prune(mut jobs: Vec<(String, u64, bool)>, keep: usize) -> Vec<String> {
+    jobs.retain(|(_, _, finished)| *finished);
+    jobs.sort_by_key(|j| std::cmp::Reverse(j.1));
+    jobs.into_iter().skip(keep).map(|(id, _, _)| id).collect()
+}
+
+// ── Paths ─────────────────────────────────────────────────────────────────────
+
+/// Directory holding all jobs of a workspace.
+pub fn jobs_dir(home: &Path, mount_name: &str) -> PathBuf {
+    state::state_dir(home, mount_name).join("jobs")
+}
+
+/// Find the directory of job `id` across all workspaces.
+pub fn find_job_dir(home: &Path, id: &str) -> Option<PathBuf> {
+    std::fs::read_dir(state::state_root(home))
+        .ok()?
+        .flatten()
+        .map(|e| e.path().join("jobs").join(id))
+        .find(|p| p.join(META_FILE).is_file())
+}
+
+/// All job directories across workspaces.
+pub fn all_job_dirs(home: &Path) -> Vec<PathBuf> {
+    let Ok(workspaces) = std::fs::read_dir(state::state_root(home)) else {
+        return Vec::new();
+    };
+    workspaces
+        .flatten()
+        .filter_map(|ws| std::fs::read_dir(ws.path().join("jobs")).ok())
+        .flat_map(|jobs| jobs.flatten().map(|j| j.path()))
+        .filter(|p| p.join(META_FILE).is_file())
+        .collect()
+}
+
+// ── I/O helpers ───────────────────────────────────────────────────────────────
+
+fn read_meta(job_dir: &Path) -> Option<JobMeta> {
+    let content = std::fs::read_to_string(job_dir.join(META_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn read_exit(job_dir: &Path) -> Option<(i32, u64)> {
+    parse_exit_file(&std::fs::read_to_string(job_dir.join(EXIT_FILE)).ok()?)
+}
+
+fn pid_alive(pid: &str) -> bool {
+    std::process::Command::new("kill")
+        .arg("-0")
+        .arg(pid)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+fn runner_alive(job_dir: &Path) -> bool {
+    std::fs::read_to_string(job_dir.join(RUNNER_PID_FILE))
+        .map(|pid| pid_alive(pid.trim()))
+        .unwrap_or(false)
+}
+
+fn current_state(job_dir: &Path) -> JobState {
+    job_state(read_exit(job_dir).map(|(c, _)| c), runner_alive(job_dir))
+}
+
+/// Whether any detached job of the workspace is still running.
+pub fn has_running_job(home: &Path, mount_name: &str) -> bool {
+    std::fs::read_dir(jobs_dir(home, mount_name)).is_ok_and(|entries| {
+        entries.flatten().any(|e| {
+            e.path().join(META_FILE).is_file() && current_state(&e.path()) == JobState::Running
+        })
+    })
+}
+
+/// Remove the workspace's oldest finished jobs beyond `MAX_FINISHED_JOBS` (best effort).
+fn prune_jobs(home: &Path, mount_name: &str) {
+    let dir = jobs_dir(home, mount_name);
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return;
+    };
+    let jobs = entries
+        .flatten()
+        .filter_map(|e| {
+            let meta = read_meta(&e.path())?;
+            let finished = current_state(&e.path()) != JobState::Running;
+            Some((meta.id, meta.started, finished))
+        })
+        .collect();
+    for id in jobs_to_prune(jobs, MAX_FINISHED_JOBS) {
+        let _ = std::fs::remove_dir_all(dir.join(id));
+    }
+}
+
+// ── Entry points ──────────────────────────────────────────────────────────────
+
+/// Run `dcx run`.
+///
+/// Without `--detach`, behaves like `dcx exec`. With `--detach`, starts the command as a
+/// background job: a detached `dcx _job-runner` process runs `docker exec` and records
+/// output and exit code under `~/.local/state/dcx/<mount>/jobs/<id>/`. Prints the job ID.
+pub fn run_run(
+    home: &Path,
+    workspace_folder: Option<PathBuf>,
+    config_dir: Option<PathBuf>,
+    flags: ExecFlags,
+    detach: bool,
+    command: Vec<String>,
+) -> i32 {
+    if !detach {
+        return exec::run_exec(home, workspace_folder, config_dir, flags, command);
+    }
+    if command.is_empty() {
+        eprintln!("dcx run --detach requires a command.");
+        return exit_codes::USAGE_ERROR;
+    }
+
+    let PreparedExec { target, opts } =
+        match exec::prepare_exec(home, workspace_folder, config_dir, flags) {
+            Ok(p) => p,
+            Err(code) => return code,
+        };
+    let mount_name = target
+        .mount_point
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let nonce = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0)
+        ^ u128::from(std::process::id());
+    let id = make_job_id(&mount_name, nonce);
+    prune_jobs(home, &mount_name);
+    let job_dir = jobs_dir(home, &mount_name).join(&id);
+    if let Err(e) = std::fs::create_dir_all(&job_dir) {
+        eprintln!("Failed to create {}: {e}", job_dir.display());
+        return exit_codes::RUNTIME_ERROR;
+    }
+
+    let meta = JobMeta {
+        id: id.clone(),
+        workspace: target.workspace.clone(),
+        mount_name,
+        container_id: target.container_id.clone(),
+        command: command.clone(),
+        docker_args: exec::build_exec_args(
+            &target.container_id,
+            &opts,
+            false,
+            &wrap_command(&id, &command),
+        ),
+        started: state::unix_now(),
+    };
+    let write_meta = serde_json::to_string_pretty(&meta)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(job_dir.join(META_FILE), json).map_err(|e| e.to_string()));
+    if let Err(e) = write_meta {
+        eprintln!("Failed to write job metadata: {e}");
+        return exit_codes::RUNTIME_ERROR;
+    }
+
+    progress::step("Starting detached job...");
+    {
+        use std::os::unix::process::CommandExt;
+
+        let exe = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("dcx"));
+        let mut runner = std::process::Command::new(exe);
+        runner
+            .arg("_job-runner")
+            .arg("--job-dir")
+            .arg(&job_dir)
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .stdin(std::process::Stdio::null())
+            .process_group(0); // Detach so the job survives when dcx exits
+        match runner.spawn() {
+            Ok(child) => {
+                let _ = std::fs::write(job_dir.join(RUNNER_PID_FILE), child.id().to_string());
+            }
+            Err(e) => {
+                eprintln!("Failed to start job runner: {e}");
+                let _ = std::fs::remove_dir_all(&job_dir);
+                return exit_codes::RUNTIME_ERROR;
+            }
+        }
+    }
+
+    println!("{id}");
+    exit_codes::SUCCESS
+}
+
+/// Internal job runner: run the job's `docker exec`, appending output to the log and
+/// recording the exit code. Never returns.
+///
+/// The runner registers itself as a live session, so a running job keeps the workspace
+/// active for the supervisor and `dcx clean --idle-for` even when it uses no CPU.
+pub fn run_job_runner(home: &Path, job_dir: &Path) -> ! {
+    let Some(meta) = read_meta(job_dir) else {
+        std::process::exit(exit_codes::RUNTIME_ERROR);
+    };
+    state::register_session(home, &meta.mount_name);
+    let code = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(job_dir.join(OUTPUT_FILE))
+        .and_then(|out| {
+            let err = out.try_clone()?;
+            std::process::Command::new("docker")
+                .args(&meta.docker_args)
+                .stdin(std::process::Stdio::null())
+                .stdout(out)
+                .stderr(err)
+                .status()
+        })
+        .map(|s| s.code().unwrap_or(exit_codes::RUNTIME_ERROR))
+        .unwrap_or(exit_codes::PREREQ_NOT_FOUND);
+    let _ = std::fs::write(
+        job_dir.join(EXIT_FILE),
+        format!("{code} {}\n", state::unix_now()),
+    );
+    std::process::exit(code);
+}
+
+/// Run `dcx jobs`: list jobs across all workspaces, newest first.
+pub fn run_jobs_list(home: &Path) -> i32 {
+    let now = state::unix_now();
+    let mut jobs: Vec<(JobMeta, JobState)> = all_job_dirs(home)
+        .iter()
+        .filter_map(|dir| read_meta(dir).map(|m| (m, current_state(dir))))
+        .collect();
+    if jobs.is_empty() {
+        println!("No jobs.");
+        return exit_codes::SUCCESS;
+    }
+    jobs.sort_by_key(|(meta, _)| std::cmp::Reverse(meta.started));
+    let rows: Vec<JobRow> = jobs
+        .into_iter()
+        .map(|(meta, st)| JobRow {
+            id: meta.id,
+            workspace: meta
+                .workspace
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| meta.mount_name.clone()),
+            state: st.label().to_string(),
+            exit_code: match st {
+                JobState::Exited(c) => Some(c),
+                _ => None,
+            },
+            started: format_age(now.saturating_sub(meta.started)),
+            command: meta.command.join(" "),
+        })
+        .collect();
+    println!("{}", format_jobs_table(&rows));
+    exit_codes::SUCCESS
+}
+
+/// Run `dcx jobs logs <id> [-f]`: print the job's output; with `follow`, keep streaming
+/// until the job finishes or Ctrl+C.
+pub fn run_jobs_logs(home: &Path, id: &str, follow: bool) -> i32 {
+    let Some(job_dir) = find_job_dir(home, id) else {
+        eprintln!("No job found with ID {id}.");
+        return exit_codes::USAGE_ERROR;
+    };
+    let interrupted = signals::interrupted_flag();
+    let mut offset = 0u64;
+    loop {
+        // Check state before reading so output written just before exit is not missed.
+        let running = current_state(&job_dir) == JobState::Running;
+        if let Ok(mut f) = std::fs::File::open(job_dir.join(OUTPUT_FILE))
+            && f.seek(SeekFrom::Start(offset)).is_ok()
+        {
+            let mut buf = Vec::new();
+            if f.read_to_end(&mut buf).is_ok() && !buf.is_empty() {
+                offset += buf.len() as u64;
+                let mut stdout = std::io::stdout();
+                let _ = stdout.write_all(&buf);
+                let _ = stdout.flush();
+            }
+        }
+        if !follow || !running || interrupted.load(std::sync::atomic::Ordering::Relaxed) {
+            return exit_codes::SUCCESS;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(250));
+    }
+}
+
+/// Run `dcx jobs kill <id>`: send SIGTERM to the job's process inside the container.
+pub fn run_jobs_kill(home: &Path, id: &str) -> i32 {
+    let Some(job_dir) = find_job_dir(home, id) else {
+        eprintln!("No job found with ID {id}.");
+        return exit_codes::USAGE_ERROR;
+    };
+    if current_state(&job_dir) != JobState::Running {
+        println!("Job {id} is not running.");
+        return exit_codes::SUCCESS;
+    }
+    let Some(meta) = read_meta(&job_dir) else {
+        eprintln!("Job {id} metadata is unreadable.");
+        return exit_codes::RUNTIME_ERROR;
+    };
+    progress::step(&format!("Stopping job {id}..."));
+    let script = format!("kill -TERM \"$(cat {})\"", container_pid_path(id));
+    match cmd::run_capture(
+        "docker",
+        &[
+            "exec",
+            "-u",
+            "root",
+            &meta.container_id,
+            "sh",
+            "-c",
+            &script,
+        ],
+    ) {
+        Ok(out) if out.status == 0 => {
+            println!("Job {id} stopped.");
+            exit_codes::SUCCESS
+        }
+        Ok(out) => {
+            eprintln!("Failed to stop job {id}: {}", out.stderr.trim());
+            exit_codes::RUNTIME_ERROR
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            exit_codes::RUNTIME_ERROR
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(id: &str, mount: &str) -> JobMeta {
+        JobMeta {
+            id: id.to_string(),
+            workspace: PathBuf::from("/home/u/proj"),
+            mount_name: mount.to_string(),
+            container_id: "abc123".to_string(),
+            command: vec!["cargo".to_string(), "watch".to_string()],
+            docker_args: vec![],
+            started: 1,
+        }
+    }
+
+    // --- make_job_id ---
+
+    #[test]
+    fn make_job_id_is_8_hex_chars_and_varies_with_nonce() {
+        let a = make_job_id("dcx-proj-abcd1234", 1);
+        let b = make_job_id("dcx-proj-abcd1234", 2);
+        assert_eq!(a.len(), 8);
+        assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(a, b);
+    }
+
+    // --- wrap_command ---
+
+    #[test]
+    fn wrap_command_records_pid_and_execs_command() {
+        let cmd = vec!["cargo".to_string(), "watch".to_string()];
+        let wrapped = wrap_command("deadbeef", &cmd);
+        assert_eq!(wrapped[0], "sh");
+        assert!(wrapped[2].contains("/tmp/dcx-job-deadbeef.pid"));
+        assert!(wrapped[2].contains("exec \"$@\""));
+        assert_eq!(&wrapped[4..], &cmd[..]);
+    }
+
+    // --- parse_exit_file / job_state ---
+
+    #[test]
+    fn parse_exit_file_reads_code_and_timestamp() {
+        assert_eq!(parse_exit_file("143 1700000000\n"), Some((143, 1700000000)));
+        assert_eq!(parse_exit_file("0"), Some((0, 0)));
+        assert_eq!(parse_exit_file(""), None);
+    }
+
+    #[test]
+    fn job_state_prefers_exit_file() {
+        assert_eq!(job_state(Some(0), true), JobState::Exited(0));
+        assert_eq!(job_state(None, true), JobState::Running);
+        assert_eq!(job_state(None, false), JobState::Lost);
+    }
+
+    #[test]
+    fn jobs_to_prune_keeps_newest_finished_and_all_running() {
+        let jobs = vec![
+            ("a".to_string(), 1, true),
+            ("b".to_string(), 2, false),
+            ("c".to_string(), 3, true),
+            ("d".to_string(), 4, true),
+        ];
+        assert_eq!(jobs_to_prune(jobs.clone(), 2), vec!["a".to_string()]);
+        assert_eq!(jobs_to_prune(jobs.clone(), 0).len(), 3);
+        assert!(jobs_to_prune(jobs, 5).is_empty());
+    }
+
+    // --- job discovery ---
+
+    #[test]
+    fn find_job_dir_searches_all_workspaces() {
+        let home = tempfile::tempdir().unwrap();
+        let dir = jobs_dir(home.path(), "dcx-b-00000002").join("cafef00d");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join(META_FILE),
+            serde_json::to_string(&meta("cafef00d", "dcx-b-00000002")).unwrap(),
+        )
+        .unwrap();
+        std::fs::create_dir_all(jobs_dir(home.path(), "dcx-a-00000001")).unwrap();
+
+        assert_eq!(find_job_dir(home.path(), "cafef00d"), Some(dir));
+        assert_eq!(find_job_dir(home.path(), "missing0"), None);
+        assert_eq!(all_job_dirs(home.path()).len(), 1);
+    }
+
+    #[test]
+    fn finished_job_state_is_exited() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(EXIT_FILE), "2 5\n").unwrap();
+        assert_eq!(current_state(dir.path()), JobState::Exited(2));
+    }
+
+    #[test]
+    fn job_without_runner_or_exit_is_lost() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(current_state(dir.path()), JobState::Lost);
+    }
+}
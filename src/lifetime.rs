@@ -0,0 +1,395 @@
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use crate::docker;
+use crate::state;
+
+/// How often the supervisor checks a workspace, in seconds.
+pub const CHECK_INTERVAL: u64 = 60;
+
+/// Container CPU usage (percent) above which the container counts as active.
+pub const ACTIVE_CPU_PERCENT: f64 = 1.0;
+
+// ── Data structures ───────────────────────────────────────────────────────────
+
+/// When a workspace is brought down automatically.
+///
+/// Stored in `~/.local/state/dcx/<mount>/lifetime` as `deadline <unix_ts>` and
+/// `idle_timeout <secs>` lines.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Lifetime {
+    /// Absolute expiry from `dcx up --ttl`.
+    pub deadline: Option<u64>,
+    /// Maximum time without activity, from `up.idle_timeout`.
+    pub idle_timeout: Option<u64>,
+}
+
+impl Lifetime {
+    /// True when neither a TTL nor an idle timeout applies.
+    pub fn is_empty(&self) -> bool {
+        self.deadline.is_none() && self.idle_timeout.is_none()
+    }
+
+    /// Serialize for the lifetime file.
+    pub fn to_file(self) -> String {
+        let mut out = String::new();
+        if let Some(d) = self.deadline {
+            out.push_str(&format!("deadline {d}\n"));
+        }
+        if let Some(i) = self.idle_timeout {
+            out.push_str(&format!("idle_timeout {i}\n"));
+        }
+        out
+    }
+
+    /// Parse a lifetime file. Unknown or malformed lines are ignored.
+    pub fn parse(content: &str) -> Self {
+        let mut lifetime = Self::default();
+        for line in content.lines() {
+            let Some((key, value)) = line.split_once(' ') else {
+                continue;
+            };
+            let Ok(value) = value.trim().parse() else {
+                continue;
+            };
+            match key {
+                "deadline" => lifetime.deadline = Some(value),
+                "idle_timeout" => lifetime.idle_timeout = Some(value),
+                _ => {}
+            }
+        }
+        lifetime
+    }
+}
+
+// ── Pure functions ────────────────────────────────────────────────────────────
+
+/// Seconds until the workspace expires: the earlier of the TTL deadline and the end of the
+/// idle window (measured from `last_active`, or `now` if activity was never recorded).
+///
+/// Returns `None` when no limit applies; 0 when already expired.
+pub fn remaining(lifetime: &Lifetime, last_active: Option<u64>, now: u64) -> Option<u64> {
+    let ttl = lifetime.deadline.map(|d| d.saturating_sub(now));
+    let idle = lifetime
+        .idle_timeout
+        .map(|t| (last_active.unwrap_or(now) + t).saturating_sub(now));
+    match (ttl, idle) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Why the workspace has expired, if it has. The TTL takes precedence.
+pub fn expiry_reason(
+    lifetime: &Lifetime,
+    last_active: Option<u64>,
+    now: u64,
+) -> Option<&'static str> {
+    if lifetime.deadline.is_some_and(|d| now >= d) {
+        return Some("TTL expired");
+    }
+    let idle_expired = lifetime
+        .idle_timeout
+        .zip(last_active)
+        .is_some_and(|(t, last)| now.saturating_sub(last) >= t);
+    idle_expired.then_some("idle timeout expired")
+}
+
+/// Parse a `CPUPerc` value from `docker stats` (e.g. `12.34%`).
+pub fn parse_cpu_percent(value: &str) -> Option<f64> {
+    value.trim().trim_end_matches('%').parse().ok()
+}
+
+// ── State files ───────────────────────────────────────────────────────────────
+
+/// The workspace's lifetime file.
+pub fn lifetime_file(home: &Path, mount_name: &str) -> PathBuf {
+    state::state_dir(home, mount_name).join("lifetime")
+}
+
+/// PID file of the workspace's supervisor.
+pub fn supervisor_pid_file(home: &Path, mount_name: &str) -> PathBuf {
+    state::state_dir(home, mount_name).join("supervisor.pid")
+}
+
+/// The workspace's lifetime, if one is recorded.
+pub fn load(home: &Path, mount_name: &str) -> Option<Lifetime> {
+    std::fs::read_to_string(lifetime_file(home, mount_name))
+        .ok()
+        .map(|c| Lifetime::parse(&c))
+        .filter(|l| !l.is_empty())
+}
+
+/// Record the workspace's lifetime.
+pub fn save(home: &Path, mount_name: &str, lifetime: &Lifetime) -> Result<(), String> {
+    let path = lifetime_file(home, mount_name);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    std::fs::write(&path, lifetime.to_file())
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Seconds until the workspace expires, for `dcx status`.
+pub fn remaining_for(home: &Path, mount_name: &str) -> Option<u64> {
+    let lifetime = load(home, mount_name)?;
+    remaining(
+        &lifetime,
+        state::last_active(home, mount_name),
+        state::unix_now(),
+    )
+}
+
+// ── Supervisor process ────────────────────────────────────────────────────────
+
+/// PID of the workspace's live supervisor.
+fn supervisor_pid(home: &Path, mount_name: &str) -> Option<u32> {
+    let pid: u32 = std::fs::read_to_string(supervisor_pid_file(home, mount_name))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    let out = crate::cmd::run_capture("ps", &["-p", &pid.to_string(), "-o", "args="]).ok()?;
+    (out.status == 0 && out.stdout.contains("_supervisor")).then_some(pid)
+}
+
+/// Start a detached supervisor for the workspace unless one is already running.
+///
+/// A running supervisor re-reads the lifetime file on every check, so it picks up a new
+/// TTL or idle timeout without a restart.
+pub fn spawn_supervisor(home: &Path, mount_point: &Path, workspace: &Path) {
+    use std::os::unix::process::CommandExt;
+
+    let name = mount_point
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    if supervisor_pid(home, &name).is_some() {
+        return;
+    }
+    let exe = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("dcx"));
+    let mut cmd = std::process::Command::new(&exe);
+    cmd.arg("_supervisor")
+        .arg("--mount-point")
+        .arg(mount_point)
+        .arg("--workspace")
+        .arg(workspace)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .stdin(std::process::Stdio::null())
+        .process_group(0); // Survive the exit of `dcx up`, like the sync daemon
+    match cmd.spawn() {
+        Ok(child) => drop(child),
+        Err(e) => eprintln!("Warning: Could not start workspace supervisor: {e}"),
+    }
+}
+
+/// Remove the workspace's lifetime and stop its supervisor (best effort).
+pub fn clear(home: &Path, mount_name: &str) {
+    let _ = std::fs::remove_file(lifetime_file(home, mount_name));
+    if let Some(pid) = supervisor_pid(home, mount_name) {
+        let _ = std::process::Command::new("kill")
+            .arg("-TERM")
+            .arg(pid.to_string())
+            .status();
+    }
+    let _ = std::fs::remove_file(supervisor_pid_file(home, mount_name));
+}
+
+/// Whether the container shows activity: a live `dcx exec`/`dcx shell` session or CPU use
+/// above `ACTIVE_CPU_PERCENT`.
+fn is_active(home: &Path, mount_name: &str, container: &str) -> bool {
+    if state::has_live_session(home, mount_name) {
+        return true;
+    }
+    docker::container_stats(&[container.to_string()])
+        .first()
+        .and_then(|s| parse_cpu_percent(&s.cpu_percent))
+        .is_some_and(|cpu| cpu > ACTIVE_CPU_PERCENT)
+}
+
+/// Bring the workspace down with `dcx down`. If that fails (busy unmount, Docker hiccup),
+/// at least stop the container so an expired workspace has no network access.
+///
+/// Returns true if `dcx down` succeeded.
+fn bring_down(home: &Path, name: &str, mount_point: &Path, workspace: &Path) -> bool {
+    let exe = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("dcx"));
+    let status = std::process::Command::new(&exe)
+        .arg("down")
+        .arg("--workspace-folder")
+        .arg(workspace)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .stdin(std::process::Stdio::null())
+        .status();
+    if status.as_ref().is_ok_and(|s| s.success()) {
+        return true;
+    }
+    let reason = match status {
+        Ok(s) => format!("exit {}", s.code().unwrap_or(-1)),
+        Err(e) => e.to_string(),
+    };
+    let stopped = match docker::stop_container(mount_point) {
+        Ok(()) => "container stopped".to_string(),
+        Err(e) => format!("could not stop container: {e}"),
+    };
+    state::record_event(
+        home,
+        name,
+        &format!("supervisor: dcx down failed ({reason}), {stopped}; retrying"),
+    );
+    false
+}
+
+/// Run the supervisor loop (`dcx _supervisor`): every `CHECK_INTERVAL` seconds, record
+/// container activity and bring the workspace down with `dcx down` once it expires.
+///
+/// Fails closed: a failed `dcx down` is retried (after stopping the container), and an
+/// unreachable Docker is waited out. Exits only when the lifetime file is removed, the
+/// workspace has no container left, or `dcx down` succeeded.
+///
+/// While the container is stopped (`dcx stop`) the supervisor is paused: nothing expires,
+/// and the idle clock restarts when the container runs again.
+pub fn run_supervisor(home: &Path, mount_point: &Path, workspace: &Path) {
+    let name = mount_point
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let pid_file = supervisor_pid_file(home, &name);
+    let write_pid = || {
+        let _ = std::fs::write(&pid_file, format!("{}\n", std::process::id()));
+    };
+    write_pid();
+
+    let mut paused = false;
+    while let Some(lifetime) = load(home, &name) {
+        match docker::try_query_container_any(mount_point) {
+            Ok(containers) if containers.is_empty() => break,
+            Ok(_) => {}
+            // Docker is unreachable (e.g. Colima restarting): not a reason to stop watching.
+            Err(_) => {
+                std::thread::sleep(std::time::Duration::from_secs(CHECK_INTERVAL));
+                continue;
+            }
+        }
+        let Some(running) = docker::query_container(mount_point) else {
+            paused = true;
+            std::thread::sleep(std::time::Duration::from_secs(CHECK_INTERVAL));
+            continue;
+        };
+        if std::mem::take(&mut paused) || is_active(home, &name, &running) {
+            state::touch_active(home, &name);
+        }
+
+        let now = state::unix_now();
+        let last_active = state::last_active(home, &name);
+        if let Some(reason) = expiry_reason(&lifetime, last_active, now) {
+            state::record_event(
+                home,
+                &name,
+                &format!("supervisor: {reason}, bringing workspace down"),
+            );
+            // Remove the PID file first so `dcx down` does not signal this process.
+            let _ = std::fs::remove_file(&pid_file);
+            if bring_down(home, &name, mount_point, workspace) {
+                return;
+            }
+            write_pid();
+            std::thread::sleep(std::time::Duration::from_secs(CHECK_INTERVAL));
+            continue;
+        }
+
+        let wait = remaining(&lifetime, last_active, now)
+            .unwrap_or(CHECK_INTERVAL)
+            .clamp(1, CHECK_INTERVAL);
+        std::thread::sleep(std::time::Duration::from_secs(wait));
+    }
+    let _ = std::fs::remove_file(&pid_file);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: u64 = 3600;
+
+    #[test]
+    fn lifetime_file_round_trips() {
+        let l = Lifetime {
+            deadline: Some(1_700_000_000),
+            idle_timeout: Some(2 * HOUR),
+        };
+        assert_eq!(l.to_file(), "deadline 1700000000\nidle_timeout 7200\n");
+        assert_eq!(Lifetime::parse(&l.to_file()), l);
+        assert_eq!(
+            Lifetime::parse("garbage\nidle_timeout x\n"),
+            Lifetime::default()
+        );
+    }
+
+    #[test]
+    fn remaining_takes_the_earlier_limit() {
+        let now = 10 * HOUR;
+        let ttl_only = Lifetime {
+            deadline: Some(now + HOUR),
+            idle_timeout: None,
+        };
+        assert_eq!(remaining(&ttl_only, None, now), Some(HOUR));
+        let both = Lifetime {
+            deadline: Some(now + 5 * HOUR),
+            idle_timeout: Some(2 * HOUR),
+        };
+        // Last active 30 minutes ago: 1h30m of the idle window left.
+        assert_eq!(
+            remaining(&both, Some(now - HOUR / 2), now),
+            Some(HOUR * 3 / 2)
+        );
+        assert_eq!(remaining(&Lifetime::default(), Some(now), now), None);
+        // Past the deadline.
+        assert_eq!(remaining(&ttl_only, None, now + 2 * HOUR), Some(0));
+    }
+
+    #[test]
+    fn expiry_reason_prefers_ttl_and_needs_recorded_activity_for_idle() {
+        let now = 10 * HOUR;
+        let l = Lifetime {
+            deadline: Some(now),
+            idle_timeout: Some(HOUR),
+        };
+        assert_eq!(expiry_reason(&l, Some(0), now), Some("TTL expired"));
+        let idle = Lifetime {
+            deadline: None,
+            idle_timeout: Some(HOUR),
+        };
+        assert_eq!(
+            expiry_reason(&idle, Some(now - HOUR), now),
+            Some("idle timeout expired")
+        );
+        assert_eq!(expiry_reason(&idle, Some(now - 60), now), None);
+        assert_eq!(expiry_reason(&idle, None, now), None);
+    }
+
+    #[test]
+    fn parse_cpu_percent_strips_sign() {
+        assert_eq!(parse_cpu_percent("12.34%"), Some(12.34));
+        assert_eq!(parse_cpu_percent("0.00%"), Some(0.0));
+        assert_eq!(parse_cpu_percent("--"), None);
+    }
+
+    #[test]
+    fn load_ignores_empty_lifetime() {
+        let home = tempfile::tempdir().unwrap();
+        let name = "dcx-proj-abcd1234";
+        assert_eq!(load(home.path(), name), None);
+        save(home.path(), name, &Lifetime::default()).unwrap();
+        assert_eq!(load(home.path(), name), None);
+        let l = Lifetime {
+            deadline: Some(1),
+            idle_timeout: None,
+        };
+        save(home.path(), name, &l).unwrap();
+        assert_eq!(load(home.path(), name), Some(l));
+    }
+}
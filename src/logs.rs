@@ -1,13 +1,30 @@
 #![allow(dead_code)]
 
-use std::path::Path;
+use std::io::{BufRead, IsTerminal, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
 
 use crate::cmd;
 use crate::docker;
+use crate::duration::{parse_duration, parse_timestamp};
 use crate::exit_codes;
-use crate::naming::{mount_name, relay_dir};
+use crate::mount_table;
+use crate::naming::{mount_name, relay_dir, scan_relay, workspace_labels};
+use crate::platform;
+use crate::run_log;
+use crate::signals;
+use crate::state;
+use crate::sync::rotated_log_path;
 use crate::workspace::resolve_workspace;
 
+/// ANSI colors cycled through for workspace prefixes in merged output.
+const PREFIX_COLORS: [u8; 6] = [36, 33, 35, 32, 34, 31];
+
+/// How long merged follow output is buffered before being sorted and printed.
+const MERGE_WINDOW: std::time::Duration = std::time::Duration::from_millis(250);
+
 // ── Pure functions ────────────────────────────────────────────────────────────
 
 /// Build the argument list for `docker logs`.
@@ -43,6 +60,113 @@ pub fn build_logs_args(
     args
 }
 
+/// Parse a `--tail` value: a line count or `all` (`None` = no limit).
+pub fn parse_tail(value: &str) -> Result<Option<usize>, String> {
+    if value == "all" {
+        return Ok(None);
+    }
+    value
+        .parse()
+        .map(Some)
+        .map_err(|_| format!("Invalid --tail value '{value}'. Use a number of lines or 'all'."))
+}
+
+/// Keep the last `tail` lines (all lines if `None`).
+pub fn tail_lines(lines: Vec<String>, tail: Option<usize>) -> Vec<String> {
+    match tail {
+        Some(n) if n < lines.len() => lines[lines.len() - n..].to_vec(),
+        _ => lines,
+    }
+}
+
+/// Sort key of a log line from its leading RFC 3339 timestamp: (Unix seconds, nanoseconds).
+///
+/// Returns `None` if the line does not start with a timestamp.
+pub fn sort_key(line: &str) -> Option<(u64, u32)> {
+    let ts = line.split_whitespace().next()?;
+    let secs = parse_timestamp(ts)?;
+    let nanos = ts
+        .split_once('.')
+        .map(|(_, frac)| {
+            let digits: String = frac
+                .chars()
+                .take_while(char::is_ascii_digit)
+                .take(9)
+                .collect();
+            format!("{digits:0<9}").parse().unwrap_or(0)
+        })
+        .unwrap_or(0);
+    Some((secs, nanos))
+}
+
+/// Resolve a `--since`/`--until` value to a Unix timestamp, as `docker logs` does:
+/// `now`, a bare Unix timestamp, an RFC 3339 timestamp, or a duration before `now`.
+pub fn resolve_time_bound(value: &str, now: u64) -> Result<u64, String> {
+    if value == "now" {
+        return Ok(now);
+    }
+    if !value.is_empty() && value.chars().all(|c| c.is_ascii_digit()) {
+        return value
+            .parse()
+            .map_err(|_| format!("Invalid time '{value}'."));
+    }
+    if let Some(ts) = parse_timestamp(value) {
+        return Ok(ts);
+    }
+    parse_duration(value)
+        .map(|d| now.saturating_sub(d))
+        .map_err(|_| {
+            format!("Invalid time '{value}'. Use a duration (10m), an RFC 3339 timestamp or 'now'.")
+        })
+}
+
+/// Apply `--since`/`--until`/`--tail` to the lines of a dcx log file.
+///
+/// Lines without a timestamp inherit the previous line's time.
+pub fn filter_file_lines(
+    lines: Vec<String>,
+    since: Option<u64>,
+    until: Option<u64>,
+    tail: Option<usize>,
+) -> Vec<String> {
+    let mut last = 0;
+    let kept = lines
+        .into_iter()
+        .filter(|line| {
+            if let Some((secs, _)) = sort_key(line) {
+                last = secs;
+            }
+            since.is_none_or(|s| last >= s) && until.is_none_or(|u| last <= u)
+        })
+        .collect();
+    tail_lines(kept, tail)
+}
+
+/// Prefix a line with its source label, colored when `color` is set.
+pub fn prefix_source(label: &str, color_index: usize, color: bool, line: &str) -> String {
+    if color {
+        let code = PREFIX_COLORS[color_index % PREFIX_COLORS.len()];
+        format!("\x1b[{code}m[{label}]\x1b[0m {line}")
+    } else {
+        format!("[{label}] {line}")
+    }
+}
+
+/// A line received from one source of a merged log stream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergedLine {
+    pub key: (u64, u32),
+    /// Arrival order, used to keep equal timestamps in their original order.
+    pub seq: u64,
+    pub text: String,
+}
+
+/// Sort buffered lines into timestamp order (stable for equal timestamps).
+pub fn merge_order(mut lines: Vec<MergedLine>) -> Vec<MergedLine> {
+    lines.sort_by_key(|l| (l.key, l.seq));
+    lines
+}
+
 // ── Entry point ───────────────────────────────────────────────────────────────
 
 /// Run `dcx logs`.
@@ -55,6 +179,7 @@ pub fn run_logs(
     since: Option<&str>,
     until: Option<&str>,
     tail: Option<&str>,
+    events: bool,
 ) -> i32 {
     // 1. Validate Docker/Colima is available.
     if !docker::is_docker_available() {
@@ -83,12 +208,412 @@ pub fn run_logs(
         return exit_codes::RUNTIME_ERROR;
     };
 
-    // 5. Build and execute docker logs command.
+    // 5. With --events, merge in the sync daemon log and lifecycle events.
+    if events {
+        let label = workspace
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| name.clone());
+        let sources = workspace_sources(home, &label, &name, Some(container_id), 0, true);
+        return run_merged(sources, follow, since, until, tail);
+    }
+
+    // 6. Build and execute docker logs command.
     let args = build_logs_args(&container_id, follow, since, until, tail);
     let args_str: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
     cmd::run_stream("docker", &args_str).unwrap_or(exit_codes::PREREQ_NOT_FOUND)
 }
 
+/// Run `dcx logs --daemon`: print the workspace's sync daemon log (rotated generation
+/// first); with `follow`, keep printing new lines until Ctrl+C.
+pub fn run_daemon_logs(
+    home: &Path,
+    workspace_folder: Option<&Path>,
+    follow: bool,
+    tail: Option<&str>,
+) -> i32 {
+    let tail = match tail.map(parse_tail).transpose() {
+        Ok(t) => t.flatten(),
+        Err(e) => {
+            eprintln!("{e}");
+            return exit_codes::USAGE_ERROR;
+        }
+    };
+    let workspace = match resolve_workspace(workspace_folder) {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("{e}");
+            return exit_codes::USAGE_ERROR;
+        }
+    };
+    let log = state::daemon_log_file(home, &mount_name(&workspace));
+    if !log.exists() && !rotated_log_path(&log).exists() {
+        eprintln!(
+            "No sync daemon log for this workspace. \
+             The daemon only runs for files with `sync: true` in dcx_config.yaml."
+        );
+        return exit_codes::RUNTIME_ERROR;
+    }
+
+    let lines: Vec<String> = [rotated_log_path(&log), log.clone()]
+        .iter()
+        .filter_map(|p| std::fs::read_to_string(p).ok())
+        .flat_map(|c| c.lines().map(str::to_string).collect::<Vec<_>>())
+        .collect();
+    for line in tail_lines(lines, tail) {
+        println!("{line}");
+    }
+    if !follow {
+        return exit_codes::SUCCESS;
+    }
+
+    let interrupted = signals::interrupted_flag();
+    let mut offset = std::fs::metadata(&log).map(|m| m.len()).unwrap_or(0);
+    while !interrupted.load(std::sync::atomic::Ordering::Relaxed) {
+        std::thread::sleep(std::time::Duration::from_millis(250));
+        let Ok(mut f) = std::fs::File::open(&log) else {
+            continue;
+        };
+        let len = f.metadata().map(|m| m.len()).unwrap_or(0);
+        if len < offset {
+            // Rotated: the current file was started fresh.
+            offset = 0;
+        }
+        let mut buf = Vec::new();
+        if f.seek(SeekFrom::Start(offset)).is_ok() && f.read_to_end(&mut buf).is_ok() {
+            offset += buf.len() as u64;
+            let mut stdout = std::io::stdout();
+            let _ = stdout.write_all(&buf);
+            let _ = stdout.flush();
+        }
+    }
+    exit_codes::SUCCESS
+}
+
+/// Run `dcx logs --up`: print the log of the latest `dcx up` run for the workspace, or of
+/// run `run` when given.
+pub fn run_up_logs(
+    home: &Path,
+    workspace_folder: Option<&Path>,
+    run: Option<&str>,
+    tail: Option<&str>,
+) -> i32 {
+    let tail = match tail.map(parse_tail).transpose() {
+        Ok(t) => t.flatten(),
+        Err(e) => {
+            eprintln!("{e}");
+            return exit_codes::USAGE_ERROR;
+        }
+    };
+    let workspace = match resolve_workspace(workspace_folder) {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("{e}");
+            return exit_codes::USAGE_ERROR;
+        }
+    };
+    let dir = run_log::up_logs_dir(home, &mount_name(&workspace));
+    let runs = run_log::list_runs(&dir);
+    let Some(latest) = runs.last() else {
+        eprintln!("No `dcx up` logs for this workspace.");
+        return exit_codes::RUNTIME_ERROR;
+    };
+    let id = match run {
+        None => latest.as_str(),
+        Some(id) if runs.iter().any(|r| r == id) => id,
+        Some(id) => {
+            eprintln!(
+                "No `dcx up` run {id} for this workspace. Available runs: {}",
+                runs.join(", ")
+            );
+            return exit_codes::USAGE_ERROR;
+        }
+    };
+    let content = match std::fs::read_to_string(run_log::run_log_path(&dir, id)) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Failed to read run {id}: {e}");
+            return exit_codes::RUNTIME_ERROR;
+        }
+    };
+    for line in tail_lines(content.lines().map(str::to_string).collect(), tail) {
+        println!("{line}");
+    }
+    exit_codes::SUCCESS
+}
+
+// ── Merged logs ───────────────────────────────────────────────────────────────
+
+/// One input of a merged log stream.
+enum SourceKind {
+    /// `docker logs` of a container.
+    Container(String),
+    /// A dcx log file (sync daemon log or lifecycle events).
+    File(PathBuf),
+}
+
+struct LogSource {
+    label: String,
+    color_index: usize,
+    kind: SourceKind,
+}
+
+/// Sources for one workspace: its container, plus sync daemon and lifecycle events when
+/// `events` is set. All share the workspace's color.
+fn workspace_sources(
+    home: &Path,
+    label: &str,
+    mount: &str,
+    container: Option<String>,
+    color_index: usize,
+    events: bool,
+) -> Vec<LogSource> {
+    let mut sources = Vec::new();
+    if let Some(id) = container {
+        sources.push(LogSource {
+            label: label.to_string(),
+            color_index,
+            kind: SourceKind::Container(id),
+        });
+    }
+    if events {
+        for (suffix, path) in [
+            ("daemon", state::daemon_log_file(home, mount)),
+            ("dcx", state::events_file(home, mount)),
+        ] {
+            sources.push(LogSource {
+                label: format!("{label}:{suffix}"),
+                color_index,
+                kind: SourceKind::File(path),
+            });
+        }
+    }
+    sources
+}
+
+/// Send each line of `reader` to `tx` tagged with `index`.
+fn pump_lines<R: Read>(reader: R, index: usize, tx: &mpsc::Sender<(usize, String)>) {
+    for line in std::io::BufReader::new(reader).lines() {
+        let Ok(line) = line else { break };
+        if tx.send((index, line)).is_err() {
+            break;
+        }
+    }
+}
+
+/// Read a dcx log file (rotated generation first), filtered like `docker logs`, and,
+/// with `follow`, keep sending new lines until interrupted.
+fn pump_file(
+    path: &Path,
+    index: usize,
+    bounds: (Option<u64>, Option<u64>, Option<usize>),
+    follow: bool,
+    interrupted: &AtomicBool,
+    tx: &mpsc::Sender<(usize, String)>,
+) {
+    let (since, until, tail) = bounds;
+    let lines: Vec<String> = [rotated_log_path(path), path.to_path_buf()]
+        .iter()
+        .filter_map(|p| std::fs::read_to_string(p).ok())
+        .flat_map(|c| c.lines().map(str::to_string).collect::<Vec<_>>())
+        .collect();
+    for line in filter_file_lines(lines, since, until, tail) {
+        if tx.send((index, line)).is_err() {
+            return;
+        }
+    }
+    if !follow {
+        return;
+    }
+    let mut offset = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let mut partial = String::new();
+    while !interrupted.load(Ordering::Relaxed) {
+        std::thread::sleep(MERGE_WINDOW);
+        let Ok(mut f) = std::fs::File::open(path) else {
+            continue;
+        };
+        if f.metadata().map(|m| m.len()).unwrap_or(0) < offset {
+            offset = 0;
+        }
+        let mut buf = String::new();
+        if f.seek(SeekFrom::Start(offset)).is_err() || f.read_to_string(&mut buf).is_err() {
+            continue;
+        }
+        offset += buf.len() as u64;
+        partial.push_str(&buf);
+        while let Some(pos) = partial.find('\n') {
+            let line: String = partial.drain(..=pos).collect();
+            if tx.send((index, line.trim_end().to_string())).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Stream several log sources as one, prefixed with their labels and ordered by
+/// timestamp. Without `follow`, all output is collected and sorted before printing;
+/// with `follow`, lines are sorted within `MERGE_WINDOW` batches.
+fn run_merged(
+    sources: Vec<LogSource>,
+    follow: bool,
+    since: Option<&str>,
+    until: Option<&str>,
+    tail: Option<&str>,
+) -> i32 {
+    let now = state::unix_now();
+    let bounds = match (
+        since.map(|v| resolve_time_bound(v, now)).transpose(),
+        until.map(|v| resolve_time_bound(v, now)).transpose(),
+        tail.map(parse_tail).transpose(),
+    ) {
+        (Ok(s), Ok(u), Ok(t)) => (s, u, t.flatten()),
+        (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+            eprintln!("{e}");
+            return exit_codes::USAGE_ERROR;
+        }
+    };
+
+    // Ctrl+C stops docker (same process group) and, via the flag, the file pollers.
+    let interrupted = if follow {
+        signals::interrupted_flag()
+    } else {
+        Arc::new(AtomicBool::new(false))
+    };
+
+    let (tx, rx) = mpsc::channel::<(usize, String)>();
+    let mut children = Vec::new();
+    for (index, source) in sources.iter().enumerate() {
+        match &source.kind {
+            SourceKind::Container(id) => {
+                let args = build_logs_args(id, follow, since, until, tail);
+                let spawned = std::process::Command::new("docker")
+                    .args(&args)
+                    .stdin(std::process::Stdio::null())
+                    .stdout(std::process::Stdio::piped())
+                    .stderr(std::process::Stdio::piped())
+                    .spawn();
+                let mut child = match spawned {
+                    Ok(c) => c,
+                    Err(e) => {
+                        eprintln!("Failed to run docker: {e}");
+                        return exit_codes::PREREQ_NOT_FOUND;
+                    }
+                };
+                if let Some(out) = child.stdout.take() {
+                    let tx = tx.clone();
+                    std::thread::spawn(move || pump_lines(out, index, &tx));
+                }
+                if let Some(err) = child.stderr.take() {
+                    let tx = tx.clone();
+                    std::thread::spawn(move || pump_lines(err, index, &tx));
+                }
+                children.push(child);
+            }
+            SourceKind::File(path) => {
+                let (path, tx, interrupted) = (path.clone(), tx.clone(), Arc::clone(&interrupted));
+                std::thread::spawn(move || {
+                    pump_file(&path, index, bounds, follow, &interrupted, &tx)
+                });
+            }
+        }
+    }
+    drop(tx);
+
+    let color = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+    let mut last_key = vec![(0u64, 0u32); sources.len()];
+    let mut buffer: Vec<MergedLine> = Vec::new();
+    let mut seq = 0u64;
+    let flush = |buffer: &mut Vec<MergedLine>| {
+        let mut stdout = std::io::stdout();
+        for line in merge_order(std::mem::take(buffer)) {
+            let _ = writeln!(stdout, "{}", line.text);
+        }
+        let _ = stdout.flush();
+    };
+    loop {
+        let received = if follow {
+            rx.recv_timeout(MERGE_WINDOW)
+        } else {
+            rx.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected)
+        };
+        match received {
+            Ok((index, line)) => {
+                // Continuation lines (no timestamp) stay with the line before them.
+                if let Some(key) = sort_key(&line) {
+                    last_key[index] = key;
+                }
+                let source = &sources[index];
+                buffer.push(MergedLine {
+                    key: last_key[index],
+                    seq,
+                    text: prefix_source(&source.label, source.color_index, color, &line),
+                });
+                seq += 1;
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => flush(&mut buffer),
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    flush(&mut buffer);
+
+    for mut child in children {
+        let _ = child.wait();
+    }
+    exit_codes::SUCCESS
+}
+
+/// Run `dcx logs --all`: merge the logs of every dcx container (running or stopped),
+/// optionally with sync daemon and lifecycle events.
+pub fn run_logs_all(
+    home: &Path,
+    follow: bool,
+    since: Option<&str>,
+    until: Option<&str>,
+    tail: Option<&str>,
+    events: bool,
+) -> i32 {
+    if !docker::is_docker_available() {
+        eprintln!("Docker is not available. Is Colima running?");
+        return exit_codes::RUNTIME_ERROR;
+    }
+    let table = platform::read_mount_table().unwrap_or_default();
+    let mut workspaces = Vec::new();
+    for mount_point in scan_relay(&relay_dir(home)) {
+        let mount = mount_point
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let container = docker::query_container_any(&mount_point).into_iter().next();
+        if container.is_none() && !events {
+            continue;
+        }
+        let workspace = mount_table::find_mount_source(&table, &mount_point).map(PathBuf::from);
+        workspaces.push(((workspace, mount), container));
+    }
+    let entries: Vec<(Option<PathBuf>, String)> =
+        workspaces.iter().map(|(entry, _)| entry.clone()).collect();
+    let mut sources = Vec::new();
+    for (color_index, (((_, mount), container), label)) in workspaces
+        .into_iter()
+        .zip(workspace_labels(&entries))
+        .enumerate()
+    {
+        sources.extend(workspace_sources(
+            home,
+            &label,
+            &mount,
+            container,
+            color_index,
+            events,
+        ));
+    }
+    if sources.is_empty() {
+        println!("No active workspaces.");
+        return exit_codes::SUCCESS;
+    }
+    run_merged(sources, follow, since, until, tail)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -173,4 +698,111 @@ mod tests {
             ]
         );
     }
+
+    // --- parse_tail / tail_lines ---
+
+    #[test]
+    fn parse_tail_accepts_number_or_all() {
+        assert_eq!(parse_tail("20"), Ok(Some(20)));
+        assert_eq!(parse_tail("all"), Ok(None));
+        assert!(parse_tail("-3").is_err());
+        assert!(parse_tail("ten").is_err());
+    }
+
+    #[test]
+    fn tail_lines_keeps_last_n() {
+        let lines: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(tail_lines(lines.clone(), Some(2)), vec!["b", "c"]);
+        assert_eq!(tail_lines(lines.clone(), Some(5)), lines);
+        assert_eq!(tail_lines(lines.clone(), None), lines);
+    }
+
+    // --- sort_key ---
+
+    #[test]
+    fn sort_key_reads_docker_and_dcx_timestamps() {
+        assert_eq!(
+            sort_key("2023-11-14T22:13:20.000000123Z hello"),
+            Some((1_700_000_000, 123))
+        );
+        assert_eq!(
+            sort_key("2023-11-14T22:13:20.5Z x"),
+            Some((1_700_000_000, 500_000_000))
+        );
+        assert_eq!(
+            sort_key("2023-11-14T22:13:20Z INFO started"),
+            Some((1_700_000_000, 0))
+        );
+        assert_eq!(sort_key("  continuation"), None);
+    }
+
+    // --- resolve_time_bound ---
+
+    #[test]
+    fn resolve_time_bound_accepts_docker_forms() {
+        let now = 1_700_000_000;
+        assert_eq!(resolve_time_bound("now", now), Ok(now));
+        assert_eq!(resolve_time_bound("10m", now), Ok(now - 600));
+        assert_eq!(resolve_time_bound("1600000000", now), Ok(1_600_000_000));
+        assert_eq!(
+            resolve_time_bound("2023-11-14T22:13:20Z", now),
+            Ok(1_700_000_000)
+        );
+        assert!(resolve_time_bound("yesterday", now).is_err());
+    }
+
+    // --- filter_file_lines ---
+
+    #[test]
+    fn filter_file_lines_applies_since_until_and_tail() {
+        let lines: Vec<String> = [
+            "1970-01-01T00:00:10Z INFO a",
+            "1970-01-01T00:00:20Z INFO b",
+            "  detail of b",
+            "1970-01-01T00:00:30Z INFO c",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        assert_eq!(
+            filter_file_lines(lines.clone(), Some(15), Some(25), None),
+            vec!["1970-01-01T00:00:20Z INFO b", "  detail of b"]
+        );
+        assert_eq!(
+            filter_file_lines(lines, None, None, Some(1)),
+            vec!["1970-01-01T00:00:30Z INFO c"]
+        );
+    }
+
+    // --- prefix_source / merge_order ---
+
+    #[test]
+    fn prefix_source_colors_only_when_enabled() {
+        assert_eq!(prefix_source("proj", 0, false, "x"), "[proj] x");
+        assert_eq!(
+            prefix_source("proj", 1, true, "x"),
+            "\x1b[33m[proj]\x1b[0m x"
+        );
+        assert_eq!(
+            prefix_source("proj", PREFIX_COLORS.len(), true, "x"),
+            prefix_source("proj", 0, true, "x")
+        );
+    }
+
+    #[test]
+    fn merge_order_sorts_by_timestamp_then_arrival() {
+        let line = |key, seq, text: &str| MergedLine {
+            key,
+            seq,
+            text: text.to_string(),
+        };
+        let merged = merge_order(vec![
+            line((20, 0), 0, "b1"),
+            line((10, 5), 1, "a"),
+            line((20, 0), 2, "b2"),
+            line((10, 0), 3, "first"),
+        ]);
+        let texts: Vec<&str> = merged.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(texts, vec!["first", "a", "b1", "b2"]);
+    }
 }
@@ -5,21 +5,37 @@ mod cli;
 mod cmd;
 mod colima;
 mod completions;
+mod daemon;
 mod dcx_config;
+mod df;
 mod docker;
 mod doctor;
 mod down;
+mod drift;
+mod duration;
 mod exec;
 mod exit_codes;
 mod format;
+mod gc;
+mod inspect;
+mod jobs;
+mod lifetime;
 mod logs;
 mod mount_table;
 mod naming;
 mod network_mode;
+mod output;
 mod platform;
 mod progress;
+mod remote_env;
+mod run_log;
+mod sessions;
+mod shell;
 mod signals;
+mod size;
+mod state;
 mod status;
+mod stop;
 mod sync;
 mod up;
 mod workspace;
@@ -37,6 +53,11 @@ fn home_dir() -> std::path::PathBuf {
 
 fn main() {
     let cli = cli::Cli::parse();
+    if cli.output != output::OutputFormat::Text && !cli.command.supports_structured_output() {
+        eprintln!("{}", output::unsupported_error(cli.command.name()));
+        std::process::exit(exit_codes::USAGE_ERROR);
+    }
+    output::init(cli.output, cli.command.name());
     match cli.command {
         cli::Commands::Up {
             workspace_folder,
@@ -46,6 +67,7 @@ fn main() {
             yes,
             network,
             no_cache,
+            ttl,
         } => {
             let config_dir = config_dir.or_else(|| {
                 std::env::var("DCX_DEVCONTAINER_CONFIG_DIR_PATH")
@@ -62,14 +84,37 @@ fn main() {
                     yes,
                     cli_network: network,
                     no_cache,
+                    ttl,
                 },
             ));
         }
         cli::Commands::Exec {
             workspace_folder,
             config_dir,
+            user,
+            env,
+            env_files,
+            workdir,
+            record,
+            all,
+            parallel,
             command,
         } => {
+            let flags = exec::ExecFlags {
+                user,
+                env,
+                env_files,
+                workdir,
+                record,
+            };
+            if all {
+                std::process::exit(exec::run_exec_all(
+                    &home_dir(),
+                    flags,
+                    usize::from(parallel.unwrap_or(1)),
+                    command,
+                ));
+            }
             let config_dir = config_dir.or_else(|| {
                 std::env::var("DCX_DEVCONTAINER_CONFIG_DIR_PATH")
                     .ok()
@@ -79,19 +124,140 @@ fn main() {
                 &home_dir(),
                 workspace_folder,
                 config_dir,
+                flags,
                 command,
             ));
         }
-        cli::Commands::Down { workspace_folder } => {
+        cli::Commands::Shell { workspace_folder } => {
+            std::process::exit(shell::run_shell(&home_dir(), workspace_folder));
+        }
+        cli::Commands::Run {
+            workspace_folder,
+            config_dir,
+            user,
+            env,
+            env_files,
+            workdir,
+            detach,
+            command,
+        } => {
+            let flags = exec::ExecFlags {
+                user,
+                env,
+                env_files,
+                workdir,
+                record: false,
+            };
+            let config_dir = config_dir.or_else(|| {
+                std::env::var("DCX_DEVCONTAINER_CONFIG_DIR_PATH")
+                    .ok()
+                    .map(std::path::PathBuf::from)
+            });
+            std::process::exit(jobs::run_run(
+                &home_dir(),
+                workspace_folder,
+                config_dir,
+                flags,
+                detach,
+                command,
+            ));
+        }
+        cli::Commands::Jobs { action } => {
+            let home = home_dir();
+            let code = match action {
+                None => jobs::run_jobs_list(&home),
+                Some(cli::JobsAction::Logs { id, follow }) => {
+                    jobs::run_jobs_logs(&home, &id, follow)
+                }
+                Some(cli::JobsAction::Kill { id }) => jobs::run_jobs_kill(&home, &id),
+            };
+            std::process::exit(code);
+        }
+        cli::Commands::Sessions { action } => {
+            let home = home_dir();
+            let code = match action {
+                cli::SessionsAction::List => sessions::run_sessions_list(&home),
+                cli::SessionsAction::Replay { id, speed } => {
+                    sessions::run_sessions_replay(&home, &id, speed)
+                }
+            };
+            std::process::exit(code);
+        }
+        cli::Commands::Down {
+            workspace_folder,
+            workspaces,
+            all,
+            yes,
+        } => {
+            if all || !workspaces.is_empty() {
+                std::process::exit(down::run_down_many(&home_dir(), workspaces, all, yes));
+            }
             std::process::exit(down::run_down(&home_dir(), workspace_folder));
         }
+        cli::Commands::Stop {
+            workspace_folder,
+            daemon,
+        } => {
+            std::process::exit(stop::run_stop(&home_dir(), workspace_folder, daemon));
+        }
+        cli::Commands::Start { workspace_folder } => {
+            std::process::exit(stop::run_start(&home_dir(), workspace_folder));
+        }
+        cli::Commands::Sync { action } => {
+            let code = match action {
+                cli::SyncAction::Resolve {
+                    workspace_folder,
+                    take,
+                    file,
+                } => sync::run_sync_resolve(&home_dir(), workspace_folder, take, file),
+            };
+            std::process::exit(code);
+        }
+        cli::Commands::Daemon { action } => {
+            let code = match action {
+                cli::DaemonAction::Status => daemon::run_daemon_status(&home_dir()),
+                cli::DaemonAction::Restart => daemon::run_daemon_restart(&home_dir()),
+            };
+            std::process::exit(code);
+        }
         cli::Commands::Logs {
             workspace_folder,
             follow,
             since,
             until,
             tail,
+            daemon,
+            all,
+            events,
+            up,
+            run,
         } => {
+            if up {
+                std::process::exit(logs::run_up_logs(
+                    &home_dir(),
+                    workspace_folder.as_deref(),
+                    run.as_deref(),
+                    tail.as_deref(),
+                ));
+            }
+            if all {
+                std::process::exit(logs::run_logs_all(
+                    &home_dir(),
+                    follow,
+                    since.as_deref(),
+                    until.as_deref(),
+                    tail.as_deref(),
+                    events,
+                ));
+            }
+            if daemon {
+                std::process::exit(logs::run_daemon_logs(
+                    &home_dir(),
+                    workspace_folder.as_deref(),
+                    follow,
+                    tail.as_deref(),
+                ));
+            }
             std::process::exit(logs::run_logs(
                 &home_dir(),
                 workspace_folder.as_deref(),
@@ -99,6 +265,7 @@ fn main() {
                 since.as_deref(),
                 until.as_deref(),
                 tail.as_deref(),
+                events,
             ));
         }
         cli::Commands::Clean {
@@ -107,6 +274,9 @@ fn main() {
             yes,
             purge,
             dry_run,
+            older_than,
+            idle_for,
+            state,
         } => {
             std::process::exit(clean::run_clean(
                 &home_dir(),
@@ -115,10 +285,39 @@ fn main() {
                 yes,
                 purge,
                 dry_run,
+                &clean::CleanFilterArgs {
+                    older_than,
+                    idle_for,
+                    states: state,
+                },
             ));
         }
-        cli::Commands::Status => {
-            std::process::exit(status::run_status(&home_dir()));
+        cli::Commands::Gc {
+            workspace_folder,
+            max_image_size,
+            max_idle,
+            keep,
+            dry_run,
+        } => {
+            std::process::exit(gc::run_gc(
+                &home_dir(),
+                workspace_folder,
+                gc::GcOverrides {
+                    max_image_size,
+                    max_idle,
+                    keep,
+                },
+                dry_run,
+            ));
+        }
+        cli::Commands::Inspect { workspace_folder } => {
+            std::process::exit(inspect::run_inspect(&home_dir(), workspace_folder));
+        }
+        cli::Commands::Status { resources } => {
+            std::process::exit(status::run_status(&home_dir(), resources));
+        }
+        cli::Commands::Df => {
+            std::process::exit(df::run_df(&home_dir()));
         }
         cli::Commands::Doctor => {
             std::process::exit(doctor::run_doctor(&home_dir()));
@@ -129,17 +328,17 @@ fn main() {
         cli::Commands::Completions { shell } => {
             std::process::exit(completions::run_completions(shell));
         }
-        cli::Commands::SyncDaemon {
-            sources,
-            stagings,
-            pid_file,
+        cli::Commands::DaemonRun => {
+            daemon::run_daemon(&home_dir());
+        }
+        cli::Commands::Supervisor {
+            mount_point,
+            workspace,
         } => {
-            let sync_pairs = sources
-                .into_iter()
-                .zip(stagings)
-                .map(|(source, staging)| sync::SyncPair { source, staging })
-                .collect();
-            sync::run_sync_daemon(sync_pairs, pid_file);
+            lifetime::run_supervisor(&home_dir(), &mount_point, &workspace);
+        }
+        cli::Commands::JobRunner { job_dir } => {
+            jobs::run_job_runner(&home_dir(), &job_dir);
         }
         cli::Commands::External(args) => {
             let code =
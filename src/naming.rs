@@ -57,6 +57,34 @@ pub(crate) fn scan_relay(relay: &Path) -> Vec<PathBuf> {
     dirs
 }
 
+/// Mount name a staging directory belongs to: `.dcx-<name>-<hash>-files` → `dcx-<name>-<hash>`.
+///
+/// Inverse of `up::staging_dir`. Returns `None` for any other name.
+pub fn staging_owner(dir_name: &str) -> Option<&str> {
+    dir_name
+        .strip_prefix('.')?
+        .strip_suffix("-files")
+        .filter(|name| name.starts_with("dcx-"))
+}
+
+/// Scan `relay` for staging directories whose `dcx-*` mount directory no longer exists
+/// (left behind when the mount vanished or `dcx down` failed midway). Sorted.
+pub(crate) fn scan_orphaned_staging(relay: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(relay) else {
+        return vec![];
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|e| {
+            let e = e.ok()?;
+            let name = e.file_name();
+            let owner = staging_owner(&name.to_string_lossy())?.to_string();
+            (e.path().is_dir() && !relay.join(owner).exists()).then(|| e.path())
+        })
+        .collect();
+    dirs.sort();
+    dirs
+}
+
 /// Return true if `path` is inside a dcx-managed mount (`<relay>/dcx-*`).
 pub fn is_dcx_managed_path(path: &Path, relay: &Path) -> bool {
     if let Ok(rel) = path.strip_prefix(relay)
@@ -70,10 +98,76 @@ pub fn is_dcx_managed_path(path: &Path, relay: &Path) -> bool {
     false
 }
 
+/// Display labels for workspaces listed together (`dcx exec --all`, `dcx logs --all`).
+///
+/// Each entry is a workspace path (if known) and its mount name. The label is the
+/// workspace's directory name; names shared by several workspaces become `parent/name`,
+/// and the mount name is used when that is still ambiguous or the workspace is unknown.
+pub fn workspace_labels(entries: &[(Option<PathBuf>, String)]) -> Vec<String> {
+    let last = |p: &Path, n: usize| -> Option<String> {
+        let parts: Vec<String> = p
+            .components()
+            .filter(|c| matches!(c, std::path::Component::Normal(_)))
+            .rev()
+            .take(n)
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        (parts.len() == n).then(|| parts.into_iter().rev().collect::<Vec<_>>().join("/"))
+    };
+    let candidates = |n: usize| -> Vec<Option<String>> {
+        entries
+            .iter()
+            .map(|(ws, _)| ws.as_deref().and_then(|p| last(p, n)))
+            .collect()
+    };
+    let unique = |labels: &[Option<String>], i: usize| {
+        labels[i]
+            .as_ref()
+            .is_some_and(|l| labels.iter().filter(|o| o.as_ref() == Some(l)).count() == 1)
+    };
+    let (names, parents) = (candidates(1), candidates(2));
+    (0..entries.len())
+        .map(|i| {
+            if unique(&names, i) {
+                names[i].clone()
+            } else if unique(&parents, i) {
+                parents[i].clone()
+            } else {
+                None
+            }
+            .unwrap_or_else(|| entries[i].1.clone())
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn workspace_labels_disambiguate_shared_names() {
+        let entry = |ws: Option<&str>, mount: &str| (ws.map(PathBuf::from), mount.to_string());
+        let labels = workspace_labels(&[
+            entry(Some("/home/u/work/app"), "dcx-app-11111111"),
+            entry(Some("/home/u/play/app"), "dcx-app-22222222"),
+            entry(Some("/home/u/lib"), "dcx-lib-33333333"),
+            entry(None, "dcx-gone-44444444"),
+            entry(Some("/a/x/tool"), "dcx-tool-55555555"),
+            entry(Some("/b/x/tool"), "dcx-tool-66666666"),
+        ]);
+        assert_eq!(
+            labels,
+            [
+                "work/app",
+                "play/app",
+                "lib",
+                "dcx-gone-44444444",
+                "dcx-tool-55555555",
+                "dcx-tool-66666666"
+            ]
+        );
+    }
+
     #[test]
     fn sanitize_alphanumeric_unchanged() {
         assert_eq!(sanitize_name("myproject"), "myproject");
@@ -252,6 +346,34 @@ mod tests {
         assert!(result.is_empty());
     }
 
+    #[test]
+    fn staging_owner_inverts_staging_dir_name() {
+        assert_eq!(
+            staging_owner(".dcx-proj-a1b2c3d4-files"),
+            Some("dcx-proj-a1b2c3d4")
+        );
+        assert_eq!(staging_owner("dcx-proj-a1b2c3d4"), None);
+        assert_eq!(staging_owner(".other-files"), None);
+    }
+
+    #[test]
+    fn scan_orphaned_staging_skips_staging_with_a_mount_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("dcx-live-a1b2c3d4")).unwrap();
+        std::fs::create_dir(dir.path().join(".dcx-live-a1b2c3d4-files")).unwrap();
+        std::fs::create_dir(dir.path().join(".dcx-gone-e5f6g7h8-files")).unwrap();
+        std::fs::create_dir(dir.path().join(".unrelated-files")).unwrap();
+        assert_eq!(
+            scan_orphaned_staging(dir.path()),
+            vec![dir.path().join(".dcx-gone-e5f6g7h8-files")]
+        );
+        // Orphaned staging dirs are never reported as mounts.
+        assert_eq!(
+            scan_relay(dir.path()),
+            vec![dir.path().join("dcx-live-a1b2c3d4")]
+        );
+    }
+
     #[test]
     fn scan_relay_filters_dcx_prefix_only() {
         let dir = tempfile::tempdir().unwrap();
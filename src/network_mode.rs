@@ -43,6 +43,123 @@ impl fmt::Display for NetworkMode {
     }
 }
 
+/// Container label recording the per-phase network policy (only set when phases differ).
+pub const POLICY_LABEL: &str = "dcx.network-policy";
+
+/// Firewall script baked into dcx devcontainer images; re-run to switch network modes.
+pub const FIREWALL_SCRIPT: &str = "/usr/local/bin/init-firewall.sh";
+
+/// Lifecycle phase a network mode applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkPhase {
+    /// Image build (`docker build` via `devcontainer up`)
+    Build,
+    /// Container creation and lifecycle commands (`postCreateCommand`, `postStartCommand`)
+    Create,
+    /// Interactive/agent runtime after lifecycle commands finish
+    Run,
+}
+
+impl fmt::Display for NetworkPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Build => write!(f, "build"),
+            Self::Create => write!(f, "create"),
+            Self::Run => write!(f, "run"),
+        }
+    }
+}
+
+impl FromStr for NetworkPhase {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "build" => Ok(Self::Build),
+            "create" => Ok(Self::Create),
+            "run" => Ok(Self::Run),
+            _ => Err(format!(
+                "Invalid network phase '{}'. Must be one of: build, create, run",
+                s
+            )),
+        }
+    }
+}
+
+/// Network mode per lifecycle phase.
+///
+/// A single `--network` value (or `up.network: <mode>`) is a uniform policy where all
+/// three phases share the same mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NetworkPolicy {
+    pub build: NetworkMode,
+    pub create: NetworkMode,
+    pub run: NetworkMode,
+}
+
+impl NetworkPolicy {
+    /// A policy that uses `mode` for every phase.
+    pub fn uniform(mode: NetworkMode) -> Self {
+        Self {
+            build: mode,
+            create: mode,
+            run: mode,
+        }
+    }
+
+    /// Returns true if at least two phases use different modes.
+    pub fn is_phased(self) -> bool {
+        self.build != self.create || self.create != self.run
+    }
+
+    /// Mode for the given phase.
+    pub fn mode_for(self, phase: NetworkPhase) -> NetworkMode {
+        match phase {
+            NetworkPhase::Build => self.build,
+            NetworkPhase::Create => self.create,
+            NetworkPhase::Run => self.run,
+        }
+    }
+
+    /// Serialize as a container label value: `build=open,create=minimal,run=restricted`.
+    pub fn to_label(self) -> String {
+        format!(
+            "build={},create={},run={}",
+            self.build, self.create, self.run
+        )
+    }
+
+    /// Parse a label value produced by `to_label`. Returns `None` if any phase is missing
+    /// or invalid.
+    pub fn parse_label(value: &str) -> Option<Self> {
+        let (mut build, mut create, mut run) = (None, None, None);
+        for part in value.split(',') {
+            let (phase, mode) = part.trim().split_once('=')?;
+            let mode = mode.parse::<NetworkMode>().ok()?;
+            match phase.parse::<NetworkPhase>().ok()? {
+                NetworkPhase::Build => build = Some(mode),
+                NetworkPhase::Create => create = Some(mode),
+                NetworkPhase::Run => run = Some(mode),
+            }
+        }
+        Some(Self {
+            build: build?,
+            create: create?,
+            run: run?,
+        })
+    }
+}
+
+/// The mode a running container should be enforcing, from its `dcx.network-mode` and
+/// `dcx.network-policy` labels: the run-phase mode for phased containers, the recorded
+/// mode otherwise. `None` when the container records no (valid) mode.
+pub fn runtime_mode(mode_label: Option<&str>, policy_label: Option<&str>) -> Option<NetworkMode> {
+    match policy_label.and_then(NetworkPolicy::parse_label) {
+        Some(policy) => Some(policy.run),
+        None => mode_label?.parse().ok(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -114,4 +231,78 @@ mod tests {
             assert_eq!(mode.to_string().parse::<NetworkMode>().unwrap(), mode);
         }
     }
+
+    // --- NetworkPolicy ---
+
+    #[test]
+    fn uniform_policy_is_not_phased() {
+        let policy = NetworkPolicy::uniform(NetworkMode::Open);
+        assert!(!policy.is_phased());
+        assert_eq!(policy.mode_for(NetworkPhase::Run), NetworkMode::Open);
+    }
+
+    #[test]
+    fn policy_with_different_run_mode_is_phased() {
+        let policy = NetworkPolicy {
+            build: NetworkMode::Open,
+            create: NetworkMode::Open,
+            run: NetworkMode::Restricted,
+        };
+        assert!(policy.is_phased());
+    }
+
+    #[test]
+    fn policy_label_round_trip() {
+        let policy = NetworkPolicy {
+            build: NetworkMode::Open,
+            create: NetworkMode::Minimal,
+            run: NetworkMode::Restricted,
+        };
+        assert_eq!(
+            policy.to_label(),
+            "build=open,create=minimal,run=restricted"
+        );
+        assert_eq!(NetworkPolicy::parse_label(&policy.to_label()), Some(policy));
+    }
+
+    #[test]
+    fn policy_label_missing_phase_is_none() {
+        assert_eq!(
+            NetworkPolicy::parse_label("build=open,run=restricted"),
+            None
+        );
+    }
+
+    #[test]
+    fn policy_label_invalid_mode_is_none() {
+        assert_eq!(
+            NetworkPolicy::parse_label("build=open,create=bogus,run=restricted"),
+            None
+        );
+    }
+
+    #[test]
+    fn phase_display_round_trip() {
+        for phase in [NetworkPhase::Build, NetworkPhase::Create, NetworkPhase::Run] {
+            assert_eq!(phase.to_string().parse::<NetworkPhase>().unwrap(), phase);
+        }
+    }
+
+    #[test]
+    fn runtime_mode_prefers_run_phase_of_policy() {
+        assert_eq!(
+            runtime_mode(Some("open"), Some("build=open,create=open,run=restricted")),
+            Some(NetworkMode::Restricted)
+        );
+        assert_eq!(
+            runtime_mode(Some("minimal"), None),
+            Some(NetworkMode::Minimal)
+        );
+        assert_eq!(
+            runtime_mode(Some("host"), Some("garbage")),
+            Some(NetworkMode::Host)
+        );
+        assert_eq!(runtime_mode(None, None), None);
+        assert_eq!(runtime_mode(Some("bogus"), None), None);
+    }
 }
@@ -0,0 +1,148 @@
+#![allow(dead_code)]
+
+use std::sync::OnceLock;
+
+use serde::Serialize;
+
+/// Version of the `--output json|jsonl` document schema.
+///
+/// Bump when a field is renamed or removed; adding fields is backwards compatible.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Output format selected with the global `--output` flag.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable tables and messages (default)
+    #[default]
+    Text,
+    /// A single JSON result document on stdout
+    Json,
+    /// One JSON object per line: progress events, then the result
+    Jsonl,
+}
+
+/// Format and command name for this process, set once by `main`.
+static SELECTED: OnceLock<(OutputFormat, &'static str)> = OnceLock::new();
+
+// ── Pure functions ────────────────────────────────────────────────────────────
+
+/// Commands (and the flag they require, if any) that support structured output.
+pub const SUPPORTED: &str = "status, inspect, df, doctor, clean --dry-run, up --dry-run and down";
+
+/// Error message for a command that does not support `--output json|jsonl`.
+pub fn unsupported_error(command: &str) -> String {
+    format!("`dcx {command}` does not support --output json/jsonl (supported: {SUPPORTED}).")
+}
+
+/// Render the result document for `command`.
+///
+/// `json` is pretty-printed; `jsonl` is a single line so it can follow progress events.
+pub fn render_result<T: Serialize>(format: OutputFormat, command: &str, data: &T) -> String {
+    let doc = serde_json::json!({
+        "schema_version": SCHEMA_VERSION,
+        "type": "result",
+        "command": command,
+        "data": data,
+    });
+    let rendered = match format {
+        OutputFormat::Json => serde_json::to_string_pretty(&doc),
+        _ => serde_json::to_string(&doc),
+    };
+    rendered.unwrap_or_default()
+}
+
+/// Render a `progress` event line for `jsonl` output.
+pub fn render_progress(command: &str, message: &str) -> String {
+    serde_json::json!({
+        "schema_version": SCHEMA_VERSION,
+        "type": "progress",
+        "command": command,
+        "message": message,
+    })
+    .to_string()
+}
+
+// ── Process-wide selection ────────────────────────────────────────────────────
+
+/// Select the output format for `command`. Later calls are ignored.
+pub fn init(format: OutputFormat, command: &'static str) {
+    let _ = SELECTED.set((format, command));
+}
+
+/// The selected output format (`Text` if `init` was never called).
+pub fn format() -> OutputFormat {
+    SELECTED.get().map(|(f, _)| *f).unwrap_or_default()
+}
+
+/// Whether a JSON format was selected, i.e. plain-text stdout must be suppressed.
+pub fn is_structured() -> bool {
+    format() != OutputFormat::Text
+}
+
+/// Print the result document for the current command to stdout.
+pub fn emit<T: Serialize>(data: &T) {
+    let (format, command) = SELECTED.get().copied().unwrap_or((OutputFormat::Json, ""));
+    println!("{}", render_result(format, command, data));
+}
+
+/// Print a progress event to stdout when `jsonl` is selected.
+///
+/// Returns `false` (and prints nothing) for the other formats.
+pub fn emit_progress(message: &str) -> bool {
+    match SELECTED.get() {
+        Some((OutputFormat::Jsonl, command)) => {
+            println!("{}", render_progress(command, message));
+            true
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Sample {
+        mount: String,
+    }
+
+    #[test]
+    fn render_result_wraps_data_with_schema_version() {
+        let data = Sample {
+            mount: "dcx-a-1".to_string(),
+        };
+        let out = render_result(OutputFormat::Jsonl, "status", &data);
+        let v: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(v["schema_version"], SCHEMA_VERSION);
+        assert_eq!(v["type"], "result");
+        assert_eq!(v["command"], "status");
+        assert_eq!(v["data"]["mount"], "dcx-a-1");
+        assert!(!out.contains('\n'));
+    }
+
+    #[test]
+    fn render_result_json_is_pretty() {
+        let out = render_result(OutputFormat::Json, "doctor", &serde_json::json!({}));
+        assert!(out.contains('\n'));
+        let v: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(v["command"], "doctor");
+    }
+
+    #[test]
+    fn render_progress_is_single_line_event() {
+        let out = render_progress("down", "Stopping devcontainer...");
+        let v: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(v["type"], "progress");
+        assert_eq!(v["command"], "down");
+        assert_eq!(v["message"], "Stopping devcontainer...");
+        assert!(!out.contains('\n'));
+    }
+
+    #[test]
+    fn unsupported_error_names_command_and_supported_list() {
+        let msg = unsupported_error("exec");
+        assert!(msg.contains("dcx exec"));
+        assert!(msg.contains("status, inspect, df, doctor"));
+    }
+}
@@ -75,6 +75,43 @@ pub fn read_mount_table() -> Result<Vec<MountEntry>, String> {
     }
 }
 
+/// Start time of process `pid`, or None if no such process is running. The value is only
+/// meaningful compared with another reading for the same PID: a PID reused by a new
+/// process gets a different start time.
+///
+/// Linux: `starttime` from `/proc/<pid>/stat` (clock ticks since boot).
+/// macOS: `pbi_start_tvsec`/`pbi_start_tvusec` from `proc_pidinfo` (microseconds).
+pub fn process_start_time(pid: u32) -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+        parse_proc_stat_start_time(&stat)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let mut info: libc::proc_bsdinfo = unsafe { std::mem::zeroed() };
+        let size = std::mem::size_of::<libc::proc_bsdinfo>() as libc::c_int;
+        // SAFETY: `info` is a writable buffer of `size` bytes.
+        let n = unsafe {
+            libc::proc_pidinfo(
+                pid as libc::c_int,
+                libc::PROC_PIDTBSDINFO,
+                0,
+                (&mut info as *mut libc::proc_bsdinfo).cast(),
+                size,
+            )
+        };
+        (n == size).then(|| info.pbi_start_tvsec * 1_000_000 + info.pbi_start_tvusec)
+    }
+}
+
+/// Field 22 (`starttime`) of a `/proc/<pid>/stat` line. The command name (field 2) may
+/// contain spaces and parentheses, so fields are counted after its closing `)`.
+pub fn parse_proc_stat_start_time(stat: &str) -> Option<u64> {
+    let rest = &stat[stat.rfind(')')? + 1..];
+    rest.split_whitespace().nth(19)?.parse().ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -126,6 +163,7 @@ mod tests {
 
     #[cfg(target_os = "linux")]
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn read_mount_table_linux_parses_proc_mounts() {
         // Test that on Linux, read_mount_table() successfully parses /proc/mounts.
         // We just ensure it doesn't panic and returns a Result.
@@ -144,6 +182,7 @@ mod tests {
 
     #[cfg(target_os = "macos")]
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn read_mount_table_macos_runs_mount_command() {
         // Test that on macOS, read_mount_table() successfully calls `mount` command.
         let result = read_mount_table();
@@ -158,4 +197,21 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn parse_proc_stat_start_time_skips_command_name() {
+        let stat = "4242 (a) b (c)) S 1 4242 4242 0 -1 4194560 100 0 0 0 1 2 0 0 20 0 1 0 \
+                    987654 1000 100 18446744073709551615";
+        assert_eq!(parse_proc_stat_start_time(stat), Some(987654));
+        assert_eq!(parse_proc_stat_start_time("4242 (sh) S 1"), None);
+        assert_eq!(parse_proc_stat_start_time(""), None);
+    }
+
+    #[test]
+    fn process_start_time_is_stable_for_a_running_process() {
+        let ours = process_start_time(std::process::id());
+        assert!(ours.is_some());
+        assert_eq!(process_start_time(std::process::id()), ours);
+        assert_eq!(process_start_time(999_999_999), None);
+    }
 }
@@ -8,8 +8,15 @@ pub fn format_step(msg: &str) -> String {
 }
 
 /// Print a progress step to stderr: `→ <message>`.
+///
+/// During `dcx up` the step is also recorded in the run log. With `--output jsonl`
+/// it is printed to stdout as a `progress` event instead.
 pub fn step(msg: &str) {
-    eprintln!("{}", format_step(msg));
+    let line = format_step(msg);
+    crate::run_log::append(&line);
+    if !crate::output::emit_progress(msg) {
+        eprintln!("{line}");
+    }
 }
 
 #[cfg(test)]
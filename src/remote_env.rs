@@ -0,0 +1,491 @@
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::cmd;
+use crate::docker;
+use crate::state;
+
+/// Marker printed before and after the probed environment so shell start-up noise
+/// (motd, rc-file echo) can be discarded.
+const PROBE_MARKER: &str = "__DCX_ENV_PROBE__";
+
+/// Variables that describe the probe shell itself rather than the user's environment.
+/// The identity variables (`HOME`, `USER`, ...) describe the user the probe ran as, which
+/// need not be the user of a later exec.
+const VOLATILE_VARS: &[&str] = &[
+    "_", "PWD", "OLDPWD", "SHLVL", "HOSTNAME", "TERM", "HOME", "USER", "LOGNAME", "SHELL", "MAIL",
+];
+
+// ── userEnvProbe ──────────────────────────────────────────────────────────────
+
+/// `userEnvProbe` from devcontainer.json: which shell environment to capture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UserEnvProbe {
+    None,
+    LoginShell,
+    InteractiveShell,
+    /// Default per the devcontainer spec.
+    #[default]
+    LoginInteractiveShell,
+}
+
+impl UserEnvProbe {
+    /// Parse the devcontainer.json value. Unknown values fall back to the default.
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "none" => Self::None,
+            "loginShell" => Self::LoginShell,
+            "interactiveShell" => Self::InteractiveShell,
+            _ => Self::LoginInteractiveShell,
+        }
+    }
+
+    /// Shell flags for this probe, or `None` when probing is disabled.
+    fn shell_flags(self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::LoginShell => Some("-lc"),
+            Self::InteractiveShell => Some("-ic"),
+            Self::LoginInteractiveShell => Some("-lic"),
+        }
+    }
+}
+
+// ── Config parsing ────────────────────────────────────────────────────────────
+
+/// Environment-related settings read from devcontainer.json.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EnvConfig {
+    /// `remoteEnv` entries, sorted by key. `null` values are skipped.
+    pub remote_env: Vec<(String, String)>,
+    pub probe: UserEnvProbe,
+}
+
+/// Parse `remoteEnv` and `userEnvProbe` from devcontainer.json (JSONC) content.
+///
+/// Returns the default (no remoteEnv, default probe) if the content cannot be parsed.
+pub fn parse_env_config(jsonc: &str) -> EnvConfig {
+    let Some(value) = docker::parse_jsonc(jsonc) else {
+        return EnvConfig::default();
+    };
+    let remote_env = value
+        .get("remoteEnv")
+        .and_then(|v| v.as_object())
+        .map(|obj| {
+            obj.iter()
+                .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+    let probe = value
+        .get("userEnvProbe")
+        .and_then(|v| v.as_str())
+        .map(UserEnvProbe::parse)
+        .unwrap_or_default();
+    EnvConfig { remote_env, probe }
+}
+
+/// Expand `${containerEnv:NAME}`, `${containerEnv:NAME:default}` and `${localEnv:NAME}`
+/// (with optional default) in `value`.
+///
+/// `container_env` is looked up for `containerEnv`; `local_env` for `localEnv`.
+/// Unknown variables expand to the default, or the empty string. Other `${...}`
+/// expressions are left as-is.
+pub fn substitute(
+    value: &str,
+    container_env: &[(String, String)],
+    local_env: impl Fn(&str) -> Option<String>,
+) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            result.push_str(&rest[start..]);
+            return result;
+        };
+        let expr = &after[..end];
+        let mut parts = expr.splitn(3, ':');
+        let kind = parts.next().unwrap_or("");
+        let name = parts.next();
+        let default = parts.next().unwrap_or("");
+        match (kind, name) {
+            ("containerEnv", Some(name)) => {
+                let v = container_env
+                    .iter()
+                    .rev()
+                    .find(|(k, _)| k == name)
+                    .map(|(_, v)| v.clone());
+                result.push_str(&v.unwrap_or_else(|| default.to_string()));
+            }
+            ("localEnv", Some(name)) => {
+                result.push_str(&local_env(name).unwrap_or_else(|| default.to_string()));
+            }
+            _ => result.push_str(&rest[start..start + 2 + end + 1]),
+        }
+        rest = &after[end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+// ── Probe ─────────────────────────────────────────────────────────────────────
+
+/// Build the `docker exec` arguments that print the user's shell environment.
+///
+/// The user's login shell is looked up in `/etc/passwd` inside the container (falling
+/// back to `/bin/sh`) and started with the probe's flags. The environment is read from
+/// `/proc/self/environ` (NUL-separated, so values may contain newlines), between markers.
+pub fn probe_args(
+    container_id: &str,
+    user: Option<&str>,
+    probe: UserEnvProbe,
+) -> Option<Vec<String>> {
+    let flags = probe.shell_flags()?;
+    let script = format!(
+        "shell=$(getent passwd \"$(id -un)\" 2>/dev/null | cut -d: -f7); \
+         exec \"${{shell:-/bin/sh}}\" {flags} 'printf {PROBE_MARKER}; cat /proc/self/environ; printf {PROBE_MARKER}' </dev/null"
+    );
+    let mut args = vec!["exec".to_string()];
+    if let Some(user) = user {
+        args.push("-u".to_string());
+        args.push(user.to_string());
+    }
+    args.push(container_id.to_string());
+    args.push("sh".to_string());
+    args.push("-c".to_string());
+    args.push(script);
+    Some(args)
+}
+
+/// Extract `KEY=VAL` pairs from probe output, dropping volatile shell variables.
+pub fn parse_probe_output(stdout: &str) -> Vec<(String, String)> {
+    let mut sections = stdout.split(PROBE_MARKER);
+    let (Some(_), Some(env), Some(_)) = (sections.next(), sections.next(), sections.next()) else {
+        return Vec::new();
+    };
+    env.split('\0')
+        .filter_map(|e| e.split_once('='))
+        .filter(|(k, _)| !k.is_empty() && !VOLATILE_VARS.contains(k))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+/// Compute the environment to inject into `docker exec`.
+///
+/// Starts from the probed shell environment (only variables that differ from the
+/// container's configured env), then applies `remoteEnv` with substitution against the
+/// combined container + probed env. Later entries override earlier ones.
+pub fn merge_env(
+    container_env: &[(String, String)],
+    probed: &[(String, String)],
+    config: &EnvConfig,
+    local_env: impl Fn(&str) -> Option<String>,
+) -> Vec<(String, String)> {
+    let mut merged: Vec<(String, String)> = probed
+        .iter()
+        .filter(|(k, v)| !container_env.iter().any(|(ck, cv)| ck == k && cv == v))
+        .cloned()
+        .collect();
+
+    let mut lookup: Vec<(String, String)> = container_env.to_vec();
+    lookup.extend(probed.iter().cloned());
+
+    for (key, raw) in &config.remote_env {
+        let value = substitute(raw, &lookup, &local_env);
+        merged.retain(|(k, _)| k != key);
+        merged.push((key.clone(), value.clone()));
+        lookup.push((key.clone(), value));
+    }
+    merged
+}
+
+// ── Cache ─────────────────────────────────────────────────────────────────────
+
+/// Cached exec environment for one container, user and config revision.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvCache {
+    pub container_id: String,
+    /// User the probe ran as (`None`: the container's default user).
+    pub user: Option<String>,
+    /// SHA-256 of the devcontainer.json content (prefix), so config edits invalidate.
+    pub config_hash: String,
+    pub env: Vec<(String, String)>,
+}
+
+/// Path of the exec-environment cache for a workspace.
+pub fn cache_path(home: &Path, mount_name: &str) -> PathBuf {
+    state::state_dir(home, mount_name).join("remote-env.json")
+}
+
+/// Short content hash used to key the cache on the devcontainer.json revision.
+pub fn config_hash(content: &str) -> String {
+    let digest = Sha256::digest(content.as_bytes());
+    digest.iter().take(8).map(|b| format!("{b:02x}")).collect()
+}
+
+/// Load the cache if it matches `container_id`, `user` and `config_hash`.
+pub fn load_cache(
+    path: &Path,
+    container_id: &str,
+    user: Option<&str>,
+    config_hash: &str,
+) -> Option<EnvCache> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let cache: EnvCache = serde_json::from_str(&content).ok()?;
+    (cache.container_id == container_id
+        && cache.user.as_deref() == user
+        && cache.config_hash == config_hash)
+        .then_some(cache)
+}
+
+/// Write the cache, creating the state directory if needed. The file can hold secrets from
+/// the container environment, so it is only readable by the owner (0600).
+pub fn save_cache(path: &Path, cache: &EnvCache) -> Result<(), String> {
+    use std::io::Write;
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    let json = serde_json::to_string(cache).map_err(|e| e.to_string())?;
+    let write_err = |e: std::io::Error| format!("Failed to write {}: {e}", path.display());
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)
+        .map_err(write_err)?;
+    // `mode` only applies on creation; tighten a cache written by an older dcx.
+    file.set_permissions(std::fs::Permissions::from_mode(0o600))
+        .map_err(write_err)?;
+    file.write_all(json.as_bytes()).map_err(write_err)
+}
+
+// ── Entry point ───────────────────────────────────────────────────────────────
+
+/// Resolve the `-e KEY=VAL` entries to inject into `docker exec` for a container.
+///
+/// Uses the workspace's cached result when it matches the container, user and config;
+/// otherwise reads the container env, runs the userEnvProbe once, applies remoteEnv,
+/// and caches the result. Failures degrade to an empty environment with a warning.
+pub fn exec_env(
+    home: &Path,
+    mount_name: &str,
+    container_id: &str,
+    user: Option<&str>,
+    config_json: Option<&Path>,
+) -> Vec<String> {
+    let content = config_json
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .unwrap_or_default();
+    let hash = config_hash(&content);
+    let path = cache_path(home, mount_name);
+
+    let env = match load_cache(&path, container_id, user, &hash) {
+        Some(cache) => cache.env,
+        None => {
+            let config = parse_env_config(&content);
+            let container_env = docker::container_env(container_id);
+            let probed = match probe_args(container_id, user, config.probe) {
+                Some(args) => {
+                    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+                    match cmd::run_capture("docker", &args) {
+                        Ok(out) if out.status == 0 => parse_probe_output(&out.stdout),
+                        _ => {
+                            eprintln!("Warning: userEnvProbe failed; using container env only.");
+                            Vec::new()
+                        }
+                    }
+                }
+                None => Vec::new(),
+            };
+            let env = merge_env(&container_env, &probed, &config, |k| std::env::var(k).ok());
+            let cache = EnvCache {
+                container_id: container_id.to_string(),
+                user: user.map(str::to_string),
+                config_hash: hash,
+                env,
+            };
+            if let Err(e) = save_cache(&path, &cache) {
+                eprintln!("Warning: Could not cache exec environment: {e}");
+            }
+            cache.env
+        }
+    };
+    env.into_iter().map(|(k, v)| format!("{k}={v}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    // --- parse_env_config ---
+
+    #[test]
+    fn parse_env_config_reads_remote_env_and_probe() {
+        let json = r#"{
+            // comment
+            "remoteEnv": { "PATH": "${containerEnv:PATH}:/opt/bin", "GONE": null, },
+            "userEnvProbe": "loginShell"
+        }"#;
+        let cfg = parse_env_config(json);
+        assert_eq!(
+            cfg.remote_env,
+            pairs(&[("PATH", "${containerEnv:PATH}:/opt/bin")])
+        );
+        assert_eq!(cfg.probe, UserEnvProbe::LoginShell);
+    }
+
+    #[test]
+    fn parse_env_config_defaults_when_absent() {
+        let cfg = parse_env_config(r#"{"image": "ubuntu"}"#);
+        assert!(cfg.remote_env.is_empty());
+        assert_eq!(cfg.probe, UserEnvProbe::LoginInteractiveShell);
+    }
+
+    #[test]
+    fn parse_env_config_probe_none_disables_probe() {
+        let cfg = parse_env_config(r#"{"userEnvProbe": "none"}"#);
+        assert!(probe_args("abc", None, cfg.probe).is_none());
+    }
+
+    // --- substitute ---
+
+    #[test]
+    fn substitute_container_env() {
+        let env = pairs(&[("PATH", "/usr/bin")]);
+        let out = substitute("${containerEnv:PATH}:/opt/bin", &env, |_| None);
+        assert_eq!(out, "/usr/bin:/opt/bin");
+    }
+
+    #[test]
+    fn substitute_missing_uses_default_or_empty() {
+        let out = substitute(
+            "${containerEnv:NOPE:fallback}-${containerEnv:NOPE}",
+            &[],
+            |_| None,
+        );
+        assert_eq!(out, "fallback-");
+    }
+
+    #[test]
+    fn substitute_local_env() {
+        let out = substitute("${localEnv:USER}", &[], |k| {
+            (k == "USER").then(|| "alice".to_string())
+        });
+        assert_eq!(out, "alice");
+    }
+
+    #[test]
+    fn substitute_leaves_unknown_expressions() {
+        let out = substitute("${containerWorkspaceFolder}/x", &[], |_| None);
+        assert_eq!(out, "${containerWorkspaceFolder}/x");
+    }
+
+    // --- parse_probe_output ---
+
+    #[test]
+    fn parse_probe_output_ignores_noise_and_volatile_vars() {
+        let stdout = format!(
+            "Welcome!\n{PROBE_MARKER}PATH=/home/u/.cargo/bin:/usr/bin\0PWD=/\0MULTI=a\nb\0{PROBE_MARKER}bye"
+        );
+        assert_eq!(
+            parse_probe_output(&stdout),
+            pairs(&[("PATH", "/home/u/.cargo/bin:/usr/bin"), ("MULTI", "a\nb")])
+        );
+    }
+
+    #[test]
+    fn parse_probe_output_drops_identity_vars() {
+        let stdout = format!(
+            "{PROBE_MARKER}HOME=/root\0USER=root\0LOGNAME=root\0SHELL=/bin/bash\0MAIL=/var/mail/root\0EDITOR=vim\0{PROBE_MARKER}"
+        );
+        assert_eq!(parse_probe_output(&stdout), pairs(&[("EDITOR", "vim")]));
+    }
+
+    #[test]
+    fn parse_probe_output_without_markers_is_empty() {
+        assert!(parse_probe_output("PATH=/usr/bin").is_empty());
+    }
+
+    // --- merge_env ---
+
+    #[test]
+    fn merge_env_keeps_only_probed_changes_then_applies_remote_env() {
+        let container = pairs(&[("PATH", "/usr/bin"), ("HOME", "/home/u")]);
+        let probed = pairs(&[("PATH", "/home/u/.cargo/bin:/usr/bin"), ("HOME", "/home/u")]);
+        let config = EnvConfig {
+            remote_env: pairs(&[("PATH", "${containerEnv:PATH}:/opt/bin"), ("FOO", "bar")]),
+            probe: UserEnvProbe::LoginShell,
+        };
+        let merged = merge_env(&container, &probed, &config, |_| None);
+        assert_eq!(
+            merged,
+            pairs(&[
+                ("PATH", "/home/u/.cargo/bin:/usr/bin:/opt/bin"),
+                ("FOO", "bar")
+            ])
+        );
+    }
+
+    // --- cache ---
+
+    #[test]
+    fn cache_round_trip_and_invalidation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("remote-env.json");
+        let cache = EnvCache {
+            container_id: "abc".to_string(),
+            user: Some("vscode".to_string()),
+            config_hash: "h1".to_string(),
+            env: pairs(&[("FOO", "bar")]),
+        };
+        save_cache(&path, &cache).unwrap();
+        assert_eq!(load_cache(&path, "abc", Some("vscode"), "h1"), Some(cache));
+        assert_eq!(load_cache(&path, "other", Some("vscode"), "h1"), None);
+        assert_eq!(load_cache(&path, "abc", Some("vscode"), "h2"), None);
+        assert_eq!(load_cache(&path, "abc", Some("root"), "h1"), None);
+        assert_eq!(load_cache(&path, "abc", None, "h1"), None);
+    }
+
+    #[test]
+    fn save_cache_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("remote-env.json");
+        std::fs::write(&path, "{}").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        let cache = EnvCache {
+            container_id: "abc".to_string(),
+            user: None,
+            config_hash: "h1".to_string(),
+            env: pairs(&[("TOKEN", "secret")]),
+        };
+        save_cache(&path, &cache).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(load_cache(&path, "abc", None, "h1"), Some(cache));
+    }
+
+    #[test]
+    fn config_hash_changes_with_content() {
+        assert_ne!(config_hash("{}"), config_hash("{\"a\":1}"));
+        assert_eq!(config_hash("{}").len(), 16);
+    }
+}
@@ -0,0 +1,256 @@
+#![allow(dead_code)]
+
+use std::ffi::OsStr;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+use crate::duration::format_timestamp;
+use crate::state;
+
+/// Number of `dcx up` run logs kept per workspace; older runs are pruned.
+pub const MAX_UP_RUNS: usize = 10;
+
+const LOG_EXT: &str = "log";
+
+/// The run log currently being written, if any (one `dcx up` per process).
+static ACTIVE: Mutex<Option<ActiveLog>> = Mutex::new(None);
+
+struct ActiveLog {
+    path: PathBuf,
+    file: std::fs::File,
+}
+
+// ── Pure functions ────────────────────────────────────────────────────────────
+
+/// Run ID: `<unix_ts>-<pid as 4 hex digits>`, which sorts chronologically.
+pub fn make_run_id(now: u64, pid: u32) -> String {
+    format!("{now}-{:04x}", pid & 0xffff)
+}
+
+/// Format a log line: `<RFC 3339 UTC> <text>`.
+pub fn format_line(timestamp: u64, text: &str) -> String {
+    format!("{} {text}", format_timestamp(timestamp))
+}
+
+/// Run IDs that should be pruned so that at most `keep` remain (oldest first).
+pub fn runs_to_prune(mut ids: Vec<String>, keep: usize) -> Vec<String> {
+    ids.sort();
+    let excess = ids.len().saturating_sub(keep);
+    ids.truncate(excess);
+    ids
+}
+
+// ── Paths ─────────────────────────────────────────────────────────────────────
+
+/// Per-workspace directory of `dcx up` run logs.
+pub fn up_logs_dir(home: &Path, mount_name: &str) -> PathBuf {
+    state::state_dir(home, mount_name).join("up-logs")
+}
+
+/// Run IDs with a log in `dir`, oldest first.
+pub fn list_runs(dir: &Path) -> Vec<String> {
+    let mut ids: Vec<String> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|e| e == LOG_EXT))
+                .filter_map(|p| Some(p.file_stem()?.to_string_lossy().into_owned()))
+                .collect()
+        })
+        .unwrap_or_default();
+    ids.sort();
+    ids
+}
+
+/// Log file of run `id`.
+pub fn run_log_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{id}.{LOG_EXT}"))
+}
+
+// ── Active log ────────────────────────────────────────────────────────────────
+
+/// Start logging a `dcx up` run for `workspace`: creates the log, prunes old runs and
+/// routes `progress::step` messages and `run_stream` output into it.
+///
+/// Failures are reported as a warning; the run proceeds without a log.
+pub fn start(home: &Path, mount_name: &str, workspace: &Path) {
+    let dir = up_logs_dir(home, mount_name);
+    let now = state::unix_now();
+    let id = make_run_id(now, std::process::id());
+    let path = run_log_path(&dir, &id);
+    let opened = std::fs::create_dir_all(&dir).and_then(|_| {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+    });
+    let mut file = match opened {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("Warning: Could not create up log {}: {e}", path.display());
+            return;
+        }
+    };
+    for old in runs_to_prune(list_runs(&dir), MAX_UP_RUNS) {
+        let _ = std::fs::remove_file(run_log_path(&dir, &old));
+    }
+    let _ = writeln!(
+        file,
+        "{}",
+        format_line(now, &format!("dcx up run {id} for {}", workspace.display()))
+    );
+    if let Ok(mut active) = ACTIVE.lock() {
+        *active = Some(ActiveLog { path, file });
+    }
+}
+
+/// Append a line to the active log (no-op when no run is being logged).
+pub fn append(text: &str) {
+    if let Ok(mut active) = ACTIVE.lock()
+        && let Some(log) = active.as_mut()
+    {
+        let _ = writeln!(log.file, "{}", format_line(state::unix_now(), text));
+    }
+}
+
+/// Print an error to stderr and copy it into the active log (tagged `ERROR`), so the log a
+/// failed `dcx up` points to contains the failure itself.
+pub fn error(text: &str) {
+    append(&format!("ERROR {text}"));
+    eprintln!("{text}");
+}
+
+/// Print a warning to stderr and copy it into the active log (tagged `WARN`).
+pub fn warn(text: &str) {
+    append(&format!("WARN {text}"));
+    eprintln!("{text}");
+}
+
+/// Finish the active log with the run's exit code and return its path.
+pub fn finish(code: i32) -> Option<PathBuf> {
+    let mut log = ACTIVE.lock().ok()?.take()?;
+    let _ = writeln!(
+        log.file,
+        "{}",
+        format_line(
+            state::unix_now(),
+            &format!("dcx up exited with code {code}")
+        )
+    );
+    Some(log.path)
+}
+
+/// Like `cmd::run_stream`, but also copies every output line into the active log.
+///
+/// stdin is inherited; stdout and stderr are relayed to the terminal as they arrive.
+/// Without an active log this is exactly `cmd::run_stream`.
+pub fn run_stream<S: AsRef<OsStr>>(prog: &str, args: &[S]) -> Result<i32, String> {
+    let logging = ACTIVE.lock().map(|a| a.is_some()).unwrap_or(false);
+    if !logging {
+        return crate::cmd::run_stream(prog, args);
+    }
+    let mut child = Command::new(prog)
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run {prog}: {e}"))?;
+    let out = child
+        .stdout
+        .take()
+        .map(|o| std::thread::spawn(move || relay(o, std::io::stdout())));
+    let err = child
+        .stderr
+        .take()
+        .map(|e| std::thread::spawn(move || relay(e, std::io::stderr())));
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to run {prog}: {e}"))?;
+    for handle in [out, err].into_iter().flatten() {
+        let _ = handle.join();
+    }
+    Ok(status.code().unwrap_or(1))
+}
+
+/// Copy `reader` to `terminal` chunk by chunk, logging each complete line.
+fn relay<R: Read, W: Write>(mut reader: R, mut terminal: W) {
+    let mut chunk = [0u8; 8192];
+    let mut pending: Vec<u8> = Vec::new();
+    loop {
+        let n = match reader.read(&mut chunk) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => n,
+        };
+        let _ = terminal.write_all(&chunk[..n]);
+        let _ = terminal.flush();
+        pending.extend_from_slice(&chunk[..n]);
+        while let Some(pos) = pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=pos).collect();
+            append(String::from_utf8_lossy(&line).trim_end_matches(['\r', '\n']));
+        }
+    }
+    if !pending.is_empty() {
+        append(String::from_utf8_lossy(&pending).trim_end_matches('\r'));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn make_run_id_sorts_by_time() {
+        assert_eq!(make_run_id(1_700_000_000, 0x1234), "1700000000-1234");
+        assert_eq!(make_run_id(5, 0x12345), "5-2345");
+        assert!(make_run_id(1_700_000_000, 9) < make_run_id(1_700_000_001, 1));
+    }
+
+    #[test]
+    fn format_line_prefixes_timestamp() {
+        assert_eq!(
+            format_line(1_700_000_000, "→ Starting devcontainer..."),
+            "2023-11-14T22:13:20Z → Starting devcontainer..."
+        );
+    }
+
+    #[test]
+    fn runs_to_prune_keeps_newest() {
+        let ids = vec!["3-0000", "1-0000", "2-0000"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(runs_to_prune(ids, 2), vec!["1-0000".to_string()]);
+        assert!(runs_to_prune(vec!["1-0000".to_string()], 2).is_empty());
+    }
+
+    #[test]
+    fn list_runs_returns_log_stems_oldest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("20-0001.log"), "").unwrap();
+        std::fs::write(dir.path().join("10-0001.log"), "").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "").unwrap();
+        assert_eq!(list_runs(dir.path()), vec!["10-0001", "20-0001"]);
+        assert!(list_runs(&dir.path().join("missing")).is_empty());
+    }
+
+    #[test]
+    fn errors_and_warnings_are_copied_into_the_active_log() {
+        // One test only: the active log is process-global.
+        let home = tempfile::tempdir().unwrap();
+        start(home.path(), "dcx-proj-abcd1234", Path::new("/home/u/proj"));
+        warn("Warning: Could not tag base image: gone");
+        error("Failed to mount: busy");
+        let path = finish(1).unwrap();
+        let log = std::fs::read_to_string(path).unwrap();
+        assert!(
+            log.contains(" WARN Warning: Could not tag base image: gone\n"),
+            "got: {log}"
+        );
+        assert!(log.contains(" ERROR Failed to mount: busy\n"), "got: {log}");
+        assert!(log.ends_with("dcx up exited with code 1\n"), "got: {log}");
+    }
+}
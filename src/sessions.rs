@@ -0,0 +1,502 @@
+#![allow(dead_code)]
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+
+use crate::duration::{format_age, format_duration};
+use crate::exit_codes;
+use crate::format::{SessionRow, format_sessions_table};
+use crate::progress;
+use crate::state;
+
+/// Longest pause reproduced by `dcx sessions replay`; longer idle gaps are shortened.
+pub const MAX_REPLAY_IDLE_SECS: f64 = 2.0;
+
+const CAST_EXT: &str = "cast";
+
+// ── Asciicast format ──────────────────────────────────────────────────────────
+
+/// Header line of an asciicast v2 transcript.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CastHeader {
+    pub width: u16,
+    pub height: u16,
+    /// Unix timestamp when recording started.
+    pub timestamp: u64,
+    /// The command run in the session.
+    pub title: String,
+    /// Original workspace path (dcx extension; ignored by other players).
+    pub workspace: String,
+}
+
+/// Format the asciicast v2 header line.
+pub fn format_header(h: &CastHeader) -> String {
+    json!({
+        "version": 2,
+        "width": h.width,
+        "height": h.height,
+        "timestamp": h.timestamp,
+        "title": h.title,
+        "dcx_workspace": h.workspace,
+    })
+    .to_string()
+}
+
+/// Format an output event line: `[elapsed, "o", data]`.
+pub fn format_event(elapsed: f64, data: &str) -> String {
+    json!([(elapsed * 1_000_000.0).round() / 1_000_000.0, "o", data]).to_string()
+}
+
+/// Parse an asciicast v2 transcript into its header and output events.
+///
+/// Returns `None` if the header is missing or not version 2. Malformed event lines
+/// and non-output events are skipped.
+pub fn parse_cast(content: &str) -> Option<(CastHeader, Vec<(f64, String)>)> {
+    let mut lines = content.lines();
+    let header: serde_json::Value = serde_json::from_str(lines.next()?).ok()?;
+    if header.get("version")?.as_u64()? != 2 {
+        return None;
+    }
+    let str_field = |k: &str| {
+        header
+            .get(k)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let num_field = |k: &str| header.get(k).and_then(|v| v.as_u64()).unwrap_or(0);
+    let parsed = CastHeader {
+        width: num_field("width") as u16,
+        height: num_field("height") as u16,
+        timestamp: num_field("timestamp"),
+        title: str_field("title"),
+        workspace: str_field("dcx_workspace"),
+    };
+    let events = lines
+        .filter_map(|l| serde_json::from_str::<serde_json::Value>(l).ok())
+        .filter_map(|v| {
+            let arr = v.as_array()?;
+            if arr.get(1)?.as_str()? != "o" {
+                return None;
+            }
+            Some((arr.first()?.as_f64()?, arr.get(2)?.as_str()?.to_string()))
+        })
+        .collect();
+    Some((parsed, events))
+}
+
+/// Length of the longest prefix of `buf` that does not end in a partial UTF-8 sequence.
+///
+/// Terminal output is read in arbitrary chunks; holding back an incomplete trailing
+/// character keeps multi-byte characters intact across events.
+pub fn complete_utf8_len(buf: &[u8]) -> usize {
+    match std::str::from_utf8(buf) {
+        Ok(_) => buf.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        // Invalid bytes mid-stream: emit everything (lossily) rather than stall.
+        Err(_) => buf.len(),
+    }
+}
+
+/// Delays between replayed events, scaled by `speed` and capped at `max_idle` seconds.
+pub fn replay_delays(events: &[(f64, String)], speed: f64, max_idle: f64) -> Vec<f64> {
+    let mut prev = 0.0;
+    events
+        .iter()
+        .map(|(t, _)| {
+            let gap = (t - prev).max(0.0).min(max_idle);
+            prev = *t;
+            gap / speed
+        })
+        .collect()
+}
+
+// ── PTY wrapper ───────────────────────────────────────────────────────────────
+
+/// Quote a string for a POSIX shell.
+pub fn shell_quote(s: &str) -> String {
+    if !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:@,+".contains(c))
+    {
+        s.to_string()
+    } else {
+        format!("'{}'", s.replace('\'', r"'\''"))
+    }
+}
+
+/// Arguments for `script(1)` to run `prog args...` on a fresh pseudo-terminal.
+///
+/// `script` puts the real terminal in raw mode and relays it to the child's PTY, so the
+/// session stays fully interactive while its output passes through dcx for recording.
+///
+/// Linux (util-linux): `["-q", "-e", "-c", "<quoted command>", "/dev/null"]`
+/// macOS (BSD): `["-q", "/dev/null", prog, args...]`
+pub fn script_args(prog: &str, args: &[String]) -> Vec<String> {
+    #[cfg(target_os = "linux")]
+    {
+        let command = std::iter::once(prog)
+            .chain(args.iter().map(String::as_str))
+            .map(shell_quote)
+            .collect::<Vec<_>>()
+            .join(" ");
+        vec![
+            "-q".to_string(),
+            "-e".to_string(),
+            "-c".to_string(),
+            command,
+            "/dev/null".to_string(),
+        ]
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let mut v = vec!["-q".to_string(), "/dev/null".to_string(), prog.to_string()];
+        v.extend(args.iter().cloned());
+        v
+    }
+}
+
+/// Open a transcript for appending. It captures everything the terminal showed (echoed
+/// tokens, `env` output), so it is only readable by the owner (0600).
+fn open_transcript(path: &Path) -> std::io::Result<std::fs::File> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .mode(0o600)
+        .open(path)
+}
+
+// ── Paths ─────────────────────────────────────────────────────────────────────
+
+/// Per-workspace transcript directory: `~/.local/state/dcx/<mount>/sessions`.
+pub fn sessions_dir(home: &Path, mount_name: &str) -> PathBuf {
+    state::state_dir(home, mount_name).join("sessions")
+}
+
+/// All transcripts across workspaces.
+pub fn all_transcripts(home: &Path) -> Vec<PathBuf> {
+    let Ok(workspaces) = std::fs::read_dir(state::state_root(home)) else {
+        return Vec::new();
+    };
+    workspaces
+        .flatten()
+        .filter_map(|ws| std::fs::read_dir(ws.path().join("sessions")).ok())
+        .flat_map(|files| files.flatten().map(|f| f.path()))
+        .filter(|p| p.extension().is_some_and(|e| e == CAST_EXT))
+        .collect()
+}
+
+/// Find transcript `id` across all workspaces.
+pub fn find_transcript(home: &Path, id: &str) -> Option<PathBuf> {
+    all_transcripts(home)
+        .into_iter()
+        .find(|p| p.file_stem().is_some_and(|s| s == id))
+}
+
+// ── I/O helpers ───────────────────────────────────────────────────────────────
+
+/// Size of the controlling terminal as (columns, rows); 80x24 if unknown.
+fn terminal_size() -> (u16, u16) {
+    std::process::Command::new("stty")
+        .arg("size")
+        .stdin(std::process::Stdio::inherit())
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()
+        .and_then(|out| {
+            let s = String::from_utf8_lossy(&out.stdout).into_owned();
+            let mut parts = s.split_whitespace();
+            let rows = parts.next()?.parse().ok()?;
+            let cols = parts.next()?.parse().ok()?;
+            Some((cols, rows))
+        })
+        .unwrap_or((80, 24))
+}
+
+// ── Entry points ──────────────────────────────────────────────────────────────
+
+/// Run `docker <docker_args>` interactively while recording the terminal stream to an
+/// asciicast v2 transcript in the workspace's sessions directory.
+///
+/// Returns the exit code of the session.
+pub fn run_recorded(
+    home: &Path,
+    mount_name: &str,
+    workspace: &Path,
+    docker_args: &[String],
+    command: &[String],
+) -> i32 {
+    let started = state::unix_now();
+    let id = format!("{started}-{:04x}", std::process::id() & 0xffff);
+    let dir = sessions_dir(home, mount_name);
+    let path = dir.join(format!("{id}.{CAST_EXT}"));
+    let (width, height) = terminal_size();
+    let header = CastHeader {
+        width,
+        height,
+        timestamp: started,
+        title: command.join(" "),
+        workspace: workspace.to_string_lossy().into_owned(),
+    };
+    let mut transcript = match std::fs::create_dir_all(&dir).and_then(|_| open_transcript(&path)) {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("Failed to create transcript {}: {e}", path.display());
+            return exit_codes::RUNTIME_ERROR;
+        }
+    };
+    let _ = writeln!(transcript, "{}", format_header(&header));
+    progress::step(&format!("Recording session to {}", path.display()));
+
+    let mut child = match std::process::Command::new("script")
+        .args(script_args("docker", docker_args))
+        .stdin(std::process::Stdio::inherit())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::inherit())
+        .spawn()
+    {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Failed to run script: {e}");
+            return exit_codes::PREREQ_NOT_FOUND;
+        }
+    };
+
+    let clock = std::time::Instant::now();
+    let mut out = child.stdout.take().expect("stdout is piped");
+    let mut stdout = std::io::stdout();
+    let mut chunk = [0u8; 8192];
+    let mut pending: Vec<u8> = Vec::new();
+    loop {
+        let n = match out.read(&mut chunk) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => n,
+        };
+        let _ = stdout.write_all(&chunk[..n]);
+        let _ = stdout.flush();
+        pending.extend_from_slice(&chunk[..n]);
+        let len = complete_utf8_len(&pending);
+        if len > 0 {
+            let data = String::from_utf8_lossy(&pending[..len]).into_owned();
+            let _ = writeln!(
+                transcript,
+                "{}",
+                format_event(clock.elapsed().as_secs_f64(), &data)
+            );
+            pending.drain(..len);
+        }
+    }
+    if !pending.is_empty() {
+        let data = String::from_utf8_lossy(&pending).into_owned();
+        let _ = writeln!(
+            transcript,
+            "{}",
+            format_event(clock.elapsed().as_secs_f64(), &data)
+        );
+    }
+
+    child
+        .wait()
+        .map(|s| s.code().unwrap_or(exit_codes::RUNTIME_ERROR))
+        .unwrap_or(exit_codes::RUNTIME_ERROR)
+}
+
+/// Run `dcx sessions list`: list recorded transcripts across workspaces, newest first.
+pub fn run_sessions_list(home: &Path) -> i32 {
+    let now = state::unix_now();
+    let mut sessions: Vec<(String, CastHeader, f64)> = all_transcripts(home)
+        .into_iter()
+        .filter_map(|p| {
+            let id = p.file_stem()?.to_string_lossy().into_owned();
+            let (header, events) = parse_cast(&std::fs::read_to_string(&p).ok()?)?;
+            let length = events.last().map(|(t, _)| *t).unwrap_or(0.0);
+            Some((id, header, length))
+        })
+        .collect();
+    if sessions.is_empty() {
+        println!("No recorded sessions.");
+        return exit_codes::SUCCESS;
+    }
+    sessions.sort_by_key(|(id, h, _)| std::cmp::Reverse((h.timestamp, id.clone())));
+    let rows: Vec<SessionRow> = sessions
+        .into_iter()
+        .map(|(id, h, length)| SessionRow {
+            id,
+            workspace: Path::new(&h.workspace)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "(unknown)".to_string()),
+            started: format_age(now.saturating_sub(h.timestamp)),
+            length: format_duration(length as u64),
+            command: h.title,
+        })
+        .collect();
+    println!("{}", format_sessions_table(&rows));
+    exit_codes::SUCCESS
+}
+
+/// Run `dcx sessions replay <id>`: play a transcript back in the terminal with its
+/// original timing (scaled by `speed`, idle gaps capped at `MAX_REPLAY_IDLE_SECS`).
+pub fn run_sessions_replay(home: &Path, id: &str, speed: f64) -> i32 {
+    if !(speed.is_finite() && speed > 0.0) {
+        eprintln!("Invalid speed '{speed}'. Use a positive number (e.g. 2 for double speed).");
+        return exit_codes::USAGE_ERROR;
+    }
+    let Some(path) = find_transcript(home, id) else {
+        eprintln!("No recorded session found with ID {id}.");
+        return exit_codes::USAGE_ERROR;
+    };
+    let Some((_, events)) = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|c| parse_cast(&c))
+    else {
+        eprintln!("{} is not a valid asciicast v2 transcript.", path.display());
+        return exit_codes::RUNTIME_ERROR;
+    };
+    let interrupted = crate::signals::interrupted_flag();
+    let mut stdout = std::io::stdout();
+    for (delay, (_, data)) in replay_delays(&events, speed, MAX_REPLAY_IDLE_SECS)
+        .into_iter()
+        .zip(&events)
+    {
+        std::thread::sleep(std::time::Duration::from_secs_f64(delay));
+        if interrupted.load(std::sync::atomic::Ordering::Relaxed) {
+            break;
+        }
+        let _ = stdout.write_all(data.as_bytes());
+        let _ = stdout.flush();
+    }
+    exit_codes::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header() -> CastHeader {
+        CastHeader {
+            width: 120,
+            height: 40,
+            timestamp: 1700000000,
+            title: "bash".to_string(),
+            workspace: "/home/u/proj".to_string(),
+        }
+    }
+
+    // --- asciicast round trip ---
+
+    #[test]
+    fn cast_round_trip() {
+        let content = format!(
+            "{}\n{}\n{}\n",
+            format_header(&header()),
+            format_event(0.5, "$ ls\r\n"),
+            format_event(1.25, "caf\u{e9}\r\n"),
+        );
+        let (h, events) = parse_cast(&content).unwrap();
+        assert_eq!(h, header());
+        assert_eq!(
+            events,
+            vec![
+                (0.5, "$ ls\r\n".to_string()),
+                (1.25, "caf\u{e9}\r\n".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn header_is_asciicast_v2() {
+        let v: serde_json::Value = serde_json::from_str(&format_header(&header())).unwrap();
+        assert_eq!(v["version"], 2);
+        assert_eq!(v["width"], 120);
+        assert_eq!(v["height"], 40);
+    }
+
+    #[test]
+    fn parse_cast_rejects_other_versions_and_skips_bad_events() {
+        assert!(parse_cast("{\"version\":1}\n").is_none());
+        assert!(parse_cast("").is_none());
+        let content = "{\"version\":2}\nnot json\n[1.0,\"i\",\"x\"]\n[2.0,\"o\",\"y\"]\n";
+        let (_, events) = parse_cast(content).unwrap();
+        assert_eq!(events, vec![(2.0, "y".to_string())]);
+    }
+
+    // --- complete_utf8_len ---
+
+    #[test]
+    fn complete_utf8_len_holds_back_partial_character() {
+        let bytes = "a\u{e9}".as_bytes(); // 'a', 0xC3, 0xA9
+        assert_eq!(complete_utf8_len(bytes), 3);
+        assert_eq!(complete_utf8_len(&bytes[..2]), 1);
+        assert_eq!(complete_utf8_len(&[0xff, b'a']), 2);
+    }
+
+    // --- replay_delays ---
+
+    #[test]
+    fn replay_delays_scale_and_cap_idle() {
+        let events = vec![
+            (0.5, "a".to_string()),
+            (1.5, "b".to_string()),
+            (30.0, "c".to_string()),
+        ];
+        assert_eq!(replay_delays(&events, 1.0, 2.0), vec![0.5, 1.0, 2.0]);
+        assert_eq!(replay_delays(&events, 2.0, 2.0), vec![0.25, 0.5, 1.0]);
+    }
+
+    // --- shell_quote / script_args ---
+
+    #[test]
+    fn shell_quote_escapes_only_when_needed() {
+        assert_eq!(shell_quote("docker"), "docker");
+        assert_eq!(shell_quote("FOO=bar"), "FOO=bar");
+        assert_eq!(shell_quote("a b"), "'a b'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[test]
+    fn script_args_wrap_docker_command() {
+        let args = script_args("docker", &["exec".to_string(), "a b".to_string()]);
+        assert_eq!(args[0], "-q");
+        #[cfg(target_os = "linux")]
+        assert_eq!(
+            args,
+            vec!["-q", "-e", "-c", "docker exec 'a b'", "/dev/null"]
+        );
+        #[cfg(target_os = "macos")]
+        assert_eq!(args, vec!["-q", "/dev/null", "docker", "exec", "a b"]);
+    }
+
+    #[test]
+    fn transcripts_are_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("1700000000-00ab.cast");
+        open_transcript(&path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o600);
+    }
+
+    // --- discovery ---
+
+    #[test]
+    fn find_transcript_searches_all_workspaces() {
+        let home = tempfile::tempdir().unwrap();
+        let dir = sessions_dir(home.path(), "dcx-proj-abcd1234");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("1700000000-00ab.cast"), "{}\n").unwrap();
+        std::fs::write(dir.join("notes.txt"), "").unwrap();
+        assert_eq!(all_transcripts(home.path()).len(), 1);
+        assert_eq!(
+            find_transcript(home.path(), "1700000000-00ab"),
+            Some(dir.join("1700000000-00ab.cast"))
+        );
+        assert_eq!(find_transcript(home.path(), "missing"), None);
+    }
+}
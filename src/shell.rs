@@ -0,0 +1,219 @@
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use crate::cmd;
+use crate::dcx_config;
+use crate::docker;
+use crate::exec::{self, ExecFlags};
+use crate::exit_codes;
+use crate::lifetime;
+use crate::mount_table;
+use crate::naming::{is_dcx_managed_path, mount_name, relay_dir};
+use crate::platform;
+use crate::progress;
+use crate::up::{self, find_dcx_config_path, parse_remote_user};
+use crate::workspace::find_devcontainer_config;
+
+/// Shell used when neither `up.shell` nor `/etc/passwd` names one.
+pub const FALLBACK_SHELL: &str = "/bin/sh";
+
+// ── Pure functions ────────────────────────────────────────────────────────────
+
+/// Find the login shell for `user` in `/etc/passwd` content.
+///
+/// Returns `None` if the user has no entry or the shell field is empty or a
+/// no-login placeholder (`nologin`, `false`).
+pub fn shell_from_passwd(passwd: &str, user: &str) -> Option<String> {
+    passwd
+        .lines()
+        .map(|l| l.split(':').collect::<Vec<_>>())
+        .find(|fields| fields.len() >= 7 && fields[0] == user)
+        .map(|fields| fields[6].trim().to_string())
+        .filter(|shell| {
+            !shell.is_empty() && !shell.ends_with("/nologin") && !shell.ends_with("/false")
+        })
+}
+
+/// Pick the shell: `up.shell` wins, then the passwd entry, then `/bin/sh`.
+pub fn choose_shell(configured: Option<&str>, detected: Option<String>) -> String {
+    configured
+        .map(str::to_string)
+        .or(detected)
+        .unwrap_or_else(|| FALLBACK_SHELL.to_string())
+}
+
+// ── I/O helpers ───────────────────────────────────────────────────────────────
+
+/// Detect the login shell of `user` (or the container's default user) from the
+/// container's `/etc/passwd`.
+fn detect_shell(container_id: &str, user: Option<&str>) -> Option<String> {
+    let user = match user {
+        Some(u) => u.to_string(),
+        None => {
+            let out = cmd::run_capture("docker", &["exec", container_id, "id", "-un"]).ok()?;
+            out.stdout.trim().to_string()
+        }
+    };
+    let out = cmd::run_capture("docker", &["exec", container_id, "cat", "/etc/passwd"]).ok()?;
+    if out.status != 0 {
+        return None;
+    }
+    shell_from_passwd(&out.stdout, &user)
+}
+
+// ── Entry point ───────────────────────────────────────────────────────────────
+
+/// Run `dcx shell`.
+///
+/// Brings the workspace up if needed (replaying the options of the last successful
+/// `dcx up`), then opens the user's shell in the container via `dcx exec`.
+/// Returns the exit code that `main` should pass to `std::process::exit`.
+pub fn run_shell(home: &Path, workspace_folder: Option<PathBuf>) -> i32 {
+    // 1. Validate Docker/Colima is available.
+    if !docker::is_docker_available() {
+        eprintln!("Docker is not available. Is Colima running?");
+        return exit_codes::RUNTIME_ERROR;
+    }
+
+    // 2. Resolve workspace path to absolute canonical path, walking up from cwd like
+    // `dcx exec`.
+    let cwd = std::env::current_dir()
+        .ok()
+        .and_then(|d| d.canonicalize().ok());
+    let workspace =
+        match exec::resolve_exec_workspace(home, workspace_folder.as_deref(), cwd.as_deref()) {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("{e}");
+                return exit_codes::USAGE_ERROR;
+            }
+        };
+
+    // 3. Recursive mount guard — block nested dcx mounts.
+    let relay = relay_dir(home);
+    if is_dcx_managed_path(&workspace, &relay) {
+        eprintln!(
+            "Cannot use a dcx-managed mount point as a workspace. \
+             Use the original workspace path instead."
+        );
+        return exit_codes::USAGE_ERROR;
+    }
+
+    let name = mount_name(&workspace);
+    let mount_point = relay.join(&name);
+    let remembered = up::load_remembered_options(home, &name).unwrap_or_default();
+    let config_dir = remembered.config_dir.clone();
+
+    // 4. Bring the workspace up if the mount or running container is missing.
+    // A stopped container is started through `devcontainer up` rather than `docker start`
+    // so postStartCommand (including the network firewall) runs again.
+    let table = platform::read_mount_table().unwrap_or_default();
+    let is_mounted =
+        mount_table::find_mount_source(&table, &mount_point).is_some() && mount_point.exists();
+    let running = is_mounted && docker::find_devcontainer_by_workspace(&mount_point).is_some();
+    if !running {
+        if is_mounted && !docker::query_container_any(&mount_point).is_empty() {
+            progress::step("Starting stopped container...");
+        } else {
+            progress::step("Workspace is not up; running dcx up...");
+        }
+        let mut up_opts = remembered.into_up_options(workspace.clone());
+        // Replay --ttl only when no deadline is left to keep (e.g. after `dcx down`).
+        if lifetime::load(home, &name).is_some_and(|l| l.deadline.is_some()) {
+            up_opts.ttl = None;
+        }
+        let code = up::run_up(home, up_opts);
+        if code != exit_codes::SUCCESS {
+            return code;
+        }
+    }
+
+    let Some(container_id) = docker::find_devcontainer_by_workspace(&mount_point) else {
+        eprintln!("No running devcontainer found for this workspace. Run `dcx up` first.");
+        return exit_codes::RUNTIME_ERROR;
+    };
+
+    // 5. Pick the shell: up.shell from dcx_config.yaml, else remoteUser's passwd entry.
+    let configured = find_dcx_config_path(&workspace, config_dir.as_deref())
+        .map(|p| dcx_config::read_dcx_config(&p).up.shell)
+        .unwrap_or_default();
+    let shell = match configured {
+        Some(s) => s,
+        None => {
+            let remote_user = config_dir
+                .as_ref()
+                .map(|d| d.join("devcontainer.json"))
+                .or_else(|| find_devcontainer_config(&workspace))
+                .and_then(|p| std::fs::read_to_string(p).ok())
+                .and_then(|json| parse_remote_user(&json));
+            choose_shell(None, detect_shell(&container_id, remote_user.as_deref()))
+        }
+    };
+    progress::step(&format!("Opening shell: {shell}"));
+
+    // 6. Delegate to `dcx exec` (cwd mapping, remoteUser, remoteEnv all apply).
+    exec::run_exec(
+        home,
+        Some(workspace),
+        config_dir,
+        ExecFlags::default(),
+        vec![shell],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSWD: &str = "root:x:0:0:root:/root:/bin/bash\n\
+                          daemon:x:1:1:daemon:/usr/sbin:/usr/sbin/nologin\n\
+                          rust:x:1000:1000::/home/rust:/bin/zsh\n\
+                          empty:x:1001:1001::/home/empty:\n";
+
+    // --- shell_from_passwd ---
+
+    #[test]
+    fn shell_from_passwd_finds_user_shell() {
+        assert_eq!(
+            shell_from_passwd(PASSWD, "rust"),
+            Some("/bin/zsh".to_string())
+        );
+        assert_eq!(
+            shell_from_passwd(PASSWD, "root"),
+            Some("/bin/bash".to_string())
+        );
+    }
+
+    #[test]
+    fn shell_from_passwd_ignores_nologin_and_empty() {
+        assert_eq!(shell_from_passwd(PASSWD, "daemon"), None);
+        assert_eq!(shell_from_passwd(PASSWD, "empty"), None);
+    }
+
+    #[test]
+    fn shell_from_passwd_unknown_user_is_none() {
+        assert_eq!(shell_from_passwd(PASSWD, "nobody"), None);
+    }
+
+    #[test]
+    fn shell_from_passwd_does_not_match_prefix() {
+        assert_eq!(shell_from_passwd(PASSWD, "rus"), None);
+    }
+
+    // --- choose_shell ---
+
+    #[test]
+    fn choose_shell_prefers_configured() {
+        assert_eq!(
+            choose_shell(Some("/bin/fish"), Some("/bin/zsh".to_string())),
+            "/bin/fish"
+        );
+    }
+
+    #[test]
+    fn choose_shell_uses_detected_then_fallback() {
+        assert_eq!(choose_shell(None, Some("/bin/zsh".to_string())), "/bin/zsh");
+        assert_eq!(choose_shell(None, None), FALLBACK_SHELL);
+    }
+}
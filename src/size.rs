@@ -0,0 +1,94 @@
+#![allow(dead_code)]
+
+// ── Parsing ───────────────────────────────────────────────────────────────────
+
+/// Parse a size as printed by Docker (`0B`, `12.3kB`, `1.21GB`, `512MiB`) into bytes.
+///
+/// Decimal units (`kB`, `MB`, `GB`, `TB`) scale by 1000, binary units (`KiB`, `MiB`, `GiB`,
+/// `TiB`) by 1024. Unit case is ignored; a bare number is bytes. Returns `None` if invalid.
+pub fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (num, unit) = value.split_at(split);
+    let n: f64 = num.parse().ok()?;
+    let scale: f64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1.0,
+        "k" | "kb" => 1e3,
+        "m" | "mb" => 1e6,
+        "g" | "gb" => 1e9,
+        "t" | "tb" => 1e12,
+        "kib" => 1024.0,
+        "mib" => 1024.0 * 1024.0,
+        "gib" => 1024.0 * 1024.0 * 1024.0,
+        "tib" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((n * scale).round() as u64)
+}
+
+// ── Formatting ────────────────────────────────────────────────────────────────
+
+/// Format bytes with decimal units, the way Docker prints sizes: `512B`, `12.3kB`, `1.21GB`.
+///
+/// Keeps three significant digits.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "kB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes}B")
+    } else if value < 10.0 {
+        format!("{value:.2}{}", UNITS[unit])
+    } else if value < 100.0 {
+        format!("{value:.1}{}", UNITS[unit])
+    } else {
+        format!("{value:.0}{}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_size_decimal_and_binary_units() {
+        assert_eq!(parse_size("0B"), Some(0));
+        assert_eq!(parse_size("12.3kB"), Some(12_300));
+        assert_eq!(parse_size("1.21GB"), Some(1_210_000_000));
+        assert_eq!(parse_size("512MiB"), Some(512 * 1024 * 1024));
+        assert_eq!(parse_size("2KiB"), Some(2048));
+        assert_eq!(parse_size(" 42 "), Some(42));
+    }
+
+    #[test]
+    fn parse_size_rejects_garbage() {
+        assert_eq!(parse_size(""), None);
+        assert_eq!(parse_size("N/A"), None);
+        assert_eq!(parse_size("12XB"), None);
+    }
+
+    #[test]
+    fn format_size_keeps_three_significant_digits() {
+        assert_eq!(format_size(0), "0B");
+        assert_eq!(format_size(999), "999B");
+        assert_eq!(format_size(12_300), "12.3kB");
+        assert_eq!(format_size(1_210_000_000), "1.21GB");
+        assert_eq!(format_size(540_000_000), "540MB");
+    }
+
+    #[test]
+    fn format_size_round_trips_through_parse_size() {
+        for bytes in [0, 1_500, 73_400_000, 4_200_000_000] {
+            let formatted = format_size(bytes);
+            let parsed = parse_size(&formatted).unwrap();
+            let diff = parsed.abs_diff(bytes) as f64;
+            assert!(diff / (bytes.max(1) as f64) < 0.01, "{bytes} → {formatted}");
+        }
+    }
+}
@@ -0,0 +1,428 @@
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use crate::network_mode::{NetworkMode, NetworkPhase};
+use crate::sync::{MergeFormat, SyncPair};
+
+// ── Paths ─────────────────────────────────────────────────────────────────────
+
+/// Root of dcx's persistent per-user state: `~/.local/state/dcx`.
+///
+/// Unlike the staging dir next to the relay mount, this survives `dcx down` and
+/// rollback, so it can hold history that outlives a single container.
+pub fn state_root(home: &Path) -> PathBuf {
+    home.join(".local").join("state").join("dcx")
+}
+
+/// Per-workspace state directory: `~/.local/state/dcx/<mount_name>`.
+pub fn state_dir(home: &Path, mount_name: &str) -> PathBuf {
+    state_root(home).join(mount_name)
+}
+
+/// File recording network phase transitions for a workspace.
+pub fn network_phase_file(home: &Path, mount_name: &str) -> PathBuf {
+    state_dir(home, mount_name).join("network-phase")
+}
+
+/// Start time (Unix timestamp) of the container run whose firewall dcx last set to the
+/// run-phase network mode. A different start time means the container was restarted.
+pub fn network_applied_file(home: &Path, mount_name: &str) -> PathBuf {
+    state_dir(home, mount_name).join("network-applied")
+}
+
+/// Sync daemon log for a workspace (rotated to `sync-daemon.log.1`).
+pub fn daemon_log_file(home: &Path, mount_name: &str) -> PathBuf {
+    state_dir(home, mount_name).join("sync-daemon.log")
+}
+
+/// Directory where the dcx daemon keeps each synced file's conflict/merge base.
+pub fn sync_base_dir(home: &Path, mount_name: &str) -> PathBuf {
+    state_dir(home, mount_name).join("sync-base")
+}
+
+/// dcx lifecycle events for a workspace (up, down, network phase changes).
+pub fn events_file(home: &Path, mount_name: &str) -> PathBuf {
+    state_dir(home, mount_name).join("events.log")
+}
+
+/// Unix timestamp of the last `dcx up` or `dcx exec` (incl. `shell`/`run`) in a workspace,
+/// or of container activity seen by the workspace supervisor.
+pub fn last_active_file(home: &Path, mount_name: &str) -> PathBuf {
+    state_dir(home, mount_name).join("last-active")
+}
+
+/// One file per running `dcx exec`/`dcx shell` process, named after its PID.
+pub fn live_sessions_dir(home: &Path, mount_name: &str) -> PathBuf {
+    state_dir(home, mount_name).join("live-sessions")
+}
+
+/// Sync pairs the workspace's sync daemon was started with, one `<source>\t<staging>` per line
+/// (plus `\t<merge format>` for `up.files[].merge`).
+///
+/// Lets `dcx start` restart the daemon without re-reading the `up.files` configuration.
+pub fn sync_pairs_file(home: &Path, mount_name: &str) -> PathBuf {
+    state_dir(home, mount_name).join("sync-pairs")
+}
+
+// ── Time ──────────────────────────────────────────────────────────────────────
+
+/// Current time as seconds since the Unix epoch (0 if the clock is before 1970).
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// ── Activity ──────────────────────────────────────────────────────────────────
+
+/// Record that the workspace was just used. Best effort, like `record_event`.
+pub fn touch_active(home: &Path, mount_name: &str) {
+    let path = last_active_file(home, mount_name);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let _ = std::fs::write(&path, format!("{}\n", unix_now()));
+}
+
+/// When the workspace was last used via `dcx up`/`dcx exec`, if recorded.
+pub fn last_active(home: &Path, mount_name: &str) -> Option<u64> {
+    std::fs::read_to_string(last_active_file(home, mount_name))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Register the current process as a live exec session. Best effort; the entry is left
+/// behind when the process exits and ignored (then removed) by `has_live_session`.
+///
+/// The entry (`live-sessions/<pid>`) holds the process start time, so a PID reused by
+/// another process does not keep the workspace active.
+pub fn register_session(home: &Path, mount_name: &str) {
+    let pid = std::process::id();
+    let Some(started) = crate::platform::process_start_time(pid) else {
+        return;
+    };
+    let dir = live_sessions_dir(home, mount_name);
+    let _ = std::fs::create_dir_all(&dir);
+    let _ = std::fs::write(dir.join(pid.to_string()), format!("{started}\n"));
+}
+
+/// Whether any registered exec session process is still alive. Dead entries are removed.
+pub fn has_live_session(home: &Path, mount_name: &str) -> bool {
+    let Ok(entries) = std::fs::read_dir(live_sessions_dir(home, mount_name)) else {
+        return false;
+    };
+    let mut live = false;
+    for entry in entries.flatten() {
+        let pid = entry
+            .file_name()
+            .to_str()
+            .and_then(|p| p.parse::<u32>().ok());
+        let started = std::fs::read_to_string(entry.path())
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok());
+        let alive = match (pid, started) {
+            (Some(pid), Some(started)) => crate::platform::process_start_time(pid) == Some(started),
+            _ => false,
+        };
+        if alive {
+            live = true;
+        } else {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+    live
+}
+
+// ── Sync pairs ────────────────────────────────────────────────────────────────
+
+/// Record the sync daemon's pairs. Best effort, like `record_event`.
+pub fn save_sync_pairs(home: &Path, mount_name: &str, pairs: &[SyncPair]) {
+    let path = sync_pairs_file(home, mount_name);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let content: String = pairs
+        .iter()
+        .map(|p| match p.merge {
+            Some(format) => format!(
+                "{}\t{}\t{format}\n",
+                p.source.display(),
+                p.staging.display()
+            ),
+            None => format!("{}\t{}\n", p.source.display(), p.staging.display()),
+        })
+        .collect();
+    let _ = std::fs::write(&path, content);
+}
+
+/// Sync pairs recorded by the last `dcx up` (empty if none or unreadable).
+pub fn load_sync_pairs(home: &Path, mount_name: &str) -> Vec<SyncPair> {
+    std::fs::read_to_string(sync_pairs_file(home, mount_name))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let (source, staging) = (fields.next()?, fields.next()?);
+            Some(SyncPair {
+                source: PathBuf::from(source),
+                staging: PathBuf::from(staging),
+                merge: fields.next().and_then(|f| MergeFormat::parse(f).ok()),
+            })
+        })
+        .collect()
+}
+
+// ── Network phase transitions ─────────────────────────────────────────────────
+
+/// A recorded network phase transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhaseTransition {
+    pub timestamp: u64,
+    pub phase: NetworkPhase,
+    pub mode: NetworkMode,
+}
+
+/// Format a transition as a single line: `<unix_ts> <phase> <mode>`.
+pub fn format_transition(t: &PhaseTransition) -> String {
+    format!("{} {} {}", t.timestamp, t.phase, t.mode)
+}
+
+/// Parse a line written by `format_transition`. Returns `None` for malformed lines.
+pub fn parse_transition(line: &str) -> Option<PhaseTransition> {
+    let mut parts = line.split_whitespace();
+    let timestamp = parts.next()?.parse().ok()?;
+    let phase = parts.next()?.parse().ok()?;
+    let mode = parts.next()?.parse().ok()?;
+    Some(PhaseTransition {
+        timestamp,
+        phase,
+        mode,
+    })
+}
+
+/// Append a transition to the workspace's network-phase file.
+pub fn record_transition(home: &Path, mount_name: &str, t: &PhaseTransition) -> Result<(), String> {
+    use std::io::Write;
+
+    let path = network_phase_file(home, mount_name);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    let mut f = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+    writeln!(f, "{}", format_transition(t))
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Clear recorded transitions (called when a new container is created).
+pub fn clear_transitions(home: &Path, mount_name: &str) {
+    let _ = std::fs::remove_file(network_phase_file(home, mount_name));
+}
+
+/// Most recent recorded transition for a workspace, if any.
+pub fn last_transition(home: &Path, mount_name: &str) -> Option<PhaseTransition> {
+    let content = std::fs::read_to_string(network_phase_file(home, mount_name)).ok()?;
+    content.lines().rev().find_map(parse_transition)
+}
+
+/// Record that the run-phase network mode is in force for the container run started at
+/// `started_at`. Best effort, like `record_event`.
+pub fn record_network_applied(home: &Path, mount_name: &str, started_at: u64) {
+    let path = network_applied_file(home, mount_name);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let _ = std::fs::write(&path, format!("{started_at}\n"));
+}
+
+/// Container start time recorded by `record_network_applied`, if any.
+pub fn network_applied(home: &Path, mount_name: &str) -> Option<u64> {
+    std::fs::read_to_string(network_applied_file(home, mount_name))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+// ── Lifecycle events ──────────────────────────────────────────────────────────
+
+/// Append a lifecycle event (`<RFC 3339 UTC> <message>`) to the workspace's events log.
+///
+/// Best effort: events are informational, so write failures are ignored.
+pub fn record_event(home: &Path, mount_name: &str, message: &str) {
+    use std::io::Write;
+
+    let path = events_file(home, mount_name);
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Ok(mut f) = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+    {
+        let _ = writeln!(
+            f,
+            "{} {message}",
+            crate::duration::format_timestamp(unix_now())
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_dir_is_under_local_state() {
+        let home = Path::new("/home/user");
+        assert_eq!(
+            state_dir(home, "dcx-proj-abcd1234"),
+            PathBuf::from("/home/user/.local/state/dcx/dcx-proj-abcd1234")
+        );
+    }
+
+    #[test]
+    fn touch_active_records_current_time() {
+        let home = tempfile::tempdir().unwrap();
+        let name = "dcx-proj-abcd1234";
+        assert_eq!(last_active(home.path(), name), None);
+        let before = unix_now();
+        touch_active(home.path(), name);
+        let at = last_active(home.path(), name).unwrap();
+        assert!(at >= before && at <= unix_now());
+    }
+
+    #[test]
+    fn network_applied_round_trips() {
+        let home = tempfile::tempdir().unwrap();
+        let name = "dcx-proj-abcd1234";
+        assert_eq!(network_applied(home.path(), name), None);
+        record_network_applied(home.path(), name, 1_700_000_000);
+        assert_eq!(network_applied(home.path(), name), Some(1_700_000_000));
+    }
+
+    #[test]
+    fn live_session_tracks_running_processes_only() {
+        let home = tempfile::tempdir().unwrap();
+        let name = "dcx-proj-abcd1234";
+        assert!(!has_live_session(home.path(), name));
+        register_session(home.path(), name);
+        assert!(has_live_session(home.path(), name));
+
+        let dead = live_sessions_dir(home.path(), name).join("999999999");
+        std::fs::write(&dead, "1\n").unwrap();
+        assert!(has_live_session(home.path(), name));
+        assert!(!dead.exists(), "dead entries are removed");
+
+        // Our PID with another start time: the PID was reused by a different process.
+        let ours = live_sessions_dir(home.path(), name).join(std::process::id().to_string());
+        std::fs::write(&ours, "1\n").unwrap();
+        assert!(!has_live_session(home.path(), name));
+        assert!(!ours.exists());
+    }
+
+    #[test]
+    fn sync_pairs_round_trip() {
+        let home = tempfile::tempdir().unwrap();
+        let name = "dcx-proj-abcd1234";
+        assert!(load_sync_pairs(home.path(), name).is_empty());
+        let pairs = vec![
+            SyncPair {
+                source: PathBuf::from("/home/u/.claude.json"),
+                staging: PathBuf::from("/relay/.dcx-proj-abcd1234-files/.claude.json"),
+                merge: None,
+            },
+            SyncPair {
+                source: PathBuf::from("/home/u/my file"),
+                staging: PathBuf::from("/relay/.dcx-proj-abcd1234-files/my file"),
+                merge: Some(MergeFormat::Json),
+            },
+        ];
+        save_sync_pairs(home.path(), name, &pairs);
+        assert_eq!(load_sync_pairs(home.path(), name), pairs);
+    }
+
+    #[test]
+    fn transition_round_trip() {
+        let t = PhaseTransition {
+            timestamp: 1700000000,
+            phase: NetworkPhase::Run,
+            mode: NetworkMode::Restricted,
+        };
+        assert_eq!(format_transition(&t), "1700000000 run restricted");
+        assert_eq!(parse_transition(&format_transition(&t)), Some(t));
+    }
+
+    #[test]
+    fn parse_transition_rejects_malformed_line() {
+        assert_eq!(parse_transition("not a transition"), None);
+        assert_eq!(parse_transition("123 run"), None);
+    }
+
+    #[test]
+    fn last_transition_returns_most_recent() {
+        let home = tempfile::tempdir().unwrap();
+        let name = "dcx-proj-abcd1234";
+        for (ts, phase, mode) in [
+            (1, NetworkPhase::Create, NetworkMode::Open),
+            (2, NetworkPhase::Run, NetworkMode::Restricted),
+        ] {
+            record_transition(
+                home.path(),
+                name,
+                &PhaseTransition {
+                    timestamp: ts,
+                    phase,
+                    mode,
+                },
+            )
+            .unwrap();
+        }
+        let last = last_transition(home.path(), name).unwrap();
+        assert_eq!(last.phase, NetworkPhase::Run);
+        assert_eq!(last.mode, NetworkMode::Restricted);
+    }
+
+    #[test]
+    fn clear_transitions_removes_history() {
+        let home = tempfile::tempdir().unwrap();
+        let name = "dcx-proj-abcd1234";
+        record_transition(
+            home.path(),
+            name,
+            &PhaseTransition {
+                timestamp: 1,
+                phase: NetworkPhase::Create,
+                mode: NetworkMode::Open,
+            },
+        )
+        .unwrap();
+        clear_transitions(home.path(), name);
+        assert_eq!(last_transition(home.path(), name), None);
+    }
+
+    #[test]
+    fn record_event_appends_timestamped_lines() {
+        let home = tempfile::tempdir().unwrap();
+        let name = "dcx-proj-abcd1234";
+        record_event(home.path(), name, "up: done");
+        record_event(home.path(), name, "down: done");
+        let content = std::fs::read_to_string(events_file(home.path(), name)).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("Z up: done"), "got: {content}");
+        assert!(
+            crate::duration::parse_timestamp(lines[1].split(' ').next().unwrap()).is_some(),
+            "got: {content}"
+        );
+    }
+}
@@ -4,26 +4,46 @@ use std::path::Path;
 use std::process::Command;
 
 use crate::categorize::{MountStatus, categorize};
+use crate::daemon;
 use crate::docker;
+use crate::drift;
+use crate::duration::format_age;
 use crate::exit_codes;
-use crate::format::{StatusRow, format_status_table};
+use crate::format::{
+    ContainerResources, DiskUsage, StatusRow, format_status_resources_table, format_status_table,
+};
+use crate::lifetime;
 use crate::mount_table;
 use crate::naming::{relay_dir, scan_relay};
+use crate::network_mode::{self, NetworkPhase, NetworkPolicy};
+use crate::output;
 use crate::platform;
 use crate::progress;
+use crate::size::parse_size;
+use crate::state::{self, PhaseTransition};
+use crate::sync;
 use crate::up::staging_dir;
 
-/// Check if sync daemon is running for a given mount point.
+/// File sync status for a given mount point.
 ///
-/// Returns `"running"` if the daemon PID file exists and the process is alive.
-/// Returns `"stopped"` if the PID file doesn't exist or the process is dead.
+/// Returns `"conflict"` if a synced file has an unresolved conflict.
+/// Returns `"running"` if the dcx daemon is syncing the workspace (`syncing`), or a
+/// per-workspace sync daemon started by an older dcx is still alive (PID file in staging).
+/// Returns `"stopped"` otherwise.
 /// Returns `"–"` if not applicable (mount not active).
-fn daemon_status(mount_point: &Path, is_mounted: bool) -> String {
+pub fn daemon_status(mount_point: &Path, is_mounted: bool, syncing: bool) -> String {
     if !is_mounted {
         return "–".to_string();
     }
 
     let staging = staging_dir(mount_point);
+    // Unresolved conflicts pause syncing for those files; surface them over "running".
+    if !sync::list_conflicts(&staging).is_empty() {
+        return "conflict".to_string();
+    }
+    if syncing {
+        return "running".to_string();
+    }
     let pid_file = staging.join(".sync-daemon.pid");
 
     // Try to read the PID file
@@ -46,28 +66,106 @@ fn daemon_status(mount_point: &Path, is_mounted: bool) -> String {
     }
 }
 
+/// NETWORK column value for a container.
+///
+/// Uniform policies show just the mode label. Phased policies (containers carrying a
+/// `dcx.network-policy` label) show the mode of the current phase, e.g. `restricted (run)`,
+/// taken from the last recorded transition; with no record, the container is still in
+/// its create phase.
+pub fn network_label(
+    mode_label: Option<String>,
+    policy: Option<NetworkPolicy>,
+    last: Option<PhaseTransition>,
+) -> Option<String> {
+    let Some(policy) = policy else {
+        return mode_label;
+    };
+    let (phase, mode) = match last {
+        Some(t) => (t.phase, t.mode),
+        None => (NetworkPhase::Create, policy.create),
+    };
+    Some(format!("{mode} ({phase})"))
+}
+
 /// Human-readable state label for a dcx mount entry.
 ///
 /// `is_mounted` should be `is_fuse_mounted && is_accessible` (the caller's
 /// combined health check). Delegates to `categorize` so label and categorization
 /// logic cannot drift apart.
 ///
-/// - Mounted and has a container → `"running"`
-/// - Mounted but no container    → `"orphaned"`
-/// - Not mounted                 → `"stale mount"`
-pub fn mount_state_label(is_mounted: bool, has_container: bool) -> &'static str {
-    match categorize(is_mounted, is_mounted, has_container) {
+/// - Mounted and has a running container → `"running"`
+/// - Mounted with a stopped container     → `"stopped"`
+/// - Mounted but no container             → `"orphaned"`
+/// - Not mounted                          → `"stale mount"`
+pub fn mount_state_label(
+    is_mounted: bool,
+    has_container: bool,
+    has_stopped_container: bool,
+) -> &'static str {
+    match categorize(is_mounted, is_mounted, has_container, has_stopped_container) {
         MountStatus::Active => "running",
+        MountStatus::Stopped => "stopped",
         MountStatus::Orphaned => "orphaned",
         MountStatus::Stale | MountStatus::Empty => "stale mount",
     }
 }
 
+/// Resource usage for `container`, combining its `docker stats` sample and writable layer size.
+///
+/// Returns None when Docker reported neither.
+pub fn container_resources(
+    container: &str,
+    stats: &[docker::ContainerStats],
+    sizes: &[(String, String)],
+) -> Option<ContainerResources> {
+    let same = |id: &str| id.starts_with(container) || container.starts_with(id);
+    let sample = stats.iter().find(|s| same(&s.id));
+    let writable = sizes.iter().find(|(id, _)| same(id)).map(|(_, size)| size);
+    if sample.is_none() && writable.is_none() {
+        return None;
+    }
+    let field = |f: fn(&docker::ContainerStats) -> &String| {
+        sample.map(f).cloned().unwrap_or_else(|| "–".to_string())
+    };
+    Some(ContainerResources {
+        cpu_percent: field(|s| &s.cpu_percent),
+        memory: field(|s| &s.memory),
+        pids: field(|s| &s.pids),
+        net_io: field(|s| &s.net_io),
+        writable_size: writable.cloned().unwrap_or_else(|| "–".to_string()),
+    })
+}
+
+/// Total disk used by dcx-owned images and volumes.
+///
+/// Images are counted once per image ID, since `dcx-base:*` tags alias other tags.
+/// Sizes Docker prints in a form `parse_size` rejects are skipped.
+pub fn dcx_disk_usage(images: &[docker::ImageInfo], volumes: &[(String, String)]) -> DiskUsage {
+    let mut seen: Vec<&str> = Vec::new();
+    let mut usage = DiskUsage::default();
+    for image in images.iter().filter(|i| docker::is_dcx_image(&i.reference)) {
+        if seen.contains(&image.id.as_str()) {
+            continue;
+        }
+        seen.push(&image.id);
+        usage.images += 1;
+        usage.image_bytes += parse_size(&image.size).unwrap_or(0);
+    }
+    for (_, size) in volumes.iter().filter(|(name, _)| name.starts_with("dcx-")) {
+        usage.volumes += 1;
+        usage.volume_bytes += parse_size(size).unwrap_or(0);
+    }
+    usage
+}
+
 /// Scan all dcx-managed mounts, query their state, and print the status table.
 ///
+/// With `resources`, also samples each container's CPU, memory, PIDs, network I/O and
+/// writable layer size, and prints the dcx image/volume disk usage footer.
+///
 /// Returns `exit_codes::SUCCESS` (0) on success, `exit_codes::RUNTIME_ERROR` (1) if Docker
 /// is not available.
-pub fn run_status(home: &Path) -> i32 {
+pub fn run_status(home: &Path, resources: bool) -> i32 {
     if !docker::is_docker_available() {
         eprintln!("Docker is not available. Is Colima running?");
         return exit_codes::RUNTIME_ERROR;
@@ -77,14 +175,19 @@ pub fn run_status(home: &Path) -> i32 {
     let relay = relay_dir(home);
     let mounts = scan_relay(&relay);
 
-    if mounts.is_empty() {
-        println!("No active workspaces.");
+    if mounts.is_empty() && !resources {
+        if output::is_structured() {
+            output::emit(&serde_json::json!({ "workspaces": [] }));
+        } else {
+            println!("No active workspaces.");
+        }
         return exit_codes::SUCCESS;
     }
 
     let mount_table = platform::read_mount_table().unwrap_or_default();
+    let daemon_health = daemon::health(home);
 
-    let rows: Vec<StatusRow> = mounts
+    let mut rows: Vec<StatusRow> = mounts
         .iter()
         .map(|mount_point| {
             let workspace =
@@ -93,17 +196,41 @@ pub fn run_status(home: &Path) -> i32 {
             let is_accessible = mount_point.metadata().is_ok();
             let container = docker::query_container(mount_point);
             let has_container = container.is_some();
-            let state = mount_state_label(is_mounted && is_accessible, has_container);
+            let has_stopped =
+                !has_container && !docker::query_container_any(mount_point).is_empty();
+            let mut state =
+                mount_state_label(is_mounted && is_accessible, has_container, has_stopped);
+            // A running container created from a since-changed configuration is outdated.
+            if let (Some(c), Some(ws)) = (&container, &workspace)
+                && !drift::container_drift(home, c, mount_point, Path::new(ws)).is_empty()
+            {
+                state = "outdated";
+            }
             let mount = mount_point
                 .file_name()
                 .map(|n| n.to_string_lossy().into_owned())
                 .unwrap_or_default();
-            // Read network mode from container if it exists
-            let network = container
-                .as_ref()
-                .and_then(|c| docker::read_network_mode(c));
-            // Check sync daemon status
-            let daemon = daemon_status(mount_point, is_mounted && is_accessible);
+            // Read network mode (and phased policy) from container if it exists
+            let network = container.as_ref().and_then(|c| {
+                let policy = docker::read_label(c, network_mode::POLICY_LABEL)
+                    .and_then(|l| NetworkPolicy::parse_label(&l));
+                network_label(
+                    docker::read_network_mode(c),
+                    policy,
+                    state::last_transition(home, &mount),
+                )
+            });
+            // Check file sync status
+            let syncing = daemon_health.as_ref().is_some_and(|h| h.is_syncing(&mount));
+            let daemon = daemon_status(mount_point, is_mounted && is_accessible, syncing);
+            let daemon_error = sync::last_error(&state::daemon_log_file(home, &mount)).map(|e| {
+                format!(
+                    "{}: {}",
+                    format_age(state::unix_now().saturating_sub(e.timestamp)),
+                    e.message
+                )
+            });
+            let expires_in = lifetime::remaining_for(home, &mount);
             StatusRow {
                 workspace,
                 mount,
@@ -111,40 +238,131 @@ pub fn run_status(home: &Path) -> i32 {
                 network,
                 state: state.to_string(),
                 daemon,
+                daemon_error,
+                resources: None,
+                expires_in,
             }
         })
         .collect();
 
-    let output = format_status_table(&rows);
-    println!("{output}");
+    if !resources {
+        if output::is_structured() {
+            output::emit(&serde_json::json!({ "workspaces": rows, "daemon": daemon_health }));
+        } else {
+            println!("{}", format_status_table(&rows));
+            if let Some(footer) =
+                daemon::health_footer(daemon_health.as_ref(), daemon::load_registry(home).len())
+            {
+                println!("{footer}");
+            }
+        }
+        return exit_codes::SUCCESS;
+    }
+
+    progress::step("Sampling container resource usage...");
+    let running: Vec<String> = rows.iter().filter_map(|r| r.container.clone()).collect();
+    let stats = docker::container_stats(&running);
+    let sizes = if running.is_empty() {
+        vec![]
+    } else {
+        docker::container_writable_sizes()
+    };
+    for row in &mut rows {
+        row.resources = row
+            .container
+            .as_deref()
+            .and_then(|c| container_resources(c, &stats, &sizes));
+    }
+    let usage = dcx_disk_usage(&docker::list_images(), &docker::volume_sizes());
+    if output::is_structured() {
+        output::emit(&serde_json::json!({
+            "workspaces": rows,
+            "disk_usage": usage,
+            "daemon": daemon_health,
+        }));
+    } else {
+        println!("{}", format_status_resources_table(&rows, &usage));
+        if let Some(footer) =
+            daemon::health_footer(daemon_health.as_ref(), daemon::load_registry(home).len())
+        {
+            println!("{footer}");
+        }
+    }
     exit_codes::SUCCESS
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::network_mode::NetworkMode;
+
+    // --- network_label ---
+
+    #[test]
+    fn network_label_uniform_shows_mode_only() {
+        assert_eq!(
+            network_label(Some("minimal".to_string()), None, None),
+            Some("minimal".to_string())
+        );
+    }
+
+    #[test]
+    fn network_label_phased_shows_current_phase() {
+        let policy = NetworkPolicy {
+            build: NetworkMode::Open,
+            create: NetworkMode::Open,
+            run: NetworkMode::Restricted,
+        };
+        let last = PhaseTransition {
+            timestamp: 1,
+            phase: NetworkPhase::Run,
+            mode: NetworkMode::Restricted,
+        };
+        assert_eq!(
+            network_label(Some("open".to_string()), Some(policy), Some(last)),
+            Some("restricted (run)".to_string())
+        );
+    }
+
+    #[test]
+    fn network_label_phased_without_record_is_create_phase() {
+        let policy = NetworkPolicy {
+            build: NetworkMode::Open,
+            create: NetworkMode::Minimal,
+            run: NetworkMode::Restricted,
+        };
+        assert_eq!(
+            network_label(Some("minimal".to_string()), Some(policy), None),
+            Some("minimal (create)".to_string())
+        );
+    }
 
     // --- mount_state_label ---
 
     #[test]
     fn label_running_when_mounted_with_container() {
-        assert_eq!(mount_state_label(true, true), "running");
+        assert_eq!(mount_state_label(true, true, false), "running");
     }
 
     #[test]
     fn label_orphaned_when_mounted_no_container() {
-        assert_eq!(mount_state_label(true, false), "orphaned");
+        assert_eq!(mount_state_label(true, false, false), "orphaned");
+    }
+
+    #[test]
+    fn label_stopped_when_mounted_with_stopped_container() {
+        assert_eq!(mount_state_label(true, false, true), "stopped");
     }
 
     #[test]
     fn label_stale_when_not_mounted() {
-        assert_eq!(mount_state_label(false, false), "stale mount");
+        assert_eq!(mount_state_label(false, false, false), "stale mount");
     }
 
     #[test]
     fn label_stale_ignores_container_flag() {
         // When not mounted, the has_container flag is irrelevant — always "stale mount".
-        assert_eq!(mount_state_label(false, true), "stale mount");
+        assert_eq!(mount_state_label(false, true, false), "stale mount");
     }
 
     // --- daemon_status ---
@@ -152,7 +370,7 @@ mod tests {
     #[test]
     fn daemon_status_not_mounted_returns_dash() {
         let mount = std::path::Path::new("/some/path");
-        let status = daemon_status(mount, false);
+        let status = daemon_status(mount, false, false);
         assert_eq!(status, "–");
     }
 
@@ -160,7 +378,7 @@ mod tests {
     fn daemon_status_missing_pid_file_returns_stopped() {
         // Create a staging dir without a .sync-daemon.pid file
         let staging_dir = tempfile::TempDir::new().unwrap();
-        let status = daemon_status(staging_dir.path(), true);
+        let status = daemon_status(staging_dir.path(), true, false);
         assert_eq!(status, "stopped");
     }
 
@@ -170,7 +388,7 @@ mod tests {
         let staging_dir = tempfile::TempDir::new().unwrap();
         let pid_file = staging_dir.path().join(".sync-daemon.pid");
         std::fs::write(&pid_file, b"   \n").unwrap();
-        let status = daemon_status(staging_dir.path(), true);
+        let status = daemon_status(staging_dir.path(), true, false);
         assert_eq!(status, "stopped");
     }
 
@@ -180,7 +398,7 @@ mod tests {
         let staging_dir = tempfile::TempDir::new().unwrap();
         let pid_file = staging_dir.path().join(".sync-daemon.pid");
         std::fs::write(&pid_file, b"not-a-pid").unwrap();
-        let status = daemon_status(staging_dir.path(), true);
+        let status = daemon_status(staging_dir.path(), true, false);
         // Since `kill -0 not-a-pid` will fail (not a valid PID),
         // the status should be "stopped"
         assert_eq!(status, "stopped");
@@ -193,7 +411,7 @@ mod tests {
         let pid_file = staging_dir.path().join(".sync-daemon.pid");
         // Use a very high PID that's unlikely to exist
         std::fs::write(&pid_file, b"999999999").unwrap();
-        let status = daemon_status(staging_dir.path(), true);
+        let status = daemon_status(staging_dir.path(), true, false);
         assert_eq!(status, "stopped");
     }
 
@@ -204,8 +422,87 @@ mod tests {
         let pid_file = staging_dir.path().join(".sync-daemon.pid");
         // Write PID with surrounding whitespace (like from file read)
         std::fs::write(&pid_file, b"  999999999  \n").unwrap();
-        let status = daemon_status(staging_dir.path(), true);
+        let status = daemon_status(staging_dir.path(), true, false);
         // PID 999999999 doesn't exist, so it should be "stopped"
         assert_eq!(status, "stopped");
     }
+
+    #[test]
+    fn daemon_status_syncing_in_dcx_daemon_returns_running() {
+        let staging_dir = tempfile::TempDir::new().unwrap();
+        assert_eq!(daemon_status(staging_dir.path(), true, true), "running");
+    }
+
+    #[test]
+    fn daemon_status_with_unresolved_conflict_returns_conflict() {
+        let relay = tempfile::TempDir::new().unwrap();
+        let mount = relay.path().join("dcx-proj-abcd1234");
+        let staging = staging_dir(&mount);
+        std::fs::create_dir_all(&staging).unwrap();
+        let file = staging.join("config.json");
+        std::fs::write(&file, b"{}").unwrap();
+        std::fs::write(
+            sync::conflict_marker(&file),
+            sync::format_conflict_marker(
+                Path::new("/home/u/.config.json"),
+                Path::new("/home/u/.config.json.conflict-20260101T000000Z"),
+            ),
+        )
+        .unwrap();
+        assert_eq!(daemon_status(&mount, true, true), "conflict");
+    }
+
+    // --- container_resources / dcx_disk_usage ---
+
+    fn stats(id: &str) -> docker::ContainerStats {
+        docker::ContainerStats {
+            id: id.to_string(),
+            cpu_percent: "1.50%".to_string(),
+            memory: "100MiB / 2GiB".to_string(),
+            pids: "7".to_string(),
+            net_io: "1kB / 2kB".to_string(),
+        }
+    }
+
+    #[test]
+    fn container_resources_combines_stats_and_writable_size() {
+        let sizes = vec![("abc123def456".to_string(), "12.3kB".to_string())];
+        let r = container_resources("abc123def456", &[stats("abc123def456")], &sizes).unwrap();
+        assert_eq!(r.cpu_percent, "1.50%");
+        assert_eq!(r.memory, "100MiB / 2GiB");
+        assert_eq!(r.writable_size, "12.3kB");
+    }
+
+    #[test]
+    fn container_resources_fills_missing_values_with_dash() {
+        let sizes = vec![("abc123def456".to_string(), "0B".to_string())];
+        let r = container_resources("abc123def456", &[], &sizes).unwrap();
+        assert_eq!(r.cpu_percent, "–");
+        assert_eq!(r.writable_size, "0B");
+        assert_eq!(container_resources("fff", &[stats("abc")], &[]), None);
+    }
+
+    #[test]
+    fn dcx_disk_usage_counts_dcx_images_once_and_dcx_volumes() {
+        let image = |reference: &str, id: &str, size: &str| docker::ImageInfo {
+            reference: reference.to_string(),
+            id: id.to_string(),
+            size: size.to_string(),
+        };
+        let images = vec![
+            image("dcx-base:dcx-a-1", "img1", "1GB"),
+            image("ubuntu:22.04", "img1", "1GB"),
+            image("vsc-a-123-uid:latest", "img2", "200MB"),
+            image("vsc-a-123:latest", "img3", "900MB"),
+        ];
+        let volumes = vec![
+            ("dcx-cache-1".to_string(), "50MB".to_string()),
+            ("other".to_string(), "1GB".to_string()),
+        ];
+        let usage = dcx_disk_usage(&images, &volumes);
+        assert_eq!(usage.images, 2);
+        assert_eq!(usage.image_bytes, 1_200_000_000);
+        assert_eq!(usage.volumes, 1);
+        assert_eq!(usage.volume_bytes, 50_000_000);
+    }
 }
@@ -0,0 +1,219 @@
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use crate::daemon;
+use crate::docker;
+use crate::exit_codes;
+use crate::lifetime;
+use crate::mount_table;
+use crate::naming::{is_dcx_managed_path, mount_name, relay_dir};
+use crate::platform;
+use crate::progress;
+use crate::state;
+use crate::up::{self, collision_error, do_mount, do_unmount, tilde_path};
+use crate::workspace::resolve_workspace;
+
+// ── Pure functions ────────────────────────────────────────────────────────────
+
+/// Informational message for `dcx stop` when the workspace has no container (idempotent).
+pub fn nothing_to_stop(workspace: &Path) -> String {
+    format!(
+        "No container found for {}. Nothing to do.",
+        workspace.display()
+    )
+}
+
+/// Error message for `dcx start` when there is no container to start.
+pub fn nothing_to_start(workspace: &Path) -> String {
+    format!(
+        "No container found for {}. Run `dcx up` to create one.",
+        workspace.display()
+    )
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// Steps shared by `dcx stop` and `dcx start`: validate Docker, resolve the workspace and
+/// guard against dcx-managed paths. Returns the workspace and its mount point.
+fn resolve(home: &Path, workspace_folder: Option<PathBuf>) -> Result<(PathBuf, PathBuf), i32> {
+    if !docker::is_docker_available() {
+        eprintln!("Docker is not available. Is Colima running?");
+        return Err(exit_codes::RUNTIME_ERROR);
+    }
+    let workspace = resolve_workspace(workspace_folder.as_deref()).map_err(|e| {
+        eprintln!("{e}");
+        exit_codes::USAGE_ERROR
+    })?;
+    let relay = relay_dir(home);
+    if is_dcx_managed_path(&workspace, &relay) {
+        eprintln!(
+            "Cannot use a dcx-managed mount point as a workspace. \
+             Use the original workspace path instead."
+        );
+        return Err(exit_codes::USAGE_ERROR);
+    }
+    let mount_point = relay.join(mount_name(&workspace));
+    Ok((workspace, mount_point))
+}
+
+/// Make sure `workspace` is bind-mounted at `mount_point`, remounting after a reboot or a
+/// dead FUSE process.
+fn ensure_mounted(home: &Path, workspace: &Path, mount_point: &Path) -> Result<(), String> {
+    let table = platform::read_mount_table().unwrap_or_default();
+    let source = mount_table::find_mount_source(&table, mount_point);
+    let workspace_str = workspace.to_string_lossy();
+    match source {
+        Some(s) if mount_point.exists() => {
+            if s == workspace_str.as_ref() {
+                return Ok(());
+            }
+            let name = mount_name(workspace);
+            return Err(collision_error(workspace, s, &name[name.len() - 8..]));
+        }
+        Some(_) => {
+            do_unmount(mount_point).map_err(|e| format!("Failed to unmount stale mount: {e}"))?
+        }
+        None => {}
+    }
+    progress::step(&format!(
+        "Mounting workspace to {}...",
+        tilde_path(mount_point, home)
+    ));
+    do_mount(workspace, mount_point)
+}
+
+// ── Entry points ──────────────────────────────────────────────────────────────
+
+/// Run `dcx stop`: stop the container, keeping it and the mount for `dcx start`.
+///
+/// With `daemon`, file sync for the workspace is stopped as well.
+/// Returns the exit code that `main` should pass to `std::process::exit`.
+pub fn run_stop(home: &Path, workspace_folder: Option<PathBuf>, daemon: bool) -> i32 {
+    let (workspace, mount_point) = match resolve(home, workspace_folder) {
+        Ok(r) => r,
+        Err(code) => return code,
+    };
+    let name = mount_name(&workspace);
+
+    if docker::query_container_any(&mount_point).is_empty() {
+        println!("{}", nothing_to_stop(&workspace));
+        return exit_codes::SUCCESS;
+    }
+
+    if docker::query_container(&mount_point).is_some() {
+        progress::step("Stopping devcontainer...");
+        if let Err(e) = docker::stop_container(&mount_point) {
+            eprintln!("{e}");
+            return exit_codes::RUNTIME_ERROR;
+        }
+    } else {
+        progress::step("Container is already stopped.");
+    }
+
+    if daemon {
+        progress::step("Stopping file sync...");
+        daemon::stop_sync(home, &mount_point);
+    }
+
+    state::record_event(
+        home,
+        &name,
+        if daemon {
+            "stop: container and file sync stopped"
+        } else {
+            "stop: container stopped"
+        },
+    );
+    progress::step("Done. Run `dcx start` to resume.");
+    exit_codes::SUCCESS
+}
+
+/// Run `dcx start`: resume a workspace paused by `dcx stop`.
+///
+/// Remounts the workspace if needed, re-registers the pairs recorded by the last `dcx up`
+/// with the dcx daemon, and `docker start`s the container. `devcontainer up` is not run, so
+/// lifecycle commands (including the `postStartCommand` that sets up the firewall) do not
+/// run again: the recorded network mode (the run-phase mode of a phased policy) is applied
+/// directly, and the container is stopped again if that fails. The workspace supervisor is
+/// re-spawned when a TTL or idle timeout is recorded.
+/// Returns the exit code that `main` should pass to `std::process::exit`.
+pub fn run_start(home: &Path, workspace_folder: Option<PathBuf>) -> i32 {
+    let (workspace, mount_point) = match resolve(home, workspace_folder) {
+        Ok(r) => r,
+        Err(code) => return code,
+    };
+    let name = mount_name(&workspace);
+
+    let containers = docker::query_container_any(&mount_point);
+    let Some(container) = containers.first() else {
+        eprintln!("{}", nothing_to_start(&workspace));
+        return exit_codes::RUNTIME_ERROR;
+    };
+
+    if let Err(e) = ensure_mounted(home, &workspace, &mount_point) {
+        eprintln!("{e}");
+        return exit_codes::RUNTIME_ERROR;
+    }
+
+    let syncing = daemon::health(home).is_some_and(|h| h.is_syncing(&name));
+    if !syncing && !state::load_sync_pairs(home, &name).is_empty() {
+        progress::step("Starting file sync...");
+        daemon::stop_legacy_sync(&mount_point);
+        if let Err(e) = daemon::register(home, &name) {
+            eprintln!("Warning: Could not start file sync: {e}");
+        }
+    }
+
+    if docker::query_container(&mount_point).is_none() {
+        progress::step("Starting devcontainer...");
+        if let Err(e) = docker::start_container(container) {
+            eprintln!("{e}");
+            return exit_codes::RUNTIME_ERROR;
+        }
+    } else {
+        progress::step("Container is already running.");
+    }
+
+    // The firewall rules do not survive a stop or a restart outside dcx. Fail closed, like
+    // `dcx up`.
+    match up::ensure_network_mode(home, &mount_point, container) {
+        Ok(Some(mode)) => progress::step(&format!("Applied network mode: {mode}")),
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("{e}");
+            return exit_codes::RUNTIME_ERROR;
+        }
+    }
+
+    // The idle clock restarts now: time spent stopped is not idle time.
+    state::touch_active(home, &name);
+
+    // Nothing enforces the TTL or idle timeout after a host or VM restart.
+    if lifetime::load(home, &name).is_some() {
+        lifetime::spawn_supervisor(home, &mount_point, &workspace);
+    }
+
+    state::record_event(home, &name, "start: container started");
+    progress::step("Done.");
+    exit_codes::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nothing_to_stop_is_informational() {
+        let msg = nothing_to_stop(Path::new("/home/u/proj"));
+        assert!(msg.contains("/home/u/proj"));
+        assert!(msg.contains("Nothing to do."));
+    }
+
+    #[test]
+    fn nothing_to_start_points_to_dcx_up() {
+        let msg = nothing_to_start(Path::new("/home/u/proj"));
+        assert!(msg.contains("/home/u/proj"));
+        assert!(msg.contains("dcx up"));
+    }
+}
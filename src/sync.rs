@@ -8,20 +8,27 @@ pub struct SyncPair {
     pub source: PathBuf,
     /// Staging path: ~/.colima-mounts/.dcx-...-files/.claude.json
     pub staging: PathBuf,
+    /// Merge concurrent edits key by key (`up.files[].merge`) instead of reporting a conflict.
+    pub merge: Option<MergeFormat>,
 }
 
 /// Compute SHA256 hash of a file.
 /// Returns None if the file is missing or unreadable.
 pub fn sha256_file(path: &std::path::Path) -> Option<[u8; 32]> {
+    let content = std::fs::read(path).ok()?;
+    Some(sha256_bytes(&content))
+}
+
+/// Compute SHA256 hash of in-memory content.
+pub fn sha256_bytes(content: &[u8]) -> [u8; 32] {
     use sha2::{Digest, Sha256};
 
-    let content = std::fs::read(path).ok()?;
     let mut hasher = Sha256::new();
-    hasher.update(&content);
+    hasher.update(content);
     let result = hasher.finalize();
     let mut bytes = [0u8; 32];
     bytes.copy_from_slice(&result[..]);
-    Some(bytes)
+    bytes
 }
 
 /// State tracking for a single sync pair
@@ -29,43 +36,570 @@ pub fn sha256_file(path: &std::path::Path) -> Option<[u8; 32]> {
 struct SyncState {
     last_source_hash: Option<[u8; 32]>,
     last_staging_hash: Option<[u8; 32]>,
+    /// Content both sides had after the last sync (the base for conflict detection), or
+    /// None if they have differed since the daemon started.
+    last_synced_hash: Option<[u8; 32]>,
+    /// Content behind `last_synced_hash`, kept for merge pairs as the three-way merge base.
+    last_synced_content: Option<Vec<u8>>,
+    /// Both sides changed; syncing is paused until `dcx sync resolve` removes the marker.
+    in_conflict: bool,
+    /// Where the base survives daemon restarts (see `base_file`), if anywhere.
+    base_file: Option<PathBuf>,
+}
+
+impl SyncState {
+    /// Initial state for `pair`. When the two sides differ, the base persisted in
+    /// `base_dir` by an earlier daemon tells which side changed while it was down.
+    fn new(pair: &SyncPair, base_dir: Option<&Path>) -> Self {
+        let source = sha256_file(&pair.source);
+        let staging = sha256_file(&pair.staging);
+        let base_file = base_dir.map(|dir| base_file(dir, pair));
+        let mut state = Self {
+            last_source_hash: source,
+            last_staging_hash: staging,
+            last_synced_hash: None,
+            last_synced_content: None,
+            in_conflict: conflict_marker(&pair.staging).exists(),
+            base_file,
+        };
+        if source == staging {
+            state.synced(pair, source);
+        } else if let Some((hash, content)) = state.base_file.as_deref().and_then(load_base) {
+            state.last_synced_hash = Some(hash);
+            state.last_synced_content = pair.merge.and(content);
+            // The side that no longer holds the base changed while the daemon was down:
+            // pretend it was last seen at the base so the next pass syncs it over.
+            if source == Some(hash) {
+                state.last_staging_hash = Some(hash);
+            }
+            if staging == Some(hash) {
+                state.last_source_hash = Some(hash);
+            }
+        }
+        state
+    }
+
+    /// Record that both sides now hold `hash`. Merge pairs also keep the content as the
+    /// next merge base (dropped if the file already changed again).
+    fn synced(&mut self, pair: &SyncPair, hash: Option<[u8; 32]>) {
+        self.last_source_hash = hash;
+        self.last_staging_hash = hash;
+        self.last_synced_hash = hash;
+        self.last_synced_content = pair.merge.and_then(|_| {
+            std::fs::read(&pair.source)
+                .ok()
+                .filter(|c| Some(sha256_bytes(c)) == hash)
+        });
+        self.save_base();
+    }
+
+    /// Forget the base: the sides differ and neither is known to be unchanged.
+    fn clear_base(&mut self) {
+        self.last_synced_hash = None;
+        self.last_synced_content = None;
+        self.save_base();
+    }
+
+    /// Persist the current base (best effort, like the sync log).
+    fn save_base(&self) {
+        let Some(path) = self.base_file.as_deref() else {
+            return;
+        };
+        match self.last_synced_hash {
+            Some(hash) => save_base(path, &hash, self.last_synced_content.as_deref()),
+            None => {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+    }
+}
+
+// ── Persisted base ────────────────────────────────────────────────────────────
+
+/// File holding the base of `pair` in `base_dir` (the workspace's `sync-base` state
+/// directory): the SHA-256 of the content both sides last held, as a hex line, followed by
+/// that content for merge pairs.
+pub fn base_file(base_dir: &Path, pair: &SyncPair) -> PathBuf {
+    let name = pair
+        .staging
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    base_dir.join(format!("{name}.base"))
+}
+
+/// Write a base file. It can hold a copy of a synced credentials file, so it is only
+/// readable by the owner (0600).
+fn save_base(path: &Path, hash: &[u8; 32], content: Option<&[u8]>) {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let Ok(mut file) = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)
+    else {
+        return;
+    };
+    let hex: String = hash.iter().map(|b| format!("{b:02x}")).collect();
+    let _ = file.write_all(format!("{hex}\n").as_bytes());
+    if let Some(content) = content {
+        let _ = file.write_all(content);
+    }
+}
+
+/// Read a base file: the hash, and the content if it was stored and matches the hash.
+fn load_base(path: &Path) -> Option<([u8; 32], Option<Vec<u8>>)> {
+    let data = std::fs::read(path).ok()?;
+    let newline = data.iter().position(|&b| b == b'\n')?;
+    let hex = std::str::from_utf8(&data[..newline]).ok()?;
+    if hex.len() != 64 {
+        return None;
+    }
+    let mut hash = [0u8; 32];
+    for (i, byte) in hash.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(hex.get(2 * i..2 * i + 2)?, 16).ok()?;
+    }
+    let content = data[newline + 1..].to_vec();
+    let content = (!content.is_empty() && sha256_bytes(&content) == hash).then_some(content);
+    Some((hash, content))
+}
+
+// ── Structured merge ──────────────────────────────────────────────────────────
+
+/// Document format of a synced file merged key by key (`up.files[].merge`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl MergeFormat {
+    /// Parse an `up.files[].merge` value.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "json" => Ok(MergeFormat::Json),
+            "yaml" => Ok(MergeFormat::Yaml),
+            "toml" => Ok(MergeFormat::Toml),
+            other => Err(format!(
+                "unknown merge format '{other}' (expected json, yaml or toml)"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for MergeFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            MergeFormat::Json => "json",
+            MergeFormat::Yaml => "yaml",
+            MergeFormat::Toml => "toml",
+        })
+    }
+}
+
+/// Parse a document into the common value model used for merging.
+fn parse_document(format: MergeFormat, content: &[u8]) -> Result<serde_json::Value, String> {
+    match format {
+        MergeFormat::Json => serde_json::from_slice(content).map_err(|e| e.to_string()),
+        MergeFormat::Yaml => serde_yaml::from_slice(content).map_err(|e| e.to_string()),
+        MergeFormat::Toml => {
+            let text = std::str::from_utf8(content).map_err(|e| e.to_string())?;
+            toml::from_str(text).map_err(|e| e.to_string())
+        }
+    }
 }
 
-/// Run the sync daemon: watches parent directories of source and staging files via inotify (Linux) / FSEvents (macOS)
-/// and keeps them in sync using SHA256 debounce.
+/// Serialize a merged document. Formatting and comments are not preserved.
+fn render_document(format: MergeFormat, value: &serde_json::Value) -> Result<Vec<u8>, String> {
+    match format {
+        MergeFormat::Json => {
+            let mut out = serde_json::to_vec_pretty(value).map_err(|e| e.to_string())?;
+            out.push(b'\n');
+            Ok(out)
+        }
+        MergeFormat::Yaml => serde_yaml::to_string(value)
+            .map(String::into_bytes)
+            .map_err(|e| e.to_string()),
+        MergeFormat::Toml => {
+            // Round-trip through toml::Value so datetimes are written as TOML datetimes.
+            use serde::Deserialize;
+            let table = toml::Table::deserialize(value).map_err(|e| e.to_string())?;
+            toml::to_string(&table)
+                .map(String::into_bytes)
+                .map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// Three-way merge of one value. `None` means absent (or deleted).
 ///
-/// This function runs indefinitely until SIGTERM is received.
-pub fn run_sync_daemon(pairs: Vec<SyncPair>, pid_file: std::path::PathBuf) -> ! {
-    use notify::Watcher;
-    use std::collections::HashSet;
-    use std::sync::Arc;
-    use std::sync::atomic::{AtomicBool, Ordering};
-    use std::sync::mpsc;
+/// A side that left the value as in `base` takes the other side's version; objects changed
+/// on both sides are merged key by key; anything else changed differently on both sides
+/// (including arrays) is a conflict at `path`.
+pub fn merge_values(
+    base: Option<&serde_json::Value>,
+    ours: Option<&serde_json::Value>,
+    theirs: Option<&serde_json::Value>,
+    path: &str,
+) -> Result<Option<serde_json::Value>, String> {
+    use serde_json::Value;
+
+    if ours == theirs || theirs == base {
+        return Ok(ours.cloned());
+    }
+    if ours == base {
+        return Ok(theirs.cloned());
+    }
+    let (Some(Value::Object(o)), Some(Value::Object(t))) = (ours, theirs) else {
+        return Err(format!("`{path}` changed on both sides"));
+    };
+    let empty = serde_json::Map::new();
+    let b = match base {
+        Some(Value::Object(b)) => b,
+        _ => &empty,
+    };
+    let mut keys: Vec<&String> = o.keys().chain(t.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    let mut merged = serde_json::Map::new();
+    for key in keys {
+        let key_path = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{path}.{key}")
+        };
+        if let Some(v) = merge_values(b.get(key), o.get(key), t.get(key), &key_path)? {
+            merged.insert(key.clone(), v);
+        }
+    }
+    Ok(Some(Value::Object(merged)))
+}
+
+/// Key-level three-way merge of two versions of a document changed since `base`.
+///
+/// Returns the merged document, or an error naming the first key changed on both sides
+/// (or the side that no longer parses).
+pub fn merge_documents(
+    format: MergeFormat,
+    base: &[u8],
+    ours: &[u8],
+    theirs: &[u8],
+) -> Result<Vec<u8>, String> {
+    let base = parse_document(format, base).map_err(|e| format!("base: {e}"))?;
+    let ours = parse_document(format, ours).map_err(|e| format!("host: {e}"))?;
+    let theirs = parse_document(format, theirs).map_err(|e| format!("container: {e}"))?;
+    let merged = merge_values(Some(&base), Some(&ours), Some(&theirs), "")
+        .map_err(|e| e.replace("``", "`(document)`"))?
+        .unwrap_or(serde_json::Value::Null);
+    render_document(format, &merged)
+}
 
-    // Write PID to file
-    let pid = std::process::id().to_string();
-    let _ = std::fs::write(&pid_file, &pid);
+/// Merge both sides of `pair` against `base` and write the result to both.
+/// Returns the merged content's hash, or `None` without writing anything if the host
+/// file no longer has `src_hash` (it was rewritten during the pass; the watcher event
+/// for that write triggers the next merge).
+fn merge_pair(
+    pair: &SyncPair,
+    format: MergeFormat,
+    base: Option<&[u8]>,
+    src_hash: Option<[u8; 32]>,
+) -> Result<Option<[u8; 32]>, String> {
+    let base = base.ok_or("no merge base")?;
+    let ours = std::fs::read(&pair.source).map_err(|e| e.to_string())?;
+    let theirs = std::fs::read(&pair.staging).map_err(|e| e.to_string())?;
+    if Some(sha256_bytes(&ours)) != src_hash {
+        return Ok(None);
+    }
+    let merged = merge_documents(format, base, &ours, &theirs)?;
+    // Re-check right before replacing the host file so a concurrent host write is not lost.
+    if sha256_file(&pair.source) != src_hash {
+        return Ok(None);
+    }
+    atomic_write(&pair.source, &merged).map_err(|e| e.to_string())?;
+    atomic_write(&pair.staging, &merged).map_err(|e| e.to_string())?;
+    Ok(Some(sha256_bytes(&merged)))
+}
 
-    // Set up SIGTERM handler
-    let term_flag = Arc::new(AtomicBool::new(false));
-    let term_flag_clone = Arc::clone(&term_flag);
-    let _ = signal_hook::flag::register(signal_hook::consts::SIGTERM, term_flag_clone);
+// ── Conflicts ─────────────────────────────────────────────────────────────────
 
-    // Ignore SIGHUP so daemon survives when the terminal that ran `dcx up` closes.
-    // Registering a handler (even one we never read) prevents the default kill action.
-    let _ = signal_hook::flag::register(
-        signal_hook::consts::SIGHUP,
-        Arc::new(AtomicBool::new(false)),
-    );
+/// Suffix of the marker written next to a staged file while its pair is in conflict.
+pub const CONFLICT_MARKER_SUFFIX: &str = ".dcx-conflict";
 
-    // Initialize state tracking
-    let mut states: Vec<SyncState> = pairs
-        .iter()
-        .map(|pair| SyncState {
-            last_source_hash: sha256_file(&pair.source),
-            last_staging_hash: sha256_file(&pair.staging),
+/// An unresolved sync conflict, recorded in the marker next to the staged file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    /// Host file.
+    pub source: PathBuf,
+    /// Staged (container-side) file.
+    pub staging: PathBuf,
+    /// Copy of the container's version next to the host file.
+    pub conflict_file: PathBuf,
+}
+
+/// Three-way check: both sides changed since the last sync, to different contents.
+///
+/// Without a base (the sides differed when the daemon started) nothing is a conflict and
+/// the host stays authoritative.
+pub fn is_conflict(
+    source: Option<[u8; 32]>,
+    staging: Option<[u8; 32]>,
+    base: Option<[u8; 32]>,
+) -> bool {
+    base.is_some() && source != base && staging != base && source != staging
+}
+
+/// Marker path for a staged file: `<staging>.dcx-conflict`.
+pub fn conflict_marker(staging: &Path) -> PathBuf {
+    let mut name = staging.file_name().unwrap_or_default().to_os_string();
+    name.push(CONFLICT_MARKER_SUFFIX);
+    staging.with_file_name(name)
+}
+
+/// Where the container's version is kept: `<source>.conflict-<YYYYMMDDTHHMMSSZ>`.
+pub fn conflict_file_path(source: &Path, timestamp: u64) -> PathBuf {
+    let stamp: String = crate::duration::format_timestamp(timestamp)
+        .chars()
+        .filter(|c| *c != '-' && *c != ':')
+        .collect();
+    let mut name = source.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".conflict-{stamp}"));
+    source.with_file_name(name)
+}
+
+/// Marker content: `<source>\t<conflict file>`.
+pub fn format_conflict_marker(source: &Path, conflict_file: &Path) -> String {
+    format!("{}\t{}\n", source.display(), conflict_file.display())
+}
+
+/// Parse a marker at `marker` (whose name gives the staged file). None if malformed.
+pub fn parse_conflict_marker(marker: &Path, content: &str) -> Option<Conflict> {
+    let (source, conflict_file) = content.trim_end().split_once('\t')?;
+    let name = marker.file_name()?.to_str()?;
+    let staged = name.strip_suffix(CONFLICT_MARKER_SUFFIX)?;
+    Some(Conflict {
+        source: PathBuf::from(source),
+        staging: marker.with_file_name(staged),
+        conflict_file: PathBuf::from(conflict_file),
+    })
+}
+
+/// Unresolved conflicts in a workspace's staging directory, sorted by staged file.
+pub fn list_conflicts(staging_dir: &Path) -> Vec<Conflict> {
+    let Ok(entries) = std::fs::read_dir(staging_dir) else {
+        return Vec::new();
+    };
+    let mut conflicts: Vec<Conflict> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.ends_with(CONFLICT_MARKER_SUFFIX))
         })
+        .filter_map(|p| parse_conflict_marker(&p, &std::fs::read_to_string(&p).ok()?))
         .collect();
+    conflicts.sort_by(|a, b| a.staging.cmp(&b.staging));
+    conflicts
+}
+
+/// Which version `dcx sync resolve --take` keeps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum SyncSide {
+    /// The host file
+    Host,
+    /// The container's (staged) file
+    Container,
+}
+
+impl std::fmt::Display for SyncSide {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SyncSide::Host => "host",
+            SyncSide::Container => "container",
+        })
+    }
+}
+
+/// Whether `conflict` is selected by the optional `file` argument of `dcx sync resolve`:
+/// the host path, or just its file name.
+pub fn conflict_matches(conflict: &Conflict, file: Option<&Path>) -> bool {
+    match file {
+        None => true,
+        Some(f) if f.components().count() == 1 => {
+            conflict.source.file_name() == Some(f.as_os_str())
+        }
+        Some(f) => {
+            conflict.source == f
+                || f.canonicalize()
+                    .is_ok_and(|c| conflict.source.canonicalize().is_ok_and(|s| s == c))
+        }
+    }
+}
+
+/// Resolve one conflict. The marker is removed before copying so the daemon, woken by the
+/// copy, resumes from the resolved contents. The conflict file is removed last.
+pub fn resolve_conflict(conflict: &Conflict, take: SyncSide) -> Result<(), String> {
+    let marker = conflict_marker(&conflict.staging);
+    std::fs::remove_file(&marker)
+        .map_err(|e| format!("Failed to remove {}: {e}", marker.display()))?;
+    let (from, to) = match take {
+        SyncSide::Host => (&conflict.source, &conflict.staging),
+        SyncSide::Container => (&conflict.staging, &conflict.source),
+    };
+    atomic_copy(from, to)
+        .map_err(|e| format!("Failed to copy {} to {}: {e}", from.display(), to.display()))?;
+    let _ = std::fs::remove_file(&conflict.conflict_file);
+    Ok(())
+}
+
+/// Record a conflict: copy the container's version next to the host file and write the
+/// marker that pauses the pair. Returns the conflict file.
+fn record_conflict(pair: &SyncPair, timestamp: u64) -> io::Result<PathBuf> {
+    let conflict_file = conflict_file_path(&pair.source, timestamp);
+    std::fs::copy(&pair.staging, &conflict_file)?;
+    std::fs::write(
+        conflict_marker(&pair.staging),
+        format_conflict_marker(&pair.source, &conflict_file),
+    )?;
+    Ok(conflict_file)
+}
+
+// ── Daemon log ────────────────────────────────────────────────────────────────
+
+/// Size at which the daemon log is rotated to `<log>.1` (one generation is kept).
+pub const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// A parsed daemon log line: `<rfc3339> <LEVEL> <message>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogLine {
+    pub timestamp: u64,
+    pub level: String,
+    pub message: String,
+}
+
+/// Format a daemon log line.
+pub fn format_log_line(timestamp: u64, level: &str, message: &str) -> String {
+    format!(
+        "{} {level} {message}",
+        crate::duration::format_timestamp(timestamp)
+    )
+}
+
+/// Parse a line written by `format_log_line`. Returns `None` for malformed lines.
+pub fn parse_log_line(line: &str) -> Option<LogLine> {
+    let mut parts = line.splitn(3, ' ');
+    let timestamp = crate::duration::parse_timestamp(parts.next()?)?;
+    let level = parts.next()?.to_string();
+    let message = parts.next().unwrap_or_default().to_string();
+    Some(LogLine {
+        timestamp,
+        level,
+        message,
+    })
+}
+
+/// Path of the rotated (previous) log generation.
+pub fn rotated_log_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".1");
+    path.with_file_name(name)
+}
+
+/// Most recent `ERROR` entry in the daemon log (current generation, then rotated).
+pub fn last_error(path: &Path) -> Option<LogLine> {
+    [path.to_path_buf(), rotated_log_path(path)]
+        .iter()
+        .filter_map(|p| std::fs::read_to_string(p).ok())
+        .find_map(|content| {
+            content
+                .lines()
+                .rev()
+                .filter_map(parse_log_line)
+                .find(|l| l.level == "ERROR")
+        })
+}
+
+/// Append-only daemon log with size-based rotation. Without a path, messages go to
+/// stderr only.
+pub struct DaemonLog {
+    path: Option<PathBuf>,
+}
+
+impl DaemonLog {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self { path }
+    }
+
+    pub fn info(&self, message: &str) {
+        self.write("INFO", message);
+    }
+
+    pub fn warn(&self, message: &str) {
+        self.write("WARN", message);
+    }
+
+    pub fn error(&self, message: &str) {
+        self.write("ERROR", message);
+    }
+
+    fn write(&self, level: &str, message: &str) {
+        use std::io::Write;
+
+        let line = format_log_line(crate::state::unix_now(), level, message);
+        eprintln!("{line}");
+        let Some(path) = &self.path else {
+            return;
+        };
+        if std::fs::metadata(path).is_ok_and(|m| m.len() >= MAX_LOG_BYTES) {
+            let _ = std::fs::rename(path, rotated_log_path(path));
+        }
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Ok(mut f) = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+        {
+            let _ = writeln!(f, "{line}");
+        }
+    }
+}
+
+/// Keep `pairs` in sync until `stop` is set: watches parent directories of source and
+/// staging files via inotify (Linux) / FSEvents (macOS) and syncs using SHA256 debounce.
+///
+/// Run by the dcx daemon on one thread per workspace. The conflict/merge base of each pair
+/// is persisted in `base_dir`, so a restarted daemon still detects edits made on both sides
+/// while it was down.
+pub fn run_sync_loop(
+    pairs: &[SyncPair],
+    base_dir: Option<&Path>,
+    log: &DaemonLog,
+    stop: &std::sync::atomic::AtomicBool,
+) {
+    use notify::Watcher;
+    use std::collections::HashSet;
+    use std::sync::atomic::Ordering;
+    use std::sync::mpsc;
+
+    log.info(&format!(
+        "started ({} file{})",
+        pairs.len(),
+        if pairs.len() == 1 { "" } else { "s" }
+    ));
+
+    // Initialize state tracking
+    let mut states: Vec<SyncState> = pairs.iter().map(|p| SyncState::new(p, base_dir)).collect();
+    // Catch up on edits made while no daemon was watching.
+    sync_all_pairs(pairs, &mut states, log);
 
     // Build a set of watched filenames for efficient filtering (O(1) lookup)
     let watched_names: HashSet<std::ffi::OsString> = pairs
@@ -80,31 +614,35 @@ pub fn run_sync_daemon(pairs: Vec<SyncPair>, pid_file: std::path::PathBuf) -> !
     let mut watcher = match notify::recommended_watcher(tx) {
         Ok(w) => w,
         Err(e) => {
-            eprintln!("Warning: Could not create file watcher: {e}");
+            log.warn(&format!(
+                "could not create file watcher: {e}; polling every 1s"
+            ));
             // Fall back to 1s polling if watcher fails
-            loop_with_fallback(&pairs, &mut states, &term_flag, &pid_file);
+            loop_with_fallback(pairs, &mut states, stop, log);
+            return;
         }
     };
 
     // Watch parent directories of source and staging files (handles atomic writes correctly)
-    for pair in &pairs {
-        if let Some(parent) = pair.source.parent() {
-            let _ = watcher.watch(parent, notify::RecursiveMode::NonRecursive);
-        }
-        if let Some(parent) = pair.staging.parent() {
-            let _ = watcher.watch(parent, notify::RecursiveMode::NonRecursive);
+    for pair in pairs {
+        for parent in [pair.source.parent(), pair.staging.parent()]
+            .into_iter()
+            .flatten()
+        {
+            if let Err(e) = watcher.watch(parent, notify::RecursiveMode::NonRecursive) {
+                log.error(&format!("watch {}: {e}", parent.display()));
+            }
         }
     }
 
-    // Main event loop: wait for file change notifications or SIGTERM
+    // Main event loop: wait for file change notifications or a stop request
     loop {
-        if term_flag.load(Ordering::Relaxed) {
-            // Clean shutdown: remove PID file and exit
-            let _ = std::fs::remove_file(&pid_file);
-            std::process::exit(0);
+        if stop.load(Ordering::Relaxed) {
+            log.info("stopped");
+            return;
         }
 
-        // Wait for a file change event with 1s timeout to check SIGTERM regularly
+        // Wait for a file change event with 1s timeout to check the stop flag regularly
         match rx.recv_timeout(std::time::Duration::from_secs(1)) {
             Ok(Ok(event)) => {
                 // Only sync if the event involves one of our watched files
@@ -114,18 +652,20 @@ pub fn run_sync_daemon(pairs: Vec<SyncPair>, pid_file: std::path::PathBuf) -> !
                         .unwrap_or(false)
                 });
                 if relevant {
-                    sync_all_pairs(&pairs, &mut states);
+                    sync_all_pairs(pairs, &mut states, log);
                 }
             }
             Ok(Err(e)) => {
-                eprintln!("Warning: file watcher error: {e}");
+                log.error(&format!("file watcher error: {e}"));
             }
             Err(mpsc::RecvTimeoutError::Timeout) => {
-                // Timeout: check SIGTERM and loop
+                // Timeout: check the stop flag and loop
             }
             Err(mpsc::RecvTimeoutError::Disconnected) => {
                 // Watcher disconnected: fall back to polling
-                loop_with_fallback(&pairs, &mut states, &term_flag, &pid_file);
+                log.warn("file watcher disconnected; polling every 1s");
+                loop_with_fallback(pairs, &mut states, stop, log);
+                return;
             }
         }
     }
@@ -138,12 +678,23 @@ pub fn should_sync_to_source(source_len: u64, staging_len: u64) -> bool {
     !(staging_len == 0 && source_len > 0)
 }
 
+/// A temp file path next to `dst`, unique per call: the daemon's sync threads may write
+/// files in the same directory at the same time (e.g. two workspaces syncing
+/// `~/.claude.json`), so the PID alone is not enough.
+fn temp_path(dst: &Path) -> PathBuf {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let parent = dst.parent().unwrap_or(Path::new("."));
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    parent.join(format!(".dcx-sync-{}-{n}.tmp", std::process::id()))
+}
+
 /// Atomically copy `src` to `dst` via temp file + rename.
 /// The temp file is created in `dst`'s parent directory to guarantee
 /// same-filesystem rename (atomic on POSIX).
-fn atomic_copy(src: &Path, dst: &Path) -> io::Result<()> {
-    let parent = dst.parent().unwrap_or(Path::new("."));
-    let tmp = parent.join(format!(".dcx-sync-{}.tmp", std::process::id()));
+pub(crate) fn atomic_copy(src: &Path, dst: &Path) -> io::Result<()> {
+    let tmp = temp_path(dst);
 
     // Copy content to temp file
     match std::fs::copy(src, &tmp) {
@@ -164,24 +715,131 @@ fn atomic_copy(src: &Path, dst: &Path) -> io::Result<()> {
     }
 }
 
+/// Atomically replace `dst` with `content` via temp file + rename (see `atomic_copy`).
+/// The result keeps `dst`'s permissions (0600 if `dst` is missing): synced files are
+/// often owner-only credentials.
+pub(crate) fn atomic_write(dst: &Path, content: &[u8]) -> io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    let tmp = temp_path(dst);
+    let mode = std::fs::metadata(dst)
+        .map(|m| m.permissions().mode() & 0o7777)
+        .unwrap_or(0o600);
+
+    let written = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&tmp)
+        .and_then(|mut file| {
+            file.write_all(content)?;
+            file.set_permissions(std::fs::Permissions::from_mode(mode))
+        });
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e);
+    }
+    match std::fs::rename(&tmp, dst) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            let _ = std::fs::remove_file(&tmp);
+            Err(e)
+        }
+    }
+}
+
 /// Sync all pairs: check hashes and copy if needed
-fn sync_all_pairs(pairs: &[SyncPair], states: &mut [SyncState]) {
+fn sync_all_pairs(pairs: &[SyncPair], states: &mut [SyncState], log: &DaemonLog) {
     for (pair, state) in pairs.iter().zip(states.iter_mut()) {
         let src_hash = sha256_file(&pair.source);
         let stg_hash = sha256_file(&pair.staging);
 
+        // Paused by a conflict: resume once `dcx sync resolve` removed the marker.
+        if state.in_conflict {
+            if conflict_marker(&pair.staging).exists() {
+                continue;
+            }
+            log.info(&format!(
+                "sync: conflict resolved for {}",
+                pair.source.display()
+            ));
+            state.in_conflict = false;
+            if src_hash == stg_hash {
+                state.synced(pair, src_hash);
+            } else {
+                state.last_source_hash = src_hash;
+                state.last_staging_hash = stg_hash;
+                state.clear_base();
+            }
+            continue;
+        }
+
+        // Both changed since the last sync → merge if configured, otherwise (or if the
+        // merge fails) keep both versions and pause the pair.
+        if is_conflict(src_hash, stg_hash, state.last_synced_hash) {
+            if let Some(format) = pair.merge {
+                match merge_pair(pair, format, state.last_synced_content.as_deref(), src_hash) {
+                    Ok(None) => {
+                        log.info(&format!(
+                            "sync: {} changed during merge; merging again on the next change",
+                            pair.source.display()
+                        ));
+                        continue;
+                    }
+                    Ok(Some(merged)) => {
+                        log.info(&format!(
+                            "sync: merged {} and {} ({format})",
+                            pair.source.display(),
+                            pair.staging.display()
+                        ));
+                        state.synced(pair, Some(merged));
+                        continue;
+                    }
+                    Err(e) => log.warn(&format!(
+                        "sync: cannot merge {}: {e}",
+                        pair.source.display()
+                    )),
+                }
+            }
+            match record_conflict(pair, crate::state::unix_now()) {
+                Ok(conflict_file) => log.error(&format!(
+                    "sync: conflict: {} and {} both changed; container version saved to {}. \
+                     Run `dcx sync resolve --take host|container`",
+                    pair.source.display(),
+                    pair.staging.display(),
+                    conflict_file.display()
+                )),
+                Err(e) => {
+                    log.error(&format!(
+                        "sync: conflict on {}: could not save container version: {e}",
+                        pair.source.display()
+                    ));
+                    continue;
+                }
+            }
+            state.in_conflict = true;
+            state.last_source_hash = src_hash;
+            state.last_staging_hash = stg_hash;
+            continue;
+        }
+
         // Source changed → sync to staging (host is authority, no guard)
         if src_hash != state.last_source_hash && src_hash != stg_hash {
             if let Err(e) = atomic_copy(&pair.source, &pair.staging) {
-                eprintln!(
+                log.error(&format!(
                     "sync: {} -> {}: {e}",
                     pair.source.display(),
                     pair.staging.display()
-                );
+                ));
                 continue;
             }
-            state.last_source_hash = src_hash;
-            state.last_staging_hash = src_hash;
+            log.info(&format!(
+                "sync: {} -> {}",
+                pair.source.display(),
+                pair.staging.display()
+            ));
+            state.synced(pair, src_hash);
         }
         // Staging changed → sync to source (apply size guard)
         else if stg_hash != state.last_staging_hash && stg_hash != src_hash {
@@ -192,23 +850,28 @@ fn sync_all_pairs(pairs: &[SyncPair], states: &mut [SyncState]) {
                 .map(|m| m.len())
                 .unwrap_or(0);
             if !should_sync_to_source(src_len, stg_len) {
-                eprintln!(
-                    "sync: rejecting staging->source ({stg_len}B would overwrite {src_len}B)"
-                );
+                log.error(&format!(
+                    "sync: rejecting staging->source for {} ({stg_len}B would overwrite {src_len}B)",
+                    pair.source.display()
+                ));
                 // Acknowledge change so we don't retry every cycle
                 state.last_staging_hash = stg_hash;
                 continue;
             }
             if let Err(e) = atomic_copy(&pair.staging, &pair.source) {
-                eprintln!(
+                log.error(&format!(
                     "sync: {} -> {}: {e}",
                     pair.staging.display(),
                     pair.source.display()
-                );
+                ));
                 continue;
             }
-            state.last_source_hash = stg_hash;
-            state.last_staging_hash = stg_hash;
+            log.info(&format!(
+                "sync: {} -> {}",
+                pair.staging.display(),
+                pair.source.display()
+            ));
+            state.synced(pair, stg_hash);
         }
     }
 }
@@ -217,22 +880,93 @@ fn sync_all_pairs(pairs: &[SyncPair], states: &mut [SyncState]) {
 fn loop_with_fallback(
     pairs: &[SyncPair],
     states: &mut [SyncState],
-    term_flag: &std::sync::atomic::AtomicBool,
-    pid_file: &std::path::Path,
-) -> ! {
+    stop: &std::sync::atomic::AtomicBool,
+    log: &DaemonLog,
+) {
     use std::sync::atomic::Ordering;
 
     loop {
-        if term_flag.load(Ordering::Relaxed) {
-            let _ = std::fs::remove_file(pid_file);
-            std::process::exit(0);
+        if stop.load(Ordering::Relaxed) {
+            log.info("stopped");
+            return;
         }
 
-        sync_all_pairs(pairs, states);
+        sync_all_pairs(pairs, states, log);
         std::thread::sleep(std::time::Duration::from_secs(1));
     }
 }
 
+// ── dcx sync resolve ──────────────────────────────────────────────────────────
+
+/// Run `dcx sync resolve --take host|container [FILE]`.
+///
+/// Returns the exit code that `main` should pass to `std::process::exit`.
+pub fn run_sync_resolve(
+    home: &Path,
+    workspace_folder: Option<PathBuf>,
+    take: SyncSide,
+    file: Option<PathBuf>,
+) -> i32 {
+    use crate::exit_codes;
+
+    let workspace = match crate::workspace::resolve_workspace(workspace_folder.as_deref()) {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("{e}");
+            return exit_codes::USAGE_ERROR;
+        }
+    };
+    let name = crate::naming::mount_name(&workspace);
+    let mount_point = crate::naming::relay_dir(home).join(&name);
+    let conflicts: Vec<Conflict> = list_conflicts(&crate::up::staging_dir(&mount_point))
+        .into_iter()
+        .filter(|c| conflict_matches(c, file.as_deref()))
+        .collect();
+    if conflicts.is_empty() {
+        match &file {
+            Some(f) => {
+                eprintln!(
+                    "No sync conflict for {} in {}.",
+                    f.display(),
+                    workspace.display()
+                );
+                return exit_codes::USAGE_ERROR;
+            }
+            None => println!("No sync conflicts in {}.", workspace.display()),
+        }
+        return exit_codes::SUCCESS;
+    }
+
+    let mut failed = false;
+    for conflict in &conflicts {
+        match resolve_conflict(conflict, take) {
+            Ok(()) => {
+                println!(
+                    "Resolved {}: kept the {take} version.",
+                    conflict.source.display()
+                );
+                crate::state::record_event(
+                    home,
+                    &name,
+                    &format!(
+                        "sync: resolved conflict on {} (took {take})",
+                        conflict.source.display()
+                    ),
+                );
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        exit_codes::RUNTIME_ERROR
+    } else {
+        exit_codes::SUCCESS
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -373,6 +1107,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::cloned_ref_to_slice_refs)]
     fn atomic_write_triggers_sync() {
         use std::io::Write;
 
@@ -391,11 +1126,16 @@ mod tests {
         let pair = SyncPair {
             source: src_path.clone(),
             staging: stg_path.clone(),
+            merge: None,
         };
 
         let state = SyncState {
             last_source_hash: sha256_file(&src_path),
             last_staging_hash: sha256_file(&stg_path),
+            last_synced_hash: sha256_file(&src_path),
+            last_synced_content: None,
+            in_conflict: false,
+            base_file: None,
         };
 
         // Perform atomic write on source (write to temp, rename into place)
@@ -407,7 +1147,7 @@ mod tests {
         fs::rename(&tmp_path, &src_path).unwrap();
 
         // Run sync logic
-        sync_all_pairs(&[pair.clone()], &mut [state.clone()]);
+        sync_all_pairs(&[pair.clone()], &mut [state.clone()], &DaemonLog::new(None));
 
         // Verify staging was updated with new content
         let stg_content = fs::read(&stg_path).unwrap();
@@ -415,6 +1155,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::cloned_ref_to_slice_refs)]
     fn unrelated_file_in_same_dir_does_not_trigger_sync() {
         use std::io::Write;
 
@@ -433,11 +1174,16 @@ mod tests {
         let pair = SyncPair {
             source: src_path.clone(),
             staging: stg_path.clone(),
+            merge: None,
         };
 
         let state = SyncState {
             last_source_hash: sha256_file(&src_path),
             last_staging_hash: sha256_file(&stg_path),
+            last_synced_hash: sha256_file(&src_path),
+            last_synced_content: None,
+            in_conflict: false,
+            base_file: None,
         };
 
         // Create an unrelated file in the same directory as source
@@ -451,7 +1197,7 @@ mod tests {
         // We simulate the event filtering by directly calling sync_all_pairs
         // which will only sync if hashes have changed.
         // Since we didn't change the watched file, nothing should sync.
-        sync_all_pairs(&[pair.clone()], &mut [state.clone()]);
+        sync_all_pairs(&[pair.clone()], &mut [state.clone()], &DaemonLog::new(None));
 
         // Verify staging content is unchanged
         let stg_content = fs::read(&stg_path).unwrap();
@@ -526,6 +1272,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::unnecessary_map_or)]
     fn atomic_copy_no_temp_left_on_success() {
         let dir = tempfile::TempDir::new().unwrap();
         let src = dir.path().join("src.txt");
@@ -543,6 +1290,38 @@ mod tests {
         assert!(tmp_files.is_empty());
     }
 
+    #[test]
+    fn atomic_copy_concurrent_writers_in_one_directory() {
+        // Two daemon workers syncing files that share a directory.
+        let dir = tempfile::TempDir::new().unwrap();
+        let workers: Vec<_> = ["a", "b"]
+            .into_iter()
+            .map(|name| {
+                let src = dir.path().join(format!("{name}.src"));
+                let dst = dir.path().join(format!("{name}.json"));
+                std::thread::spawn(move || {
+                    for i in 0..200 {
+                        let content = format!("{name}-{i}");
+                        fs::write(&src, &content).unwrap();
+                        atomic_copy(&src, &dst).unwrap();
+                        assert_eq!(fs::read_to_string(&dst).unwrap(), content);
+                        atomic_write(&dst, content.as_bytes()).unwrap();
+                        assert_eq!(fs::read_to_string(&dst).unwrap(), content);
+                    }
+                })
+            })
+            .collect();
+        for w in workers {
+            w.join().unwrap();
+        }
+        let leftovers = fs::read_dir(dir.path())
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "tmp"))
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
     // --- sync_all_pairs with size guard ---
 
     #[test]
@@ -558,62 +1337,582 @@ mod tests {
         let pair = SyncPair {
             source: src.clone(),
             staging: stg.clone(),
+            merge: None,
         };
         let state = SyncState {
             last_source_hash: sha256_file(&src),
             last_staging_hash: sha256_file(&stg),
+            last_synced_hash: sha256_file(&src),
+            last_synced_content: None,
+            in_conflict: false,
+            base_file: None,
         };
 
         // Now empty the staging file (simulating container writing minimal config)
         fs::write(&stg, b"").unwrap();
 
-        sync_all_pairs(&[pair], &mut [state]);
+        sync_all_pairs(&[pair], &mut [state], &DaemonLog::new(None));
 
         // Source must NOT have been overwritten
         assert_eq!(fs::read(&src).unwrap(), b"important auth tokens here");
     }
 
     #[test]
-    fn sync_all_pairs_simultaneous_source_and_staging_change_host_wins() {
-        // Test case: both source and staging changed simultaneously
-        // Expected: source is authority, so source overwrites staging
+    fn sync_all_pairs_simultaneous_source_and_staging_change_is_a_conflict() {
+        // Both files change independently since the last sync: neither overwrites the
+        // other; the container's version is kept next to the host file and the pair pauses.
         let dir = tempfile::TempDir::new().unwrap();
         let src = dir.path().join("source.json");
-        let stg = dir.path().join("staging.json");
-
-        // Initial state: both files have same content
+        let stg_dir = tempfile::TempDir::new().unwrap();
+        let stg = stg_dir.path().join("source.json");
         fs::write(&src, b"v1").unwrap();
         fs::write(&stg, b"v1").unwrap();
+        let pair = SyncPair {
+            source: src.clone(),
+            staging: stg.clone(),
+            merge: None,
+        };
+        let mut states = [SyncState::new(&pair, None)];
 
-        let initial_src_hash = sha256_file(&src).unwrap();
-        let initial_stg_hash = sha256_file(&stg).unwrap();
-        assert_eq!(initial_src_hash, initial_stg_hash);
+        fs::write(&src, b"host-change").unwrap();
+        fs::write(&stg, b"container-change").unwrap();
+        sync_all_pairs(
+            std::slice::from_ref(&pair),
+            &mut states,
+            &DaemonLog::new(None),
+        );
 
+        assert_eq!(fs::read(&src).unwrap(), b"host-change");
+        assert_eq!(fs::read(&stg).unwrap(), b"container-change");
+        let conflicts = list_conflicts(stg_dir.path());
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].source, src);
+        assert_eq!(conflicts[0].staging, stg);
+        assert_eq!(
+            fs::read(&conflicts[0].conflict_file).unwrap(),
+            b"container-change"
+        );
+
+        // Paused: a further host change is not pushed while the marker exists.
+        fs::write(&src, b"host-change-2").unwrap();
+        sync_all_pairs(
+            std::slice::from_ref(&pair),
+            &mut states,
+            &DaemonLog::new(None),
+        );
+        assert_eq!(fs::read(&stg).unwrap(), b"container-change");
+
+        // Resolve (take host): marker removed first, then the host version copied over.
+        fs::remove_file(conflict_marker(&stg)).unwrap();
+        atomic_copy(&src, &stg).unwrap();
+        sync_all_pairs(
+            std::slice::from_ref(&pair),
+            &mut states,
+            &DaemonLog::new(None),
+        );
+        assert!(!states[0].in_conflict);
+
+        // Syncing resumes.
+        fs::write(&stg, b"container-edit").unwrap();
+        sync_all_pairs(
+            std::slice::from_ref(&pair),
+            &mut states,
+            &DaemonLog::new(None),
+        );
+        assert_eq!(fs::read(&src).unwrap(), b"container-edit");
+    }
+
+    #[test]
+    fn sync_all_pairs_without_base_keeps_host_authority() {
+        // The sides already differed when the daemon started: no base, so host wins.
+        let dir = tempfile::TempDir::new().unwrap();
+        let src = dir.path().join("source.json");
+        let stg = dir.path().join("staging.json");
+        fs::write(&src, b"host").unwrap();
+        fs::write(&stg, b"container").unwrap();
         let pair = SyncPair {
             source: src.clone(),
             staging: stg.clone(),
+            merge: None,
         };
-        let state = SyncState {
-            last_source_hash: Some(initial_src_hash),
-            last_staging_hash: Some(initial_stg_hash),
-        };
+        let mut states = [SyncState::new(&pair, None)];
+        assert_eq!(states[0].last_synced_hash, None);
 
-        // BOTH files are modified independently
         fs::write(&src, b"host-change").unwrap();
         fs::write(&stg, b"container-change").unwrap();
+        sync_all_pairs(&[pair], &mut states, &DaemonLog::new(None));
 
-        let src_hash_now = sha256_file(&src).unwrap();
-        let stg_hash_now = sha256_file(&stg).unwrap();
-        assert_ne!(src_hash_now, stg_hash_now);
-        assert_ne!(src_hash_now, initial_src_hash);
-        assert_ne!(stg_hash_now, initial_stg_hash);
+        assert_eq!(fs::read(&stg).unwrap(), b"host-change");
+        assert!(list_conflicts(dir.path()).is_empty());
+    }
 
-        // Run sync: source changed AND source != staging → source wins
-        sync_all_pairs(&[pair], &mut [state.clone()]);
+    /// A pair whose base was persisted by a daemon that has since stopped.
+    fn pair_with_persisted_base(
+        dir: &Path,
+        merge: Option<MergeFormat>,
+        base: &[u8],
+    ) -> (SyncPair, PathBuf) {
+        let pair = SyncPair {
+            source: dir.join("source.json"),
+            staging: dir.join("staging.json"),
+            merge,
+        };
+        fs::write(&pair.source, base).unwrap();
+        fs::write(&pair.staging, base).unwrap();
+        let base_dir = dir.join("sync-base");
+        drop(SyncState::new(&pair, Some(&base_dir)));
+        (pair, base_dir)
+    }
 
-        // Verify: staging was overwritten with source (host authority)
-        assert_eq!(fs::read(&stg).unwrap(), b"host-change");
-        // Source is unchanged
-        assert_eq!(fs::read(&src).unwrap(), b"host-change");
+    #[test]
+    fn persisted_base_detects_conflict_across_restart() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (pair, base_dir) = pair_with_persisted_base(dir.path(), None, b"base");
+        // Both sides change while no daemon runs.
+        fs::write(&pair.source, b"host").unwrap();
+        fs::write(&pair.staging, b"container").unwrap();
+
+        let mut states = [SyncState::new(&pair, Some(&base_dir))];
+        sync_all_pairs(
+            std::slice::from_ref(&pair),
+            &mut states,
+            &DaemonLog::new(None),
+        );
+
+        assert!(states[0].in_conflict);
+        assert_eq!(fs::read(&pair.source).unwrap(), b"host");
+        assert_eq!(list_conflicts(dir.path()).len(), 1);
+    }
+
+    #[test]
+    fn persisted_base_syncs_the_side_changed_during_restart() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (pair, base_dir) = pair_with_persisted_base(dir.path(), None, b"base");
+        fs::write(&pair.staging, b"container").unwrap();
+
+        let mut states = [SyncState::new(&pair, Some(&base_dir))];
+        sync_all_pairs(
+            std::slice::from_ref(&pair),
+            &mut states,
+            &DaemonLog::new(None),
+        );
+
+        assert_eq!(fs::read(&pair.source).unwrap(), b"container");
+        assert!(list_conflicts(dir.path()).is_empty());
+        let (hash, _) = load_base(&base_file(&base_dir, &pair)).unwrap();
+        assert_eq!(hash, sha256_bytes(b"container"));
+    }
+
+    #[test]
+    fn persisted_base_keeps_merge_content_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let base = br#"{"a":1,"b":1}"#;
+        let (pair, base_dir) = pair_with_persisted_base(dir.path(), Some(MergeFormat::Json), base);
+        let path = base_file(&base_dir, &pair);
+        assert_eq!(
+            load_base(&path),
+            Some((sha256_bytes(base), Some(base.to_vec())))
+        );
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        // Different keys changed on each side while down: merged after the restart.
+        fs::write(&pair.source, br#"{"a":2,"b":1}"#).unwrap();
+        fs::write(&pair.staging, br#"{"a":1,"b":2}"#).unwrap();
+        let mut states = [SyncState::new(&pair, Some(&base_dir))];
+        sync_all_pairs(
+            std::slice::from_ref(&pair),
+            &mut states,
+            &DaemonLog::new(None),
+        );
+        assert!(!states[0].in_conflict);
+        let merged: serde_json::Value =
+            serde_json::from_slice(&fs::read(&pair.source).unwrap()).unwrap();
+        assert_eq!(merged, serde_json::json!({"a": 2, "b": 2}));
+    }
+
+    #[test]
+    fn load_base_rejects_garbage() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("x.base");
+        fs::write(&path, b"not-a-hash\n").unwrap();
+        assert_eq!(load_base(&path), None);
+        assert_eq!(load_base(&dir.path().join("missing.base")), None);
+    }
+
+    #[test]
+    fn is_conflict_needs_both_sides_to_diverge_from_base() {
+        let (a, b, c) = (Some([1u8; 32]), Some([2u8; 32]), Some([3u8; 32]));
+        assert!(is_conflict(b, c, a));
+        assert!(!is_conflict(b, a, a), "only the host changed");
+        assert!(!is_conflict(a, b, a), "only the container changed");
+        assert!(!is_conflict(b, b, a), "both made the same change");
+        assert!(!is_conflict(b, c, None), "no base");
+    }
+
+    #[test]
+    fn conflict_paths_and_marker_round_trip() {
+        let source = Path::new("/home/u/.claude.json");
+        let conflict = conflict_file_path(source, 1_700_000_000);
+        assert_eq!(
+            conflict,
+            PathBuf::from("/home/u/.claude.json.conflict-20231114T221320Z")
+        );
+        let staging = Path::new("/relay/.dcx-p-a1b2c3d4-files/.claude.json");
+        let marker = conflict_marker(staging);
+        assert_eq!(
+            marker,
+            PathBuf::from("/relay/.dcx-p-a1b2c3d4-files/.claude.json.dcx-conflict")
+        );
+        assert_eq!(
+            parse_conflict_marker(&marker, &format_conflict_marker(source, &conflict)),
+            Some(Conflict {
+                source: source.to_path_buf(),
+                staging: staging.to_path_buf(),
+                conflict_file: conflict,
+            })
+        );
+        assert_eq!(parse_conflict_marker(&marker, "garbage"), None);
+    }
+
+    fn write_conflict(dir: &Path) -> Conflict {
+        let source = dir.join("settings.json");
+        let staging = dir.join("staged-settings.json");
+        let conflict_file = dir.join("settings.json.conflict-20260101T000000Z");
+        std::fs::write(&source, b"host").unwrap();
+        std::fs::write(&staging, b"container").unwrap();
+        std::fs::write(&conflict_file, b"container").unwrap();
+        std::fs::write(
+            conflict_marker(&staging),
+            format_conflict_marker(&source, &conflict_file),
+        )
+        .unwrap();
+        Conflict {
+            source,
+            staging,
+            conflict_file,
+        }
+    }
+
+    #[test]
+    fn resolve_conflict_take_host_overwrites_staging() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let c = write_conflict(dir.path());
+        resolve_conflict(&c, SyncSide::Host).unwrap();
+        assert_eq!(std::fs::read(&c.staging).unwrap(), b"host");
+        assert_eq!(std::fs::read(&c.source).unwrap(), b"host");
+        assert!(!conflict_marker(&c.staging).exists());
+        assert!(!c.conflict_file.exists());
+    }
+
+    #[test]
+    fn resolve_conflict_take_container_overwrites_source() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let c = write_conflict(dir.path());
+        resolve_conflict(&c, SyncSide::Container).unwrap();
+        assert_eq!(std::fs::read(&c.source).unwrap(), b"container");
+        assert!(!conflict_marker(&c.staging).exists());
+        assert!(!c.conflict_file.exists());
+        assert!(list_conflicts(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn conflict_matches_by_path_or_file_name() {
+        let c = Conflict {
+            source: PathBuf::from("/home/u/.gitconfig"),
+            staging: PathBuf::from("/stage/gitconfig"),
+            conflict_file: PathBuf::from("/home/u/.gitconfig.conflict-20260101T000000Z"),
+        };
+        assert!(conflict_matches(&c, None));
+        assert!(conflict_matches(&c, Some(Path::new(".gitconfig"))));
+        assert!(conflict_matches(&c, Some(Path::new("/home/u/.gitconfig"))));
+        assert!(!conflict_matches(&c, Some(Path::new(".bashrc"))));
+    }
+
+    // --- Structured merge ---
+
+    #[test]
+    fn merge_values_combines_unrelated_key_changes() {
+        let base = serde_json::json!({"a": 1, "b": {"x": 1, "y": 1}, "gone": true});
+        let ours = serde_json::json!({"a": 2, "b": {"x": 1, "y": 1}});
+        let theirs = serde_json::json!({"a": 1, "b": {"x": 1, "y": 2}, "gone": true, "new": [1]});
+        let merged = merge_values(Some(&base), Some(&ours), Some(&theirs), "").unwrap();
+        assert_eq!(
+            merged,
+            Some(serde_json::json!({"a": 2, "b": {"x": 1, "y": 2}, "new": [1]}))
+        );
+    }
+
+    #[test]
+    fn merge_values_reports_key_changed_on_both_sides() {
+        let base = serde_json::json!({"b": {"x": 1}, "list": [1]});
+        let ours = serde_json::json!({"b": {"x": 2}, "list": [1]});
+        let theirs = serde_json::json!({"b": {"x": 3}, "list": [1]});
+        let err = merge_values(Some(&base), Some(&ours), Some(&theirs), "").unwrap_err();
+        assert_eq!(err, "`b.x` changed on both sides");
+
+        // Arrays are merged as whole values.
+        let ours = serde_json::json!({"b": {"x": 1}, "list": [1, 2]});
+        let theirs = serde_json::json!({"b": {"x": 1}, "list": [1, 3]});
+        assert!(merge_values(Some(&base), Some(&ours), Some(&theirs), "").is_err());
+    }
+
+    #[test]
+    fn merge_format_parse() {
+        assert_eq!(MergeFormat::parse("json"), Ok(MergeFormat::Json));
+        assert_eq!(MergeFormat::parse("yaml"), Ok(MergeFormat::Yaml));
+        assert_eq!(MergeFormat::parse("toml"), Ok(MergeFormat::Toml));
+        assert!(MergeFormat::parse("xml").unwrap_err().contains("xml"));
+    }
+
+    #[test]
+    fn merge_documents_yaml_and_toml() {
+        let merged = merge_documents(
+            MergeFormat::Yaml,
+            b"a: 1\nb: 1\n",
+            b"a: 2\nb: 1\n",
+            b"a: 1\nb: 2\n",
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(merged).unwrap(), "a: 2\nb: 2\n");
+
+        let merged = merge_documents(
+            MergeFormat::Toml,
+            b"[t]\nwhen = 1979-05-27T07:32:00Z\nx = 1\n",
+            b"[t]\nwhen = 1979-05-27T07:32:00Z\nx = 2\n",
+            b"[t]\nwhen = 2000-01-01T00:00:00Z\nx = 1\n",
+        )
+        .unwrap();
+        let table: toml::Table = toml::from_str(std::str::from_utf8(&merged).unwrap()).unwrap();
+        assert_eq!(table["t"]["x"].as_integer(), Some(2));
+        assert!(table["t"]["when"].is_datetime(), "got: {table:?}");
+    }
+
+    #[test]
+    fn merge_documents_rejects_unparseable_side() {
+        let err = merge_documents(MergeFormat::Json, b"{}", b"{\"a\":1}", b"{oops").unwrap_err();
+        assert!(err.starts_with("container:"), "got: {err}");
+    }
+
+    #[test]
+    fn sync_all_pairs_merges_concurrent_json_edits() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let src = dir.path().join("source.json");
+        let stg = dir.path().join("staging.json");
+        fs::write(&src, br#"{"theme":"dark","projects":{}}"#).unwrap();
+        fs::write(&stg, br#"{"theme":"dark","projects":{}}"#).unwrap();
+        let pair = SyncPair {
+            source: src.clone(),
+            staging: stg.clone(),
+            merge: Some(MergeFormat::Json),
+        };
+        let mut states = [SyncState::new(&pair, None)];
+
+        fs::write(&src, br#"{"theme":"light","projects":{}}"#).unwrap();
+        fs::write(&stg, br#"{"theme":"dark","projects":{"/w":{}}}"#).unwrap();
+        sync_all_pairs(
+            std::slice::from_ref(&pair),
+            &mut states,
+            &DaemonLog::new(None),
+        );
+
+        let expected = serde_json::json!({"theme": "light", "projects": {"/w": {}}});
+        let host: serde_json::Value = serde_json::from_slice(&fs::read(&src).unwrap()).unwrap();
+        assert_eq!(host, expected);
+        assert_eq!(fs::read(&src).unwrap(), fs::read(&stg).unwrap());
+        assert!(list_conflicts(dir.path()).is_empty());
+
+        // The merged document is the new base: a second round of edits merges again.
+        fs::write(&src, br#"{"theme":"light","projects":{"/w":{}},"a":1}"#).unwrap();
+        fs::write(&stg, br#"{"theme":"light","projects":{"/w":{"b":2}}}"#).unwrap();
+        sync_all_pairs(&[pair], &mut states, &DaemonLog::new(None));
+        let host: serde_json::Value = serde_json::from_slice(&fs::read(&src).unwrap()).unwrap();
+        assert_eq!(
+            host,
+            serde_json::json!({"theme": "light", "projects": {"/w": {"b": 2}}, "a": 1})
+        );
+    }
+
+    #[test]
+    fn merge_keeps_owner_only_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let src = dir.path().join("source.json");
+        let stg = dir.path().join("staging.json");
+        fs::write(&src, br#"{"a":1,"b":1}"#).unwrap();
+        fs::write(&stg, br#"{"a":1,"b":1}"#).unwrap();
+        fs::set_permissions(&src, fs::Permissions::from_mode(0o600)).unwrap();
+        let pair = SyncPair {
+            source: src.clone(),
+            staging: stg.clone(),
+            merge: Some(MergeFormat::Json),
+        };
+        let mut states = [SyncState::new(&pair, None)];
+
+        fs::write(&src, br#"{"a":2,"b":1}"#).unwrap();
+        fs::write(&stg, br#"{"a":1,"b":2}"#).unwrap();
+        sync_all_pairs(&[pair], &mut states, &DaemonLog::new(None));
+
+        assert_eq!(fs::read(&src).unwrap(), fs::read(&stg).unwrap());
+        let mode = fs::metadata(&src).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o600);
+    }
+
+    #[test]
+    fn merge_pair_skips_when_host_changed_during_pass() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let src = dir.path().join("source.json");
+        let stg = dir.path().join("staging.json");
+        fs::write(&src, br#"{"a":2,"b":1}"#).unwrap();
+        fs::write(&stg, br#"{"a":1,"b":2}"#).unwrap();
+        let pair = SyncPair {
+            source: src.clone(),
+            staging: stg.clone(),
+            merge: Some(MergeFormat::Json),
+        };
+        let base = br#"{"a":1,"b":1}"#;
+
+        // The pass saw an older host version: nothing is written.
+        let stale = Some(sha256_bytes(br#"{"a":1,"b":1}"#));
+        let result = merge_pair(&pair, MergeFormat::Json, Some(base), stale).unwrap();
+        assert_eq!(result, None);
+        assert_eq!(fs::read(&src).unwrap(), br#"{"a":2,"b":1}"#);
+        assert_eq!(fs::read(&stg).unwrap(), br#"{"a":1,"b":2}"#);
+
+        let current = sha256_file(&src);
+        let merged = merge_pair(&pair, MergeFormat::Json, Some(base), current).unwrap();
+        assert_eq!(merged, sha256_file(&src));
+        assert_eq!(fs::read(&src).unwrap(), fs::read(&stg).unwrap());
+    }
+
+    #[test]
+    fn atomic_write_creates_missing_file_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let dst = dir.path().join("new.json");
+        atomic_write(&dst, b"{}").unwrap();
+        assert_eq!(fs::read(&dst).unwrap(), b"{}");
+        let mode = fs::metadata(&dst).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o600);
+    }
+
+    #[test]
+    fn sync_all_pairs_falls_back_to_conflict_when_merge_fails() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let src = dir.path().join("source.json");
+        let stg_dir = tempfile::TempDir::new().unwrap();
+        let stg = stg_dir.path().join("source.json");
+        fs::write(&src, br#"{"theme":"dark"}"#).unwrap();
+        fs::write(&stg, br#"{"theme":"dark"}"#).unwrap();
+        let pair = SyncPair {
+            source: src.clone(),
+            staging: stg.clone(),
+            merge: Some(MergeFormat::Json),
+        };
+        let mut states = [SyncState::new(&pair, None)];
+
+        fs::write(&src, br#"{"theme":"light"}"#).unwrap();
+        fs::write(&stg, br#"{"theme":"blue"}"#).unwrap();
+        sync_all_pairs(&[pair], &mut states, &DaemonLog::new(None));
+
+        assert_eq!(fs::read(&src).unwrap(), br#"{"theme":"light"}"#);
+        assert_eq!(list_conflicts(stg_dir.path()).len(), 1);
+        assert!(states[0].in_conflict);
+    }
+
+    // --- Daemon log ---
+
+    #[test]
+    fn log_line_round_trip() {
+        let line = format_log_line(1_700_000_000, "ERROR", "sync: a -> b: denied");
+        assert_eq!(line, "2023-11-14T22:13:20Z ERROR sync: a -> b: denied");
+        assert_eq!(
+            parse_log_line(&line),
+            Some(LogLine {
+                timestamp: 1_700_000_000,
+                level: "ERROR".to_string(),
+                message: "sync: a -> b: denied".to_string(),
+            })
+        );
+        assert_eq!(parse_log_line("garbage"), None);
+    }
+
+    #[test]
+    fn last_error_prefers_current_log_then_rotated() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let log = dir.path().join("sync-daemon.log");
+        fs::write(
+            rotated_log_path(&log),
+            format!("{}\n", format_log_line(1, "ERROR", "old")),
+        )
+        .unwrap();
+        assert_eq!(last_error(&log).unwrap().message, "old");
+
+        fs::write(
+            &log,
+            format!(
+                "{}\n{}\n{}\n",
+                format_log_line(2, "ERROR", "first"),
+                format_log_line(3, "ERROR", "second"),
+                format_log_line(4, "INFO", "sync: a -> b"),
+            ),
+        )
+        .unwrap();
+        assert_eq!(last_error(&log).unwrap().message, "second");
+    }
+
+    #[test]
+    fn daemon_log_rotates_when_full() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("sync-daemon.log");
+        fs::write(&path, vec![b'x'; MAX_LOG_BYTES as usize]).unwrap();
+
+        DaemonLog::new(Some(path.clone())).info("after rotation");
+
+        assert_eq!(
+            fs::metadata(rotated_log_path(&path)).unwrap().len(),
+            MAX_LOG_BYTES
+        );
+        let current = fs::read_to_string(&path).unwrap();
+        assert!(current.ends_with("INFO after rotation\n"), "got: {current}");
+    }
+
+    #[test]
+    fn sync_rejection_is_logged_as_error() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let src = dir.path().join("source.json");
+        let stg = dir.path().join("staging.json");
+        let log_path = dir.path().join("sync-daemon.log");
+        fs::write(&src, b"tokens").unwrap();
+        fs::write(&stg, b"tokens").unwrap();
+        let state = SyncState {
+            last_source_hash: sha256_file(&src),
+            last_staging_hash: sha256_file(&stg),
+            last_synced_hash: sha256_file(&src),
+            last_synced_content: None,
+            in_conflict: false,
+            base_file: None,
+        };
+        fs::write(&stg, b"").unwrap();
+
+        sync_all_pairs(
+            &[SyncPair {
+                source: src,
+                staging: stg,
+                merge: None,
+            }],
+            &mut [state],
+            &DaemonLog::new(Some(log_path.clone())),
+        );
+
+        let err = last_error(&log_path).unwrap();
+        assert!(
+            err.message.contains("rejecting staging->source"),
+            "got: {err:?}"
+        );
     }
 }
@@ -6,17 +6,27 @@ use std::process;
 
 use std::sync::atomic::Ordering;
 
+use serde::Serialize;
+
 use crate::cmd;
 use crate::colima;
+use crate::daemon;
 use crate::dcx_config;
 use crate::docker;
+use crate::drift;
+use crate::duration::parse_duration;
 use crate::exit_codes;
+use crate::gc;
+use crate::lifetime;
 use crate::mount_table;
 use crate::naming::{is_dcx_managed_path, mount_name, relay_dir};
-use crate::network_mode::NetworkMode;
+use crate::network_mode::{self, NetworkMode, NetworkPhase, NetworkPolicy};
+use crate::output;
 use crate::platform;
 use crate::progress;
+use crate::run_log;
 use crate::signals;
+use crate::state;
 use crate::workspace::{find_devcontainer_config, resolve_workspace};
 
 // ── RAII TempFile ─────────────────────────────────────────────────────────
@@ -58,7 +68,7 @@ fn json_escape(s: &str) -> String {
 
 /// Parse the remoteUser field from a devcontainer.json JSON string.
 /// Returns None if the field is not found or malformed.
-fn parse_remote_user(json: &str) -> Option<String> {
+pub(crate) fn parse_remote_user(json: &str) -> Option<String> {
     // Strip JSONC comments for safer parsing
     let clean = docker::strip_jsonc_comments(json);
 
@@ -101,7 +111,7 @@ fn build_mount_entry(source: &Path, target: &Path, writable: bool) -> String {
 /// Build environment variable overrides for well-known apps (git).
 /// Returns a vec of (key, value) pairs to inject into containerEnv.
 /// Note: ~/.claude is now mounted at the container user's home, so CLAUDE_CONFIG_DIR is not needed.
-fn build_env_overrides(
+pub fn build_env_overrides(
     mounts: &[crate::colima::ColimaMount],
     home: &Path,
 ) -> Vec<(String, String)> {
@@ -270,6 +280,12 @@ pub fn env_key_in_container_env(key: &str, base_json: &str) -> bool {
 /// If the array exists, appends before `]`. If absent, adds `"mounts": [...]` before `}`.
 /// Entries are properly quoted as JSON strings.
 fn inject_mounts(json: &str, entries: &[String]) -> String {
+    inject_array_entries(json, "mounts", entries)
+}
+
+/// Inject string entries into the top-level JSON array named `key`.
+/// If the array exists, appends before `]`. If absent, adds `"<key>": [...]` before `}`.
+fn inject_array_entries(json: &str, key: &str, entries: &[String]) -> String {
     if entries.is_empty() {
         return json.to_string();
     }
@@ -280,11 +296,11 @@ fn inject_mounts(json: &str, entries: &[String]) -> String {
         .map(|e| format!("\"{}\"", json_escape(e)))
         .collect();
 
-    // Try to find an existing "mounts" array
-    if let Some(mounts_pos) = json.find("\"mounts\"")
-        && let Some(bracket_pos) = json[mounts_pos..].find('[')
+    // Try to find an existing array for `key`
+    if let Some(key_pos) = json.find(&format!("\"{key}\""))
+        && let Some(bracket_pos) = json[key_pos..].find('[')
     {
-        let bracket_abs = mounts_pos + bracket_pos;
+        let bracket_abs = key_pos + bracket_pos;
         if let Some(close_pos) = find_closing_bracket(json, bracket_abs) {
             let array_content = json[bracket_abs + 1..close_pos].trim();
             let is_empty = array_content.is_empty();
@@ -296,7 +312,7 @@ fn inject_mounts(json: &str, entries: &[String]) -> String {
         }
     }
 
-    // No "mounts" array found — add one before the outermost closing `}`
+    // No array found — add one before the outermost closing `}`
     if let Some(last_brace) = json.rfind('}') {
         let before = json[..last_brace].trim_end();
         let needs_comma = !before.is_empty() && !before.ends_with(',') && before != "{";
@@ -305,7 +321,7 @@ fn inject_mounts(json: &str, entries: &[String]) -> String {
         let mut result = json.to_string();
         result.insert_str(
             last_brace,
-            &format!("{}\n  \"mounts\": [{}]\n", sep, entries_str),
+            &format!("{}\n  \"{}\": [{}]\n", sep, key, entries_str),
         );
         return result;
     }
@@ -437,19 +453,12 @@ pub fn tilde_path(path: &Path, home: &Path) -> String {
     }
 }
 
-/// Format the `--dry-run` plan message for `dcx up`.
-pub fn dry_run_plan(
-    workspace: &Path,
-    mount_point: &Path,
-    home: &Path,
-    config: Option<&Path>,
-    no_cache: bool,
-) -> String {
-    let tilde_mount = tilde_path(mount_point, home);
+/// Arguments of the `devcontainer up` invocation for a dry-run plan.
+fn dry_run_args(workspace_folder: String, config: Option<&Path>, no_cache: bool) -> Vec<String> {
     let mut args = vec![
         "up".to_string(),
         "--workspace-folder".to_string(),
-        tilde_mount.clone(),
+        workspace_folder,
     ];
     if let Some(cfg) = config {
         args.push("--config".to_string());
@@ -458,6 +467,19 @@ pub fn dry_run_plan(
     if no_cache {
         args.push("--build-no-cache".to_string());
     }
+    args
+}
+
+/// Format the `--dry-run` plan message for `dcx up`.
+pub fn dry_run_plan(
+    workspace: &Path,
+    mount_point: &Path,
+    home: &Path,
+    config: Option<&Path>,
+    no_cache: bool,
+) -> String {
+    let tilde_mount = tilde_path(mount_point, home);
+    let args = dry_run_args(tilde_mount.clone(), config, no_cache);
     let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
     let devcontainer_cmd = cmd::display_cmd("devcontainer", &args_ref);
     format!(
@@ -466,6 +488,40 @@ pub fn dry_run_plan(
     )
 }
 
+/// The `dcx up --dry-run` plan as reported by `--output json`.
+#[derive(Serialize)]
+pub struct UpPlan {
+    /// Absolute workspace path on the host.
+    pub workspace: String,
+    /// Mount name (e.g. `dcx-myproject-a1b2c3d4`).
+    pub mount_name: String,
+    /// Absolute mount point path.
+    pub mount_point: String,
+    /// The `devcontainer up` command that would run, program first.
+    pub command: Vec<String>,
+}
+
+/// Build the structured `--dry-run` plan for `dcx up`.
+pub fn up_plan(
+    workspace: &Path,
+    mount_point: &Path,
+    config: Option<&Path>,
+    no_cache: bool,
+) -> UpPlan {
+    let mount_point_str = mount_point.to_string_lossy().into_owned();
+    let mut command = vec!["devcontainer".to_string()];
+    command.extend(dry_run_args(mount_point_str.clone(), config, no_cache));
+    UpPlan {
+        workspace: workspace.to_string_lossy().into_owned(),
+        mount_name: mount_point
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        mount_point: mount_point_str,
+        command,
+    }
+}
+
 /// Format the hash-collision error message for `dcx up`.
 pub fn collision_error(workspace: &Path, found_source: &str, hash: &str) -> String {
     format!(
@@ -479,6 +535,101 @@ pub fn collision_error(workspace: &Path, found_source: &str, hash: &str) -> Stri
     )
 }
 
+/// Locate dcx_config.yaml for a workspace.
+///
+/// Config discovery: 4-step order per spec:
+/// 1. --config-dir (explicit, already resolved to `config_dir`)
+/// 2. $DCX_DEVCONTAINER_CONFIG_DIR_PATH (merged with --config-dir at call site in main.rs)
+/// 3. Alongside the auto-detected devcontainer.json (if no explicit config dir)
+/// 4. Workspace root fallback (dcx_config::find_dcx_config)
+pub fn find_dcx_config_path(workspace: &Path, config_dir: Option<&Path>) -> Option<PathBuf> {
+    if let Some(dir) = config_dir {
+        // Steps 1 & 2: explicit config dir (--config-dir or env var)
+        Some(dir.join("dcx_config.yaml"))
+    } else if let Some(json_path) = find_devcontainer_config(workspace) {
+        // Step 3: check alongside the auto-detected devcontainer.json
+        json_path.parent().map(|p| p.join("dcx_config.yaml"))
+    } else {
+        // Step 4: workspace root fallback
+        dcx_config::find_dcx_config(workspace)
+    }
+}
+
+/// Parse a network mode from dcx_config.yaml, warning and falling back to the default
+/// when the value is invalid.
+fn parse_yaml_mode(value: &str, warnings: &mut Vec<String>) -> NetworkMode {
+    value.parse::<NetworkMode>().unwrap_or_else(|e| {
+        warnings.push(format!("Warning: dcx_config.yaml up.network: {}", e));
+        NetworkMode::default()
+    })
+}
+
+/// Validate `up.network.build`. dcx cannot restrict network access while `devcontainer up`
+/// builds the image, so only `open` is accepted rather than a mode that is silently not
+/// enforced.
+pub fn check_build_network(phases: Option<&dcx_config::NetworkPhases>) -> Result<(), String> {
+    match phases.and_then(|p| p.build.as_deref()) {
+        Some(v) if v.parse::<NetworkMode>() != Ok(NetworkMode::Open) => Err(format!(
+            "up.network.build: '{v}' is not supported. dcx cannot restrict network access \
+             during the image build; use `open` (or omit it) and restrict the create and run \
+             phases instead."
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Resolve the network policy from dcx_config.yaml and `--network`.
+///
+/// YAML wins over CLI. A per-phase `up.network` map yields a phased policy (an omitted
+/// build phase is `open`, see `check_build_network`; other omitted phases use the default
+/// mode); a single value or `--network` yields a uniform policy.
+/// Returns the policy plus any warnings to print.
+pub fn resolve_network_policy(
+    yaml_mode: Option<&str>,
+    yaml_phases: Option<&dcx_config::NetworkPhases>,
+    cli_network: Option<NetworkMode>,
+) -> (NetworkPolicy, Vec<String>) {
+    let mut warnings = Vec::new();
+    let yaml_policy = if let Some(phases) = yaml_phases {
+        let mut phase_mode = |v: &Option<String>| {
+            v.as_deref()
+                .map(|m| parse_yaml_mode(m, &mut warnings))
+                .unwrap_or_default()
+        };
+        Some(NetworkPolicy {
+            build: match phases.build {
+                Some(_) => phase_mode(&phases.build),
+                None => NetworkMode::Open,
+            },
+            create: phase_mode(&phases.create),
+            run: phase_mode(&phases.run),
+        })
+    } else {
+        yaml_mode.map(|m| NetworkPolicy::uniform(parse_yaml_mode(m, &mut warnings)))
+    };
+
+    let policy = match (yaml_policy, cli_network) {
+        (Some(yaml), Some(cli_val)) => {
+            if yaml != NetworkPolicy::uniform(cli_val) {
+                let shown = if yaml.is_phased() {
+                    yaml.to_label()
+                } else {
+                    yaml.run.to_string()
+                };
+                warnings.push(format!(
+                    "Warning: up.network from dcx_config.yaml ({}) overrides --network {}",
+                    shown, cli_val
+                ));
+            }
+            yaml
+        }
+        (Some(yaml), None) => yaml,
+        (None, Some(cli_val)) => NetworkPolicy::uniform(cli_val),
+        (None, None) => NetworkPolicy::default(),
+    };
+    (policy, warnings)
+}
+
 // ── OS helpers ────────────────────────────────────────────────────────────────
 
 /// Return the UID of the file/directory at `path`, or `None` on error.
@@ -548,12 +699,84 @@ fn confirm_non_owned(workspace: &Path, owner_uid: u32, current_uid: u32) -> bool
     matches!(line.trim().to_ascii_lowercase().as_str(), "y" | "yes")
 }
 
+/// Ask whether to recreate a container whose configuration changed.
+///
+/// Returns `true` if the user confirms, `false` if they decline or input fails.
+fn confirm_recreate(changed: &[String]) -> bool {
+    eprintln!(
+        "Configuration changed since the container was created: {}",
+        changed.join(", ")
+    );
+    eprint!("Recreate the container? [y/N] ");
+    let _ = io::stderr().flush();
+    let mut line = String::new();
+    if io::stdin().lock().read_line(&mut line).is_err() {
+        return false;
+    }
+    matches!(line.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+}
+
+/// Re-apply the container's run-phase network mode if the container was (re)started since
+/// dcx last applied it. Firewall rules do not survive a restart, so a container restarted
+/// outside dcx (`docker restart`, a VM reboot with a restart policy) would otherwise run
+/// with the create-phase policy.
+///
+/// Fails closed: if the mode cannot be applied the container is stopped and `Err` says so.
+/// Returns the re-applied mode, or `None` if it was already in force (or none is recorded).
+pub fn ensure_network_mode(
+    home: &Path,
+    mount_point: &Path,
+    container_id: &str,
+) -> Result<Option<NetworkMode>, String> {
+    let name = mount_point
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let started = docker::container_started_at(container_id);
+    if started.is_some() && state::network_applied(home, &name) == started {
+        return Ok(None);
+    }
+    let Some(mode) = docker::read_runtime_network_mode(container_id) else {
+        return Ok(None);
+    };
+    if let Err(e) = docker::apply_network_mode(container_id, mode) {
+        let mut msg = format!("{e}\nStopping container: network mode could not be applied.");
+        if let Err(stop) = docker::stop_container(mount_point) {
+            msg.push_str(&format!("\n{stop}"));
+        }
+        state::record_event(
+            home,
+            &name,
+            &format!("network: container stopped, {mode} could not be applied"),
+        );
+        return Err(msg);
+    }
+    if let Some(t) = started {
+        state::record_network_applied(home, &name, t);
+    }
+    state::record_event(
+        home,
+        &name,
+        &format!("network: {mode} applied after a restart"),
+    );
+    Ok(Some(mode))
+}
+
 // ── Mount helpers ─────────────────────────────────────────────────────────────
 
+/// Stop the workspace container and remove `container_ids`.
+fn remove_containers(mount_point: &Path, container_ids: &[String]) -> Result<(), String> {
+    docker::stop_container(mount_point)?;
+    for id in container_ids {
+        docker::remove_container(id)?;
+    }
+    Ok(())
+}
+
 /// Create `mount_point` and bind-mount `workspace` into it with `bindfs`.
 ///
 /// On bindfs failure the directory is removed to avoid leaving an empty stray dir.
-fn do_mount(workspace: &Path, mount_point: &Path) -> Result<(), String> {
+pub fn do_mount(workspace: &Path, mount_point: &Path) -> Result<(), String> {
     std::fs::create_dir_all(mount_point)
         .map_err(|e| format!("Failed to create {}: {e}", mount_point.display()))?;
     let out = cmd::run_capture(
@@ -576,7 +799,7 @@ fn do_mount(workspace: &Path, mount_point: &Path) -> Result<(), String> {
 }
 
 /// Unmount `mount_point` using the platform-appropriate unmount command.
-fn do_unmount(mount_point: &Path) -> Result<(), String> {
+pub fn do_unmount(mount_point: &Path) -> Result<(), String> {
     let prog = platform::unmount_prog();
     let args = platform::unmount_args(mount_point);
     let args_str: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
@@ -594,40 +817,83 @@ fn do_unmount(mount_point: &Path) -> Result<(), String> {
 /// Unmount and remove `mount_point`, then print "Mount rolled back." to stderr.
 ///
 /// Errors during rollback are reported but do not abort the rollback.
-fn rollback(mount_point: &Path) {
+fn rollback(home: &Path, mount_point: &Path) {
     if let Err(e) = do_unmount(mount_point) {
-        eprintln!("Warning: rollback unmount failed: {e}");
+        run_log::warn(&format!("Warning: rollback unmount failed: {e}"));
     }
     if let Err(e) = std::fs::remove_dir(mount_point) {
-        eprintln!("Warning: rollback rmdir failed: {e}");
+        run_log::warn(&format!("Warning: rollback rmdir failed: {e}"));
     }
-    // Kill sync daemon before removing staging dir (prevents orphaned daemon).
+    // Stop syncing before removing staging dir.
+    daemon::stop_sync(home, mount_point);
     let staging = staging_dir(mount_point);
-    let pid_file = staging.join(".sync-daemon.pid");
-    if let Ok(pid_str) = std::fs::read_to_string(&pid_file) {
-        let pid = pid_str.trim();
-        if !pid.is_empty() {
-            let _ = std::process::Command::new("kill")
-                .arg("-TERM")
-                .arg(pid)
-                .status();
-            std::thread::sleep(std::time::Duration::from_millis(200));
-        }
-    }
     // Clean up the staging dir that may have been populated before devcontainer up failed.
     if staging.exists()
         && let Err(e) = std::fs::remove_dir_all(&staging)
     {
-        eprintln!("Warning: rollback staging cleanup failed: {e}");
+        run_log::warn(&format!("Warning: rollback staging cleanup failed: {e}"));
     }
-    eprintln!("Mount rolled back.");
+    run_log::error("Mount rolled back.");
+}
+
+// ── Remembered options ────────────────────────────────────────────────────────
+
+/// Options from the last successful `dcx up`, replayed by `dcx shell` when the
+/// workspace needs to be brought up again.
+#[derive(Debug, Clone, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+pub struct RememberedUpOptions {
+    pub config_dir: Option<PathBuf>,
+    pub extra_files: Vec<PathBuf>,
+    pub network: Option<String>,
+    pub yes: bool,
+    /// `--ttl` of the `dcx up` that set the workspace's current deadline.
+    pub ttl: Option<String>,
+}
+
+impl RememberedUpOptions {
+    /// Convert back into `UpOptions` for `workspace`.
+    pub fn into_up_options(self, workspace: PathBuf) -> UpOptions {
+        UpOptions {
+            workspace_folder: Some(workspace),
+            config_dir: self.config_dir,
+            extra_files: self.extra_files,
+            dry_run: false,
+            yes: self.yes,
+            cli_network: self.network.and_then(|n| n.parse().ok()),
+            no_cache: false,
+            ttl: self.ttl,
+        }
+    }
+}
+
+/// Path of the remembered `dcx up` options for a workspace.
+pub fn remembered_options_path(home: &Path, mount_name: &str) -> PathBuf {
+    state::state_dir(home, mount_name).join("up-options.json")
+}
+
+/// Load remembered options; `None` if `dcx up` never succeeded for this workspace.
+pub fn load_remembered_options(home: &Path, mount_name: &str) -> Option<RememberedUpOptions> {
+    let content = std::fs::read_to_string(remembered_options_path(home, mount_name)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Persist options after a successful `dcx up`.
+pub fn save_remembered_options(
+    home: &Path,
+    mount_name: &str,
+    opts: &RememberedUpOptions,
+) -> Result<(), String> {
+    let path = remembered_options_path(home, mount_name);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    let json = serde_json::to_string_pretty(opts).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {}: {e}", path.display()))
 }
 
 // ── Entry point ───────────────────────────────────────────────────────────────
 
-/// Run `dcx up`.
-///
-/// Returns the exit code that `main` should pass to `std::process::exit`.
 /// Options for `dcx up`.
 pub struct UpOptions {
     pub workspace_folder: Option<PathBuf>,
@@ -637,9 +903,29 @@ pub struct UpOptions {
     pub yes: bool,
     pub cli_network: Option<NetworkMode>,
     pub no_cache: bool,
+    /// `--ttl`: bring the workspace down this long after `dcx up` (unparsed).
+    pub ttl: Option<String>,
 }
 
+/// Run `dcx up`.
+///
+/// Output of the run (progress steps and `devcontainer up`) is also written to a
+/// per-workspace log; on failure its path is printed. See `dcx logs --up`.
+/// Returns the exit code that `main` should pass to `std::process::exit`.
 pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
+    let code = run_up_logged(home, opts);
+    if let Some(path) = run_log::finish(code)
+        && code != exit_codes::SUCCESS
+    {
+        eprintln!(
+            "Full output of this run: {} (or `dcx logs --up`)",
+            tilde_path(&path, home)
+        );
+    }
+    code
+}
+
+fn run_up_logged(home: &Path, opts: UpOptions) -> i32 {
     let UpOptions {
         workspace_folder,
         config_dir,
@@ -648,6 +934,7 @@ pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
         yes,
         cli_network,
         no_cache,
+        ttl,
     } = opts;
 
     // Install SIGINT handler before any mount operations so Ctrl+C triggers rollback
@@ -673,7 +960,7 @@ pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
         workspace.display()
     ));
 
-    // 2b. Resolve --config-dir to an absolute path and locate devcontainer.json inside it.
+    // 3. Resolve --config-dir to an absolute path and locate devcontainer.json inside it.
     let (devcontainer_config, dcx_config_dir): (Option<PathBuf>, Option<PathBuf>) =
         if let Some(dir) = config_dir {
             let abs_dir = if dir.is_absolute() {
@@ -701,69 +988,77 @@ pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
             (None, None)
         };
 
-    // 2c. Merge network and yes settings from dcx_config.yaml.
-    // Config discovery: 4-step order per spec:
-    // 1. --config-dir (explicit, already resolved above to dcx_config_dir)
-    // 2. $DCX_DEVCONTAINER_CONFIG_DIR_PATH (merged with --config-dir at call site in main.rs)
-    // 3. Alongside the auto-detected devcontainer.json (if no explicit config dir)
-    // 4. Workspace root fallback (dcx_config::find_dcx_config)
-    let cfg_path = if let Some(ref dir) = dcx_config_dir {
-        // Steps 1 & 2: explicit config dir (--config-dir or env var)
-        Some(dir.join("dcx_config.yaml"))
-    } else {
-        // No explicit config dir: try step 3 (alongside auto-detected devcontainer.json)
-        // then step 4 (workspace fallback)
-        let auto_detected_json = find_devcontainer_config(&workspace);
-        if let Some(json_path) = auto_detected_json {
-            // Step 3: check alongside the auto-detected devcontainer.json
-            json_path.parent().map(|p| p.join("dcx_config.yaml"))
-        } else {
-            // Step 4: workspace root fallback
-            dcx_config::find_dcx_config(&workspace)
-        }
-    };
+    // 4. Merge network and yes settings from dcx_config.yaml.
+    let cfg_path = find_dcx_config_path(&workspace, dcx_config_dir.as_deref());
     let cfg = cfg_path
         .map(|p| dcx_config::read_dcx_config(&p))
         .unwrap_or_default();
     let up_cfg = &cfg.up;
 
     // Merge network: YAML wins over CLI, with warning if both present and different.
-    let final_network: NetworkMode = match (up_cfg.network.as_deref(), cli_network) {
-        (Some(yaml_val), Some(cli_val)) => {
-            let yaml_mode = match yaml_val.parse::<NetworkMode>() {
-                Ok(mode) => mode,
-                Err(e) => {
-                    eprintln!("Warning: dcx_config.yaml up.network: {}", e);
-                    NetworkMode::default()
-                }
-            };
-            if yaml_mode != cli_val {
-                eprintln!(
-                    "Warning: up.network from dcx_config.yaml ({}) overrides --network {}",
-                    yaml_mode, cli_val
-                );
-            }
-            yaml_mode
-        }
-        (Some(yaml_val), None) => match yaml_val.parse::<NetworkMode>() {
-            Ok(mode) => mode,
-            Err(e) => {
-                eprintln!("Warning: dcx_config.yaml up.network: {}", e);
-                NetworkMode::default()
-            }
-        },
-        (None, Some(cli_val)) => cli_val,
-        (None, None) => NetworkMode::default(),
-    };
+    // The create-phase mode is what the container starts with (label + postStartCommand);
+    // the build-phase mode (always `open` for phased policies) is exported for configs that
+    // consume it in build args.
+    if let Err(e) = check_build_network(up_cfg.network_phases.as_ref()) {
+        eprintln!("{e}");
+        return exit_codes::USAGE_ERROR;
+    }
+    let (network_policy, network_warnings) = resolve_network_policy(
+        up_cfg.network.as_deref(),
+        up_cfg.network_phases.as_ref(),
+        cli_network,
+    );
+    for w in &network_warnings {
+        eprintln!("{w}");
+    }
     // SAFETY: single-threaded at this point; set before spawning devcontainer
     unsafe {
-        std::env::set_var("DCX_NETWORK_MODE", final_network.to_string());
+        std::env::set_var("DCX_NETWORK_MODE", network_policy.create.to_string());
+        std::env::set_var("DCX_BUILD_NETWORK_MODE", network_policy.build.to_string());
     }
 
     // Merge yes: OR-combine (true from either source).
     let final_yes = yes || up_cfg.yes.unwrap_or(false);
 
-    // 3. Recursive mount guard — block nested dcx mounts.
+    // 5. Parse --ttl and up.idle_timeout (applied by the workspace supervisor, step 23).
+    let ttl_secs = match ttl.as_deref().map(parse_duration).transpose() {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("--ttl: {e}");
+            return exit_codes::USAGE_ERROR;
+        }
+    };
+    let idle_timeout = match up_cfg
+        .idle_timeout
+        .as_deref()
+        .map(parse_duration)
+        .transpose()
+    {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("up.idle_timeout: {e}");
+            return exit_codes::USAGE_ERROR;
+        }
+    };
+
+    // 6. Validate up.files[].merge (applied by the sync daemon).
+    for file_mount in &up_cfg.files {
+        let Some(merge) = file_mount.merge.as_deref() else {
+            continue;
+        };
+        if let Err(e) = crate::sync::MergeFormat::parse(merge) {
+            eprintln!("up.files[].merge ({}): {e}", file_mount.path);
+            return exit_codes::USAGE_ERROR;
+        }
+        if !file_mount.sync {
+            eprintln!(
+                "Warning: up.files[].merge has no effect without sync: true ({})",
+                file_mount.path
+            );
+        }
+    }
+
+    // 7. Recursive mount guard — block nested dcx mounts.
     let relay = relay_dir(home);
     if is_dcx_managed_path(&workspace, &relay) {
         eprintln!(
@@ -773,7 +1068,7 @@ pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
         return exit_codes::USAGE_ERROR;
     }
 
-    // 4. Require a devcontainer configuration.
+    // 8. Require a devcontainer configuration.
     if devcontainer_config.is_none() && find_devcontainer_config(&workspace).is_none() {
         eprintln!(
             "No devcontainer configuration found in {}.",
@@ -782,11 +1077,20 @@ pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
         return exit_codes::USAGE_ERROR;
     }
 
-    // 5. Compute mount point.
+    // 9. Compute mount point.
     let name = mount_name(&workspace);
     let mount_point = relay.join(&name);
 
-    // 6. Dry-run: print plan and exit without side effects.
+    // 10. Dry-run: print plan and exit without side effects.
+    if dry_run && output::is_structured() {
+        output::emit(&up_plan(
+            &workspace,
+            &mount_point,
+            devcontainer_config.as_deref(),
+            no_cache,
+        ));
+        return exit_codes::SUCCESS;
+    }
     if dry_run {
         println!(
             "{}",
@@ -801,15 +1105,18 @@ pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
         return exit_codes::SUCCESS;
     }
 
-    // 7. Auto-create relay directory.
+    // 11. Tee the rest of this run to ~/.local/state/dcx/<mount>/up-logs/<run>.log.
+    run_log::start(home, &name, &workspace);
+
+    // 12. Auto-create relay directory.
     if !relay.exists()
         && let Err(e) = std::fs::create_dir_all(&relay)
     {
-        eprintln!("Failed to create {}: {e}", relay.display());
+        run_log::error(&format!("Failed to create {}: {e}", relay.display()));
         return exit_codes::RUNTIME_ERROR;
     }
 
-    // 9. Mount handling: new / idempotent reuse / stale recovery / collision.
+    // 13. Mount handling: new / idempotent reuse / stale recovery / collision.
     let workspace_str = workspace.to_string_lossy();
     let table = platform::read_mount_table().unwrap_or_default();
     let source_in_table = mount_table::find_mount_source(&table, &mount_point).map(str::to_string);
@@ -824,7 +1131,7 @@ pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
             Some(found_source) => {
                 // Healthy mount, source differs — hash collision.
                 let hash = &name[name.len() - 8..];
-                eprintln!("{}", collision_error(&workspace, found_source, hash));
+                run_log::error(&collision_error(&workspace, found_source, hash));
                 return exit_codes::RUNTIME_ERROR;
             }
             None => {
@@ -832,7 +1139,7 @@ pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
                 let tilde_mp = tilde_path(&mount_point, home);
                 progress::step(&format!("Mounting workspace to {tilde_mp}..."));
                 if let Err(e) = do_mount(&workspace, &mount_point) {
-                    eprintln!("{e}");
+                    run_log::error(&e);
                     return exit_codes::RUNTIME_ERROR;
                 }
                 true
@@ -843,7 +1150,7 @@ pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
         if source_in_table.is_some() {
             // In mount table but inaccessible — zombie FUSE, unmount first.
             if let Err(e) = do_unmount(&mount_point) {
-                eprintln!("Failed to unmount stale mount: {e}");
+                run_log::error(&format!("Failed to unmount stale mount: {e}"));
                 return exit_codes::RUNTIME_ERROR;
             }
         }
@@ -851,43 +1158,83 @@ pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
         progress::step(&format!("Mounting workspace to {tilde_mp}..."));
         // Create dir and mount (create_dir_all is a no-op if dir already exists).
         if let Err(e) = do_mount(&workspace, &mount_point) {
-            eprintln!("{e}");
+            run_log::error(&e);
             return exit_codes::RUNTIME_ERROR;
         }
         true
     };
 
-    // 9.5. Network mode enforcement: if an existing container was started with a different
-    // dcx.network-mode, remove it so devcontainer up creates a fresh container with the
-    // requested mode. Handles containers that survived dcx down for any reason.
+    // 14. Network mode enforcement: if an existing container was started with a different
+    // dcx.network-mode (or a different phased policy), remove it so devcontainer up creates
+    // a fresh container with the requested mode. Handles containers that survived dcx down
+    // for any reason.
     let requested_network =
         std::env::var("DCX_NETWORK_MODE").unwrap_or_else(|_| "minimal".to_string());
+    let requested_policy = network_policy
+        .is_phased()
+        .then(|| network_policy.to_label());
     let stale_containers: Vec<String> = docker::query_container_any(&mount_point)
         .into_iter()
-        .filter(|id| docker::read_network_mode(id).as_deref() != Some(requested_network.as_str()))
+        .filter(|id| {
+            docker::read_network_mode(id).as_deref() != Some(requested_network.as_str())
+                || docker::read_label(id, network_mode::POLICY_LABEL) != requested_policy
+        })
         .collect();
     if !stale_containers.is_empty() {
         progress::step("Recreating container for new network mode...");
-        if let Err(e) = docker::stop_container(&mount_point) {
-            eprintln!("{e}");
+        if let Err(e) = remove_containers(&mount_point, &stale_containers) {
+            run_log::error(&e);
             if mounted_fresh {
-                rollback(&mount_point);
+                rollback(home, &mount_point);
             }
             return exit_codes::RUNTIME_ERROR;
         }
-        for id in &stale_containers {
-            if let Err(e) = docker::remove_container(id) {
-                eprintln!("{e}");
+    }
+
+    // 15. Configuration drift: if devcontainer.json, its Dockerfile, dcx_config.yaml or the
+    // colima.yaml mounts changed since the container was created (dcx.config-hash label),
+    // offer to recreate it. Declining keeps the existing container.
+    let config_fingerprint = drift::fingerprint(
+        home,
+        devcontainer_config
+            .clone()
+            .or_else(|| find_devcontainer_config(&workspace))
+            .as_deref(),
+    );
+    let existing = docker::query_container_any(&mount_point);
+    let mut changed: Vec<String> = Vec::new();
+    for id in &existing {
+        let label = docker::read_label(id, drift::CONFIG_HASH_LABEL);
+        for input in drift::drifted_inputs(label.as_deref(), &config_fingerprint) {
+            if !changed.contains(&input) {
+                changed.push(input);
+            }
+        }
+    }
+    if !changed.is_empty() {
+        if final_yes || confirm_recreate(&changed) {
+            progress::step("Recreating container for changed configuration...");
+            if let Err(e) = remove_containers(&mount_point, &existing) {
+                run_log::error(&e);
                 if mounted_fresh {
-                    rollback(&mount_point);
+                    rollback(home, &mount_point);
                 }
                 return exit_codes::RUNTIME_ERROR;
             }
+            state::record_event(
+                home,
+                &name,
+                &format!("up: recreated container ({} changed)", changed.join(", ")),
+            );
+        } else {
+            run_log::error(
+                "Keeping the existing container. Run `dcx up --yes` to recreate it later.",
+            );
         }
     }
 
-    // 12. Non-owned directory warning — prompt unless --yes (or up.yes from config).
-    // (After mount exists, per spec step 12.)
+    // 16. Non-owned directory warning — prompt unless --yes (or up.yes from config).
+    // (After the mount exists.)
     if !final_yes {
         #[cfg(unix)]
         if let (Some(fuid), Some(cuid)) = (file_uid(&workspace), current_uid())
@@ -895,18 +1242,18 @@ pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
             && !confirm_non_owned(&workspace, fuid, cuid)
         {
             if mounted_fresh {
-                rollback(&mount_point);
+                rollback(home, &mount_point);
             }
             return exit_codes::USER_ABORTED;
         }
     }
 
-    // 13. Delegate to `devcontainer up` with rewritten workspace path.
+    // 17. Delegate to `devcontainer up` with rewritten workspace path.
     // Check the interrupted flag before starting devcontainer: if SIGINT arrived
     // in the window between do_mount returning and here, roll back and exit cleanly.
     if interrupted.load(Ordering::Relaxed) {
         if mounted_fresh {
-            rollback(&mount_point);
+            rollback(home, &mount_point);
         }
         return exit_codes::RUNTIME_ERROR;
     }
@@ -963,9 +1310,10 @@ pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
                                     json_escape(&expanded.to_string_lossy()),
                                 ));
                             }
-                            Err(e) => {
-                                eprintln!("Warning: Could not stage {}: {e}", expanded.display())
-                            }
+                            Err(e) => run_log::warn(&format!(
+                                "Warning: Could not stage {}: {e}",
+                                expanded.display()
+                            )),
                         }
                     } else {
                         // For .claude directories, mount at container user's home instead of host path
@@ -1004,17 +1352,17 @@ pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
             let file_path = colima::expand_tilde(&file_mount.path, home);
 
             if !file_path.exists() {
-                eprintln!(
+                run_log::warn(&format!(
                     "Warning: --file {} does not exist, skipping.",
                     file_path.display()
-                );
+                ));
                 continue;
             }
             if !file_path.is_file() {
-                eprintln!(
+                run_log::warn(&format!(
                     "Warning: --file {} is not a file, skipping.",
                     file_path.display()
-                );
+                ));
                 continue;
             }
             if mount_target_in_base(&file_path, base_json, home) {
@@ -1029,10 +1377,10 @@ pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
             let file_name = match file_path.file_name() {
                 Some(name) => name.to_string_lossy().to_string(),
                 None => {
-                    eprintln!(
+                    run_log::warn(&format!(
                         "Warning: Could not extract filename from {}",
                         file_path.display()
-                    );
+                    ));
                     continue;
                 }
             };
@@ -1041,10 +1389,10 @@ pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
             if let Err(e) = std::fs::remove_file(&staged)
                 && e.kind() != std::io::ErrorKind::NotFound
             {
-                eprintln!(
+                run_log::warn(&format!(
                     "Warning: Could not remove existing {}: {e}",
                     staged.display()
-                );
+                ));
             }
 
             // Choose staging strategy based on sync flag
@@ -1059,10 +1407,10 @@ pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
                 match std::fs::hard_link(&file_path, &staged) {
                     Ok(()) => Ok((staged.clone(), true)),
                     Err(e) if e.raw_os_error() == Some(18) => {
-                        eprintln!(
+                        run_log::warn(&format!(
                             "Warning: {} is on a different filesystem; staging as readonly copy.",
                             file_path.display()
-                        );
+                        ));
                         match std::fs::copy(&file_path, &staged) {
                             Ok(_) => Ok((staged.clone(), false)),
                             Err(e) => Err(format!("Failed to copy {}: {e}", file_path.display())),
@@ -1079,6 +1427,10 @@ pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
                         sync_pairs.push(crate::sync::SyncPair {
                             source: file_path.clone(),
                             staging: staged_path.clone(),
+                            merge: file_mount
+                                .merge
+                                .as_deref()
+                                .and_then(|m| crate::sync::MergeFormat::parse(m).ok()),
                         });
                     }
 
@@ -1106,24 +1458,27 @@ pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
                         json_escape(&mount_target.to_string_lossy()),
                     ));
                 }
-                Err(e) => eprintln!("Warning: Could not stage {}: {e}", file_path.display()),
+                Err(e) => run_log::warn(&format!(
+                    "Warning: Could not stage {}: {e}",
+                    file_path.display()
+                )),
             }
         }
 
         // Process --file CLI flags (these are always sync: false)
         for file_path in &extra_files {
             if !file_path.exists() {
-                eprintln!(
+                run_log::warn(&format!(
                     "Warning: --file {} does not exist, skipping.",
                     file_path.display()
-                );
+                ));
                 continue;
             }
             if !file_path.is_file() {
-                eprintln!(
+                run_log::warn(&format!(
                     "Warning: --file {} is not a file, skipping.",
                     file_path.display()
-                );
+                ));
                 continue;
             }
             if mount_target_in_base(file_path, base_json, home) {
@@ -1142,7 +1497,10 @@ pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
                         json_escape(&file_path.to_string_lossy()),
                     ));
                 }
-                Err(e) => eprintln!("Warning: Could not stage {}: {e}", file_path.display()),
+                Err(e) => run_log::warn(&format!(
+                    "Warning: Could not stage {}: {e}",
+                    file_path.display()
+                )),
             }
         }
 
@@ -1162,28 +1520,43 @@ pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
                     &extra_env,
                 ),
                 Some(Err(e)) => {
-                    eprintln!(
+                    run_log::warn(&format!(
                         "Warning: Could not read base config at {}, falling back to standalone mode: {e}",
                         base_config_path.as_ref().unwrap().display()
-                    );
+                    ));
                     generate_override_config(&mount_point, &workspace)
                 }
                 None => generate_override_config(&mount_point, &workspace),
             };
+            // Label the container with its config fingerprint (drift detection), and phased
+            // containers with their policy, so status and stale-container checks can see them.
+            let mut labels = vec![
+                "--label".to_string(),
+                format!(
+                    "{}={}",
+                    drift::CONFIG_HASH_LABEL,
+                    config_fingerprint.to_label()
+                ),
+            ];
+            if let Some(label) = requested_policy.as_deref() {
+                labels.push("--label".to_string());
+                labels.push(format!("{}={label}", network_mode::POLICY_LABEL));
+            }
+            let json_content = inject_array_entries(&json_content, "runArgs", &labels);
 
             if let Err(e) = std::fs::write(temp_file.path(), &json_content) {
-                eprintln!("Failed to write override config: {e}");
+                run_log::error(&format!("Failed to write override config: {e}"));
                 if mounted_fresh {
-                    rollback(&mount_point);
+                    rollback(home, &mount_point);
                 }
                 return exit_codes::RUNTIME_ERROR;
             }
             Some(temp_file)
         }
         Err(e) => {
-            eprintln!("Failed to create temp file: {e}");
+            run_log::error(&format!("Failed to create temp file: {e}"));
             if mounted_fresh {
-                rollback(&mount_point);
+                rollback(home, &mount_point);
             }
             return exit_codes::RUNTIME_ERROR;
         }
@@ -1209,78 +1582,149 @@ pub fn run_up(home: &Path, opts: UpOptions) -> i32 {
         dc_args.push("--build-no-cache");
     }
 
-    // Spawn sync daemon if there are synced files to keep in sync
+    // Register synced files with the dcx daemon (started if needed). Failure is a warning:
+    // the container works without syncing. A sync daemon left by an older dcx is stopped
+    // first so the pairs are not synced twice.
     if !sync_pairs.is_empty() {
-        use std::os::unix::process::CommandExt;
-
-        let exe = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("dcx"));
-        let pid_file = staging_dir(&mount_point).join(".sync-daemon.pid");
-
-        // Skip if a daemon is already running for this workspace
-        let daemon_running = std::fs::read_to_string(&pid_file)
-            .ok()
-            .and_then(|s| s.trim().parse::<u32>().ok())
-            .map(|pid| {
-                std::process::Command::new("kill")
-                    .arg("-0")
-                    .arg(pid.to_string())
-                    .status()
-                    .map(|s| s.success())
-                    .unwrap_or(false)
-            })
-            .unwrap_or(false);
-
-        if !daemon_running {
-            let mut daemon_cmd = std::process::Command::new(&exe);
-            daemon_cmd
-                .arg("_sync-daemon")
-                .arg("--pid-file")
-                .arg(&pid_file);
-            for pair in &sync_pairs {
-                daemon_cmd
-                    .arg("--source")
-                    .arg(&pair.source)
-                    .arg("--staging")
-                    .arg(&pair.staging);
-            }
-            daemon_cmd
-                .stdout(std::process::Stdio::null())
-                .stderr(std::process::Stdio::null())
-                .stdin(std::process::Stdio::null())
-                .process_group(0); // Detach from parent process group so daemon survives when parent exits
-            match daemon_cmd.spawn() {
-                Ok(child) => drop(child), // orphan: adopted by init when dcx up exits
-                Err(e) => eprintln!("Warning: Could not start sync daemon: {e}"),
-            }
+        daemon::stop_legacy_sync(&mount_point);
+        state::save_sync_pairs(home, &name, &sync_pairs);
+        if let Err(e) = daemon::register(home, &name) {
+            run_log::warn(&format!("Warning: Could not start file sync: {e}"));
         }
     }
 
-    let code = cmd::run_stream("devcontainer", &dc_args).unwrap_or(exit_codes::PREREQ_NOT_FOUND);
+    let code =
+        run_log::run_stream("devcontainer", &dc_args).unwrap_or(exit_codes::PREREQ_NOT_FOUND);
     // Drop override_config to clean up temp file before continuing
     drop(override_config);
 
-    // 14. Roll back on failure (if we mounted this run) and return RUNTIME_ERROR.
+    // 18. Roll back on failure (if we mounted this run) and return RUNTIME_ERROR.
     // This handles both normal devcontainer failures and Ctrl+C (SIGINT kills the child,
     // returning non-zero, which lands here for rollback).
     // The spec requires exit code 1 (not the child's exit code) when dcx up fails
     // after rollback, because the failure is a dcx error, not a pass-through.
     if code != 0 {
         if mounted_fresh {
-            rollback(&mount_point);
+            rollback(home, &mount_point);
         }
+        state::record_event(
+            home,
+            &name,
+            &format!("up: devcontainer up failed (exit {code})"),
+        );
         return exit_codes::RUNTIME_ERROR;
     }
 
-    // 12. Tag the base image for later cleanup by `dcx clean --purge`.
+    // 19. Phased network policy: lifecycle commands ran under the create-phase mode;
+    // tighten (or loosen) to the run-phase mode now. Fail closed: if the firewall cannot
+    // be switched, stop the container rather than leave it with the create-phase policy.
+    state::clear_transitions(home, &name);
+    if network_policy.is_phased() {
+        let record = |phase: NetworkPhase| {
+            let t = state::PhaseTransition {
+                timestamp: state::unix_now(),
+                phase,
+                mode: network_policy.mode_for(phase),
+            };
+            if let Err(e) = state::record_transition(home, &name, &t) {
+                run_log::warn(&format!("Warning: Could not record network phase: {e}"));
+            }
+            state::record_event(home, &name, &format!("network: {phase} phase, {}", t.mode));
+        };
+        record(NetworkPhase::Create);
+        if network_policy.run != network_policy.create {
+            progress::step(&format!(
+                "Applying run-phase network mode: {}...",
+                network_policy.run
+            ));
+            let applied = match docker::query_container(&mount_point) {
+                Some(id) => docker::apply_network_mode(&id, network_policy.run),
+                None => Err("No running container found after devcontainer up.".to_string()),
+            };
+            if let Err(e) = applied {
+                run_log::error(&e);
+                run_log::error(
+                    "Stopping container: run-phase network policy could not be applied.",
+                );
+                if let Err(e) = docker::stop_container(&mount_point) {
+                    run_log::error(&e);
+                }
+                state::record_event(
+                    home,
+                    &name,
+                    "up: container stopped, run-phase network mode could not be applied",
+                );
+                return exit_codes::RUNTIME_ERROR;
+            }
+        }
+        record(NetworkPhase::Run);
+    }
+
+    // 20. The run-phase mode is now in force for this container run; `dcx start` and
+    // `dcx exec` re-apply it when the container was restarted since.
+    if let Some(id) = docker::query_container(&mount_point)
+        && let Some(started) = docker::container_started_at(&id)
+    {
+        state::record_network_applied(home, &name, started);
+    }
+
+    // 21. Tag the base image for later cleanup by `dcx clean --purge`.
     // Non-fatal: if tagging fails (e.g. no "image" field in devcontainer.json),
     // purge will simply skip base image removal for this workspace.
     if let Some(base_image) =
         docker::get_base_image_name(&workspace, devcontainer_config.as_deref())
         && let Err(e) = docker::tag_base_image(&base_image, &name)
     {
-        eprintln!("Warning: Could not tag base image: {e}");
+        run_log::warn(&format!("Warning: Could not tag base image: {e}"));
     }
 
+    // 22. Remember the options so `dcx shell` can bring the workspace back up the same way.
+    let remembered = RememberedUpOptions {
+        config_dir: dcx_config_dir.clone(),
+        extra_files: extra_files
+            .iter()
+            .map(|f| f.canonicalize().unwrap_or_else(|_| f.clone()))
+            .collect(),
+        network: cli_network.map(|n| n.to_string()),
+        yes,
+        // Like the deadline below, a running workspace keeps its TTL without --ttl.
+        ttl: ttl.clone().or_else(|| {
+            (!mounted_fresh)
+                .then(|| load_remembered_options(home, &name).and_then(|r| r.ttl))
+                .flatten()
+        }),
+    };
+    if let Err(e) = save_remembered_options(home, &name, &remembered) {
+        run_log::warn(&format!("Warning: Could not remember up options: {e}"));
+    }
+
+    state::record_event(home, &name, "up: done");
+    state::touch_active(home, &name);
+
+    // 23. TTL / idle timeout: record the workspace's lifetime and make sure a supervisor
+    // watches it. Without --ttl, the deadline of an already-running workspace is kept
+    // (a fresh mount never inherits one left behind by `dcx clean`).
+    let lifetime = lifetime::Lifetime {
+        deadline: ttl_secs.map(|t| state::unix_now() + t).or_else(|| {
+            (!mounted_fresh)
+                .then(|| lifetime::load(home, &name).and_then(|l| l.deadline))
+                .flatten()
+        }),
+        idle_timeout,
+    };
+    if lifetime.is_empty() {
+        lifetime::clear(home, &name);
+    } else {
+        match lifetime::save(home, &name, &lifetime) {
+            Ok(()) => lifetime::spawn_supervisor(home, &mount_point, &workspace),
+            Err(e) => run_log::warn(&format!(
+                "Warning: Could not record workspace lifetime: {e}"
+            )),
+        }
+    }
+
+    // 24. Apply the gc: policy (rate-limited, failures are warnings).
+    gc::auto_gc(home, &cfg.gc);
     progress::step("Done.");
     exit_codes::SUCCESS
 }
@@ -1406,6 +1850,30 @@ mod tests {
         assert!(!out.contains("--config"), "got: {out}");
     }
 
+    // --- up_plan ---
+
+    #[test]
+    fn up_plan_uses_absolute_paths_and_full_command() {
+        let ws = Path::new("/home/user/myproject");
+        let mp = Path::new("/home/user/.colima-mounts/dcx-myproject-a1b2c3d4");
+        let plan = up_plan(ws, mp, Some(Path::new("/cfg/devcontainer.json")), true);
+        assert_eq!(plan.workspace, "/home/user/myproject");
+        assert_eq!(plan.mount_name, "dcx-myproject-a1b2c3d4");
+        assert_eq!(plan.mount_point, mp.to_str().unwrap());
+        assert_eq!(
+            plan.command,
+            vec![
+                "devcontainer",
+                "up",
+                "--workspace-folder",
+                "/home/user/.colima-mounts/dcx-myproject-a1b2c3d4",
+                "--config",
+                "/cfg/devcontainer.json",
+                "--build-no-cache",
+            ]
+        );
+    }
+
     // --- current_username ---
 
     #[test]
@@ -1572,6 +2040,162 @@ mod tests {
         assert!(result.contains("\"existing\",\"new\""), "got: {result}");
     }
 
+    // --- inject_array_entries ---
+
+    #[test]
+    fn inject_array_entries_appends_to_existing_run_args() {
+        let json = r#"{"runArgs": ["--cap-add=NET_ADMIN"]}"#;
+        let entries = vec!["--label".to_string(), "a=b".to_string()];
+        let result = inject_array_entries(json, "runArgs", &entries);
+        assert!(result.contains(r#"["--cap-add=NET_ADMIN","--label","a=b"]"#));
+    }
+
+    #[test]
+    fn inject_array_entries_creates_run_args_when_absent() {
+        let json = r#"{"image": "ubuntu"}"#;
+        let entries = vec!["--label".to_string()];
+        let result = inject_array_entries(json, "runArgs", &entries);
+        assert!(result.contains(r#""runArgs": ["--label"]"#));
+    }
+
+    // --- remembered options ---
+
+    #[test]
+    fn remembered_options_round_trip() {
+        let home = tempfile::tempdir().unwrap();
+        let opts = RememberedUpOptions {
+            config_dir: Some(PathBuf::from("/proj/.devcontainer/full")),
+            extra_files: vec![PathBuf::from("/home/u/.npmrc")],
+            network: Some("open".to_string()),
+            yes: true,
+            ttl: Some("8h".to_string()),
+        };
+        save_remembered_options(home.path(), "dcx-proj-abcd1234", &opts).unwrap();
+        assert_eq!(
+            load_remembered_options(home.path(), "dcx-proj-abcd1234"),
+            Some(opts)
+        );
+    }
+
+    #[test]
+    fn remembered_options_into_up_options_parses_network() {
+        let opts = RememberedUpOptions {
+            network: Some("restricted".to_string()),
+            ..RememberedUpOptions::default()
+        };
+        let up = opts.into_up_options(PathBuf::from("/proj"));
+        assert_eq!(up.cli_network, Some(NetworkMode::Restricted));
+        assert_eq!(up.workspace_folder, Some(PathBuf::from("/proj")));
+        assert!(!up.dry_run);
+    }
+
+    #[test]
+    fn remembered_options_without_ttl_still_load() {
+        let home = tempfile::tempdir().unwrap();
+        let path = remembered_options_path(home.path(), "dcx-proj-abcd1234");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &path,
+            r#"{"config_dir":null,"extra_files":[],"network":null,"yes":false}"#,
+        )
+        .unwrap();
+        let opts = load_remembered_options(home.path(), "dcx-proj-abcd1234").unwrap();
+        assert_eq!(opts.ttl, None);
+        assert_eq!(opts.into_up_options(PathBuf::from("/proj")).ttl, None);
+    }
+
+    #[test]
+    fn load_remembered_options_missing_is_none() {
+        let home = tempfile::tempdir().unwrap();
+        assert_eq!(load_remembered_options(home.path(), "dcx-x-00000000"), None);
+    }
+
+    // --- resolve_network_policy ---
+
+    #[test]
+    fn resolve_network_policy_defaults_to_uniform_minimal() {
+        let (policy, warnings) = resolve_network_policy(None, None, None);
+        assert_eq!(policy, NetworkPolicy::uniform(NetworkMode::Minimal));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn resolve_network_policy_cli_is_uniform() {
+        let (policy, _) = resolve_network_policy(None, None, Some(NetworkMode::Open));
+        assert_eq!(policy, NetworkPolicy::uniform(NetworkMode::Open));
+    }
+
+    #[test]
+    fn resolve_network_policy_yaml_phases_fill_default_for_omitted() {
+        let phases = dcx_config::NetworkPhases {
+            build: None,
+            create: None,
+            run: Some("restricted".to_string()),
+        };
+        let (policy, warnings) = resolve_network_policy(None, Some(&phases), None);
+        assert_eq!(
+            policy,
+            NetworkPolicy {
+                build: NetworkMode::Open,
+                create: NetworkMode::Minimal,
+                run: NetworkMode::Restricted,
+            }
+        );
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn check_build_network_accepts_only_open() {
+        let phases = |build: Option<&str>| dcx_config::NetworkPhases {
+            build: build.map(str::to_string),
+            create: None,
+            run: Some("restricted".to_string()),
+        };
+        assert!(check_build_network(None).is_ok());
+        assert!(check_build_network(Some(&phases(None))).is_ok());
+        assert!(check_build_network(Some(&phases(Some("open")))).is_ok());
+        assert!(check_build_network(Some(&phases(Some("OPEN")))).is_ok());
+        for mode in ["restricted", "minimal", "host", "none"] {
+            let err = check_build_network(Some(&phases(Some(mode)))).unwrap_err();
+            assert!(err.contains("up.network.build"));
+            assert!(err.contains(mode));
+        }
+    }
+
+    #[test]
+    fn resolve_network_policy_yaml_phases_override_cli_with_warning() {
+        let phases = dcx_config::NetworkPhases {
+            build: Some("open".to_string()),
+            create: Some("open".to_string()),
+            run: Some("restricted".to_string()),
+        };
+        let (policy, warnings) =
+            resolve_network_policy(None, Some(&phases), Some(NetworkMode::Open));
+        assert_eq!(policy.run, NetworkMode::Restricted);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("overrides --network open"));
+    }
+
+    #[test]
+    fn resolve_network_policy_invalid_phase_warns_and_uses_default() {
+        let phases = dcx_config::NetworkPhases {
+            build: None,
+            create: None,
+            run: Some("bogus".to_string()),
+        };
+        let (policy, warnings) = resolve_network_policy(None, Some(&phases), None);
+        assert_eq!(policy.run, NetworkMode::Minimal);
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn resolve_network_policy_same_yaml_and_cli_no_warning() {
+        let (policy, warnings) =
+            resolve_network_policy(Some("open"), None, Some(NetworkMode::Open));
+        assert_eq!(policy, NetworkPolicy::uniform(NetworkMode::Open));
+        assert!(warnings.is_empty());
+    }
+
     // --- inject_env_vars ---
 
     #[test]
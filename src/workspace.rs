@@ -38,6 +38,31 @@ pub fn find_devcontainer_config(workspace: &Path) -> Option<PathBuf> {
     None
 }
 
+/// Find the nearest ancestor of `start` (including `start` itself) that is a workspace.
+///
+/// `is_workspace` decides whether a directory qualifies (e.g. has an active dcx mount).
+/// Returns `None` if no ancestor qualifies.
+pub fn find_enclosing_workspace(
+    start: &Path,
+    is_workspace: impl Fn(&Path) -> bool,
+) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| is_workspace(dir))
+        .map(Path::to_path_buf)
+}
+
+/// Translate `cwd` on the host into the matching path inside the container.
+///
+/// If `cwd` is inside `host_workspace`, its relative part is appended to
+/// `container_workspace`; otherwise the container workspace root is returned.
+pub fn container_workdir(container_workspace: &Path, host_workspace: &Path, cwd: &Path) -> PathBuf {
+    match cwd.strip_prefix(host_workspace) {
+        Ok(rel) => container_workspace.join(rel),
+        Err(_) => container_workspace.to_path_buf(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -118,4 +143,77 @@ mod tests {
             result.display()
         );
     }
+
+    // --- find_enclosing_workspace ---
+
+    #[test]
+    fn find_enclosing_workspace_returns_start_when_it_matches() {
+        let start = Path::new("/home/user/proj");
+        let found = find_enclosing_workspace(start, |d| d == Path::new("/home/user/proj"));
+        assert_eq!(found, Some(PathBuf::from("/home/user/proj")));
+    }
+
+    #[test]
+    fn find_enclosing_workspace_walks_up_to_ancestor() {
+        let start = Path::new("/home/user/proj/crates/foo");
+        let found = find_enclosing_workspace(start, |d| d == Path::new("/home/user/proj"));
+        assert_eq!(found, Some(PathBuf::from("/home/user/proj")));
+    }
+
+    #[test]
+    fn find_enclosing_workspace_prefers_nearest() {
+        let start = Path::new("/home/user/proj/sub");
+        let found = find_enclosing_workspace(start, |d| {
+            d == Path::new("/home/user/proj") || d == Path::new("/home/user/proj/sub")
+        });
+        assert_eq!(found, Some(PathBuf::from("/home/user/proj/sub")));
+    }
+
+    #[test]
+    fn find_enclosing_workspace_none_when_nothing_matches() {
+        let found = find_enclosing_workspace(Path::new("/home/user/proj"), |_| false);
+        assert_eq!(found, None);
+    }
+
+    // --- container_workdir ---
+
+    #[test]
+    fn container_workdir_maps_subdirectory() {
+        let wd = container_workdir(
+            Path::new("/workspace"),
+            Path::new("/home/user/proj"),
+            Path::new("/home/user/proj/crates/foo"),
+        );
+        assert_eq!(wd, PathBuf::from("/workspace/crates/foo"));
+    }
+
+    #[test]
+    fn container_workdir_root_maps_to_container_root() {
+        let wd = container_workdir(
+            Path::new("/workspace"),
+            Path::new("/home/user/proj"),
+            Path::new("/home/user/proj"),
+        );
+        assert_eq!(wd, PathBuf::from("/workspace"));
+    }
+
+    #[test]
+    fn container_workdir_outside_workspace_uses_root() {
+        let wd = container_workdir(
+            Path::new("/home/user/proj"),
+            Path::new("/home/user/proj"),
+            Path::new("/tmp"),
+        );
+        assert_eq!(wd, PathBuf::from("/home/user/proj"));
+    }
+
+    #[test]
+    fn container_workdir_does_not_match_sibling_prefix() {
+        let wd = container_workdir(
+            Path::new("/workspace"),
+            Path::new("/home/user/proj"),
+            Path::new("/home/user/project2"),
+        );
+        assert_eq!(wd, PathBuf::from("/workspace"));
+    }
 }
@@ -179,6 +179,34 @@ fn up_no_cache_flag_is_accepted() {
     }
 }
 
+#[test]
+fn up_invalid_ttl_exits_usage_error() {
+    // Exit 2 for the bad --ttl, or 1 if Docker is unavailable (checked first).
+    use assert_fs::TempDir;
+    use assert_fs::prelude::*;
+    let workspace = TempDir::new().unwrap();
+    workspace
+        .child(".devcontainer/devcontainer.json")
+        .touch()
+        .unwrap();
+    let out = dcx()
+        .args([
+            "up",
+            "--ttl",
+            "soon",
+            "--dry-run",
+            "--workspace-folder",
+            workspace.path().to_str().unwrap(),
+        ])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    match out.status.code() {
+        Some(2) => assert!(stderr.contains("--ttl"), "got: {stderr}"),
+        code => assert_eq!(code, Some(1), "stderr: {stderr}"),
+    }
+}
+
 #[test]
 fn up_dry_run_without_devcontainer_config_exits_nonzero() {
     // --dry-run still validates before printing the plan.
@@ -395,6 +423,56 @@ fn doctor_always_prints_checking_prerequisites() {
         .stdout(predicate::str::contains("Checking prerequisites..."));
 }
 
+#[test]
+fn doctor_output_json_prints_versioned_result_document() {
+    let out = dcx().args(["--output", "json", "doctor"]).output().unwrap();
+    let doc: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(doc["schema_version"], 1);
+    assert_eq!(doc["type"], "result");
+    assert_eq!(doc["command"], "doctor");
+    let checks = doc["data"]["checks"].as_array().unwrap();
+    assert!(!checks.is_empty());
+    assert!(checks[0]["name"].is_string());
+    assert!(checks[0]["passed"].is_boolean());
+}
+
+#[test]
+fn doctor_output_jsonl_streams_progress_then_result() {
+    let out = dcx()
+        .args(["doctor", "--output", "jsonl"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let lines: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(lines.first().unwrap()["type"], "progress");
+    assert_eq!(
+        lines.first().unwrap()["message"],
+        "Running prerequisite checks..."
+    );
+    assert_eq!(lines.last().unwrap()["type"], "result");
+}
+
+#[test]
+fn output_json_rejected_for_unsupported_command() {
+    dcx()
+        .args(["--output", "json", "jobs"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("does not support --output"));
+}
+
+#[test]
+fn output_json_requires_dry_run_for_clean() {
+    dcx()
+        .args(["clean", "--output", "json"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("clean --dry-run"));
+}
+
 // --- dcx status ---
 
 #[test]
@@ -410,6 +488,37 @@ fn status_output_is_table_or_no_workspaces() {
     );
 }
 
+#[test]
+fn status_resources_is_accepted() {
+    let out = dcx().args(["status", "--resources"]).output().unwrap();
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        !stderr.contains("unexpected argument"),
+        "--resources should be a status flag: {stderr}"
+    );
+}
+
+// --- dcx inspect ---
+
+#[test]
+fn inspect_missing_workspace_fails() {
+    // Exit 2 for the missing workspace, or 1 when Docker is not available.
+    let out = dcx()
+        .args([
+            "inspect",
+            "--workspace-folder",
+            "/nonexistent/dcx-inspect-test",
+        ])
+        .output()
+        .unwrap();
+    assert!(
+        matches!(out.status.code(), Some(1) | Some(2)),
+        "unexpected exit: {:?}",
+        out.status
+    );
+    assert!(out.stdout.is_empty());
+}
+
 // --- dcx exec ---
 
 #[test]
@@ -490,6 +599,65 @@ fn exec_with_nonexistent_config_exits_nonzero() {
         .failure();
 }
 
+#[test]
+fn exec_invalid_env_entry_is_usage_error() {
+    dcx()
+        .args(["exec", "-e", "1BAD=x", "--", "true"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Invalid environment variable"));
+}
+
+#[test]
+fn exec_missing_env_file_is_usage_error() {
+    dcx()
+        .args([
+            "exec",
+            "--env-file",
+            "/nonexistent/__dcx_test_vars__.env",
+            "--",
+            "true",
+        ])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Env file not found"));
+}
+
+#[test]
+fn exec_all_conflicts_with_workspace_folder() {
+    dcx()
+        .args(["exec", "--all", "--workspace-folder", ".", "--", "true"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn exec_parallel_requires_all() {
+    dcx()
+        .args(["exec", "--parallel", "2", "--", "true"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn exec_all_with_empty_relay_reports_no_workspaces() {
+    // With Docker available and no mounts: "No active workspaces.", exit 0.
+    // Without Docker: exit 1 with the Docker error.
+    use assert_fs::TempDir;
+    let home = TempDir::new().unwrap();
+    let out = dcx()
+        .env("HOME", home.path())
+        .args(["exec", "--all", "--", "true"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stdout.contains("No active workspaces.") || stderr.contains("Docker is not available"),
+        "got stdout: {stdout}, stderr: {stderr}"
+    );
+}
+
 // --- dcx down ---
 
 #[test]
@@ -532,6 +700,180 @@ fn down_valid_workspace_no_mount_prints_nothing_to_do_or_docker_error() {
     }
 }
 
+#[test]
+fn down_all_conflicts_with_workspace_folder() {
+    dcx()
+        .args(["down", "--all", "--workspace-folder", "/tmp"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn down_named_missing_workspace_exits_usage_error() {
+    // Named workspaces are resolved before Docker is checked, so this is exit 2 either way.
+    dcx()
+        .args(["down", "/tmp", "/nonexistent/__dcx_test_path__"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("does not exist"));
+}
+
+#[test]
+fn down_all_empty_relay_prints_no_active_workspaces() {
+    use assert_fs::TempDir;
+    let home = TempDir::new().unwrap();
+    let out = dcx()
+        .env("HOME", home.path())
+        .args(["down", "--all"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stdout.contains("No active workspaces.") || stderr.contains("Docker is not available"),
+        "got stdout: {stdout}, stderr: {stderr}"
+    );
+}
+
+// --- dcx stop / dcx start ---
+
+#[test]
+fn stop_missing_workspace_exits_nonzero() {
+    dcx()
+        .args([
+            "stop",
+            "--workspace-folder",
+            "/nonexistent/__dcx_test_path__",
+        ])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn stop_without_container_prints_nothing_to_do_or_docker_error() {
+    use assert_fs::TempDir;
+    let home = TempDir::new().unwrap();
+    let out = dcx()
+        .env("HOME", home.path())
+        .args(["stop", "--workspace-folder", "/tmp"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    if out.status.success() {
+        assert!(stdout.contains("Nothing to do."), "got: {stdout}");
+    } else {
+        assert!(stderr.contains("Docker"), "got: {stderr}");
+    }
+}
+
+#[test]
+fn start_without_container_fails() {
+    // Exit 1 either way: no container to start, or Docker unavailable.
+    use assert_fs::TempDir;
+    let home = TempDir::new().unwrap();
+    let out = dcx()
+        .env("HOME", home.path())
+        .args(["start", "--workspace-folder", "/tmp"])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("dcx up") || stderr.contains("Docker"),
+        "got: {stderr}"
+    );
+}
+
+// --- dcx daemon ---
+
+#[test]
+fn daemon_status_without_daemon_reports_not_running() {
+    use assert_fs::TempDir;
+    let home = TempDir::new().unwrap();
+    dcx()
+        .env("HOME", home.path())
+        .args(["daemon", "status"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("dcx daemon: not running"));
+}
+
+#[test]
+fn daemon_restart_resumes_registered_workspaces() {
+    use assert_fs::TempDir;
+    use std::io::Write;
+    let home = TempDir::new().unwrap();
+    let files = TempDir::new().unwrap();
+    let state = home.path().join(".local/state/dcx");
+    let (source, staging) = (files.path().join("a.json"), files.path().join("b.json"));
+    std::fs::write(&source, "{}").unwrap();
+    std::fs::write(&staging, "{}").unwrap();
+    std::fs::create_dir_all(state.join("dcx-p-abcd1234")).unwrap();
+    std::fs::write(
+        state.join("dcx-p-abcd1234/sync-pairs"),
+        format!("{}\t{}\n", source.display(), staging.display()),
+    )
+    .unwrap();
+    std::fs::write(state.join("daemon-workspaces"), "dcx-p-abcd1234\n").unwrap();
+
+    dcx()
+        .env("HOME", home.path())
+        .args(["daemon", "restart"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 workspace resumed"));
+    dcx()
+        .env("HOME", home.path())
+        .args(["daemon", "status"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("dcx-p-abcd1234"));
+
+    // The resumed workspace is synced.
+    std::fs::write(&source, r#"{"k":1}"#).unwrap();
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+    while std::fs::read_to_string(&staging).unwrap() != r#"{"k":1}"#
+        && std::time::Instant::now() < deadline
+    {
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    let synced = std::fs::read_to_string(&staging).unwrap();
+
+    let mut sock = std::os::unix::net::UnixStream::connect(state.join("daemon.sock")).unwrap();
+    sock.write_all(b"{\"request\":\"shutdown\"}\n").unwrap();
+    assert_eq!(synced, r#"{"k":1}"#);
+}
+
+// --- dcx sync resolve ---
+
+#[test]
+fn sync_resolve_without_conflicts_is_a_no_op() {
+    use assert_fs::TempDir;
+    let home = TempDir::new().unwrap();
+    dcx()
+        .env("HOME", home.path())
+        .args([
+            "sync",
+            "resolve",
+            "--take",
+            "host",
+            "--workspace-folder",
+            "/tmp",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No sync conflicts in"));
+}
+
+#[test]
+fn sync_resolve_rejects_unknown_side() {
+    dcx()
+        .args(["sync", "resolve", "--take", "both"])
+        .assert()
+        .code(2);
+}
+
 // --- dcx clean ---
 
 #[test]
@@ -607,6 +949,108 @@ fn clean_all_yes_with_empty_relay_prints_nothing_to_clean() {
     );
 }
 
+#[test]
+fn clean_invalid_older_than_is_usage_error() {
+    // Filter values are validated before Docker is checked.
+    use assert_fs::TempDir;
+    let home = TempDir::new().unwrap();
+    let out = dcx()
+        .env("HOME", home.path())
+        .args(["clean", "--older-than", "soon"])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--older-than"));
+}
+
+#[test]
+fn clean_filters_conflict_with_workspace_folder() {
+    use assert_fs::TempDir;
+    let home = TempDir::new().unwrap();
+    let out = dcx()
+        .env("HOME", home.path())
+        .args(["clean", "--state", "exited", "--workspace-folder", "/tmp"])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn clean_filtered_dry_run_with_empty_relay_prints_nothing_to_clean() {
+    use assert_fs::TempDir;
+    let home = TempDir::new().unwrap();
+    let out = dcx()
+        .env("HOME", home.path())
+        .args([
+            "clean",
+            "--idle-for",
+            "2d",
+            "--state",
+            "orphaned,stale",
+            "--dry-run",
+        ])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stdout.contains("Nothing to clean.") || stderr.contains("Docker is not available"),
+        "Expected 'Nothing to clean.' or Docker error, got stdout={stdout} stderr={stderr}"
+    );
+}
+
+// --- dcx df ---
+
+#[test]
+fn df_with_empty_relay_reports_no_workspaces() {
+    use assert_fs::TempDir;
+    let home = TempDir::new().unwrap();
+    let out = dcx().env("HOME", home.path()).arg("df").output().unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stdout.contains("No dcx workspaces.") || stderr.contains("Docker is not available"),
+        "got stdout={stdout} stderr={stderr}"
+    );
+}
+
+// --- dcx gc ---
+
+#[test]
+fn gc_without_policy_is_usage_error() {
+    use assert_fs::TempDir;
+    let home = TempDir::new().unwrap();
+    let ws = TempDir::new().unwrap();
+    let out = dcx()
+        .env("HOME", home.path())
+        .args(["gc", "--workspace-folder"])
+        .arg(ws.path())
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("No GC policy configured"));
+}
+
+#[test]
+fn gc_invalid_config_size_is_usage_error() {
+    use assert_fs::TempDir;
+    let home = TempDir::new().unwrap();
+    let ws = TempDir::new().unwrap();
+    std::fs::write(
+        ws.path().join("dcx_config.yaml"),
+        "gc:\n  max_image_size: lots\n",
+    )
+    .unwrap();
+    let out = dcx()
+        .env("HOME", home.path())
+        .args(["gc", "--workspace-folder"])
+        .arg(ws.path())
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("gc.max_image_size"));
+}
+
 // --- Progress output ---
 
 // The progress arrow character (→ U+2192) must appear on stderr when commands
@@ -728,6 +1172,180 @@ fn logs_nonexistent_workspace_exits_nonzero() {
         .failure();
 }
 
+#[test]
+fn logs_daemon_without_log_exits_runtime_error() {
+    use assert_fs::TempDir;
+    let home = TempDir::new().unwrap();
+    let workspace = TempDir::new().unwrap();
+    dcx()
+        .env("HOME", home.path())
+        .args(["logs", "--daemon", "--workspace-folder"])
+        .arg(workspace.path())
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("No sync daemon log"));
+}
+
+#[test]
+fn logs_daemon_conflicts_with_since() {
+    dcx()
+        .args(["logs", "--daemon", "--since", "10m"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn logs_all_conflicts_with_workspace_folder() {
+    dcx()
+        .args(["logs", "--all", "--workspace-folder", "/tmp"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn logs_all_with_empty_relay_reports_no_workspaces() {
+    // With Docker available and no mounts: "No active workspaces.", exit 0.
+    // Without Docker: exit 1 with the Docker error.
+    use assert_fs::TempDir;
+    let home = TempDir::new().unwrap();
+    let out = dcx()
+        .env("HOME", home.path())
+        .args(["logs", "--all", "--events"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stdout.contains("No active workspaces.") || stderr.contains("Docker is not available"),
+        "got stdout: {stdout}, stderr: {stderr}"
+    );
+}
+
+#[test]
+fn logs_up_without_runs_exits_runtime_error() {
+    use assert_fs::TempDir;
+    let home = TempDir::new().unwrap();
+    let workspace = TempDir::new().unwrap();
+    dcx()
+        .env("HOME", home.path())
+        .args(["logs", "--up", "--workspace-folder"])
+        .arg(workspace.path())
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("No `dcx up` logs"));
+}
+
+#[test]
+fn logs_run_requires_up() {
+    dcx().args(["logs", "--run", "1-0000"]).assert().code(2);
+}
+
+// --- dcx shell ---
+
+#[test]
+fn shell_nonexistent_workspace_exits_nonzero() {
+    // exit 1 if Docker is unavailable; exit 2 if Docker is available (USAGE_ERROR).
+    dcx()
+        .args([
+            "shell",
+            "--workspace-folder",
+            "/nonexistent/__dcx_test_path__",
+        ])
+        .assert()
+        .failure();
+}
+
+// --- dcx run / dcx jobs ---
+
+#[test]
+fn jobs_with_empty_state_lists_no_jobs() {
+    use assert_fs::TempDir;
+    let home = TempDir::new().unwrap();
+    dcx()
+        .env("HOME", home.path())
+        .arg("jobs")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No jobs."));
+}
+
+#[test]
+fn jobs_logs_unknown_id_exits_usage_error() {
+    use assert_fs::TempDir;
+    let home = TempDir::new().unwrap();
+    dcx()
+        .env("HOME", home.path())
+        .args(["jobs", "logs", "deadbeef"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("No job found"));
+}
+
+#[test]
+fn jobs_kill_unknown_id_exits_usage_error() {
+    use assert_fs::TempDir;
+    let home = TempDir::new().unwrap();
+    dcx()
+        .env("HOME", home.path())
+        .args(["jobs", "kill", "deadbeef"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn run_detach_without_command_exits_usage_error() {
+    dcx().args(["run", "--detach"]).assert().code(2);
+}
+
+#[test]
+fn run_passes_exec_flags_through() {
+    for detach in [false, true] {
+        let mut args = vec!["run", "-e", "1BAD=x"];
+        if detach {
+            args.push("--detach");
+        }
+        args.extend(["--", "true"]);
+        dcx()
+            .args(&args)
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains("Invalid environment variable"));
+    }
+}
+
+// --- dcx sessions ---
+
+#[test]
+fn sessions_list_with_empty_state_lists_nothing() {
+    use assert_fs::TempDir;
+    let home = TempDir::new().unwrap();
+    dcx()
+        .env("HOME", home.path())
+        .args(["sessions", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No recorded sessions."));
+}
+
+#[test]
+fn sessions_replay_unknown_id_exits_usage_error() {
+    use assert_fs::TempDir;
+    let home = TempDir::new().unwrap();
+    dcx()
+        .env("HOME", home.path())
+        .args(["sessions", "replay", "1700000000-00ab"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn exec_record_conflicts_with_all() {
+    dcx()
+        .args(["exec", "--all", "--record", "--", "true"])
+        .assert()
+        .code(2);
+}
+
 // --- dcx completions ---
 
 #[test]